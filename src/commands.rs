@@ -5,14 +5,30 @@ use crate::{
 };
 
 pub use assignment::*;
+pub use backup::*;
+pub use completions::*;
 pub use projects::*;
 pub use tasks::*;
+pub use theme::*;
 
-use regex::Regex;
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use regex::{Regex, RegexBuilder};
 
 mod assignment;
+mod backup;
+mod completions;
 mod projects;
 mod tasks;
+mod theme;
+
+/// Returns true if an error was caused by the user cancelling an interactive prompt (ie. with
+/// Ctrl-C)
+pub fn is_cancelled(error: &toado::Error) -> bool {
+    matches!(
+        error.downcast_ref::<dialoguer::Error>(),
+        Some(dialoguer::Error::IO(e)) if e.kind() == std::io::ErrorKind::Interrupted
+    )
+}
 
 //
 // Private methods
@@ -68,6 +84,136 @@ where
     }
 }
 
+/// Completes user input of a name with the first existing name that starts with the input
+struct NameCompletion {
+    names: Vec<String>,
+    ignore_case: bool,
+}
+
+impl NameCompletion {
+    /// Creates a completion from the names of all tasks. Names are only read from the database
+    /// if `prompt` is true, ie. the user will be prompted for a name
+    ///
+    /// # Errors
+    ///
+    /// Returns error if selecting task names fails
+    fn tasks(
+        prompt: bool,
+        app: &toado::Server,
+        config: &config::Config,
+    ) -> Result<Self, toado::Error> {
+        Ok(NameCompletion {
+            names: if prompt {
+                app.select_task_names()?
+            } else {
+                vec![]
+            },
+            ignore_case: config.search.ignore_case,
+        })
+    }
+}
+
+impl dialoguer::Completion for NameCompletion {
+    fn get(&self, input: &str) -> Option<String> {
+        if input.is_empty() {
+            return None;
+        }
+
+        self.names
+            .iter()
+            .find(|name| {
+                if self.ignore_case {
+                    name.to_lowercase().starts_with(&input.to_lowercase())
+                } else {
+                    name.starts_with(input)
+                }
+            })
+            .cloned()
+    }
+}
+
+/// Returns the number of rows affected by an update or deletion, or a not found error if no rows
+/// are affected, so an operation that matches nothing is not mistaken for a successful one
+///
+/// # Errors
+///
+/// Returns error if the number of affected rows is zero
+fn matched_rows(affected_rows: u64, items: &str) -> Result<u64, toado::Error> {
+    match affected_rows {
+        0 => Err(toado::ToadoError::NotFound(format!("no {items} matched")).into()),
+        rows => Ok(rows),
+    }
+}
+
+/// Return true if skip is true, otherwise, prompt the user to confirm an action. Defaults to not
+/// confirming
+///
+/// # Errors
+///
+/// Returns error if getting user input fails
+fn skip_or_confirm(skip: bool, confirm: dialoguer::Confirm) -> Result<bool, toado::Error> {
+    if skip {
+        Ok(true)
+    } else {
+        Ok(confirm.default(false).interact()?)
+    }
+}
+
+/// Return Ok if skip is true, otherwise, prompt the user to type the word DELETE to confirm
+/// deleting all items
+///
+/// # Errors
+///
+/// Returns error if getting user input fails, or if the user input does not match
+fn skip_or_confirm_delete_all(skip: bool, items: &str) -> Result<(), toado::Error> {
+    if skip {
+        return Ok(());
+    }
+
+    let input: String = dialoguer::Input::with_theme(&get_input_theme())
+        .with_prompt(format!("Type DELETE to delete all {items}"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    confirm_delete_all(&input, items)
+}
+
+/// Return Ok if confirmation input is the word DELETE
+///
+/// # Errors
+///
+/// Returns error if the input does not match
+fn confirm_delete_all(input: &str, items: &str) -> Result<(), toado::Error> {
+    if input == "DELETE" {
+        Ok(())
+    } else {
+        Err(Into::into(format!(
+            "confirmation did not match, no {items} deleted"
+        )))
+    }
+}
+
+/// Maximum number of fuzzy matches presented to the user for selection
+const MAX_FUZZY_MATCHES: usize = 10;
+
+/// Orders items by the case insensitive fuzzy match score of their name against a search term,
+/// best match first. Items without a matching name are removed
+fn fuzzy_rank<T>(items: Vec<T>, term: &str, name: impl Fn(&T) -> Option<&String>) -> Vec<T> {
+    let matcher = SkimMatcherV2::default().ignore_case();
+
+    let mut scored: Vec<(i64, T)> = items
+        .into_iter()
+        .filter_map(|item| {
+            let score = matcher.fuzzy_match(name(&item)?, term)?;
+            Some((score, item))
+        })
+        .collect();
+
+    // Stable sort keeps the original order of items with equal scores
+    scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
 enum TasksOrProjects {
     Tasks(Vec<toado::Task>),
     Projects(Vec<toado::Project>),
@@ -108,6 +254,157 @@ impl TasksOrProjects {
             Self::Projects(_) => "projects",
         }
     }
+
+    /// Orders items by fuzzy match score against a search term, removing items that don't match
+    fn fuzzy_rank(self, term: &str) -> Self {
+        match self {
+            Self::Tasks(tasks) => Self::Tasks(fuzzy_rank(tasks, term, |task| task.name.as_ref())),
+            Self::Projects(projects) => {
+                Self::Projects(fuzzy_rank(projects, term, |project| project.name.as_ref()))
+            }
+        }
+    }
+
+    /// Shortens list of items to the first len items
+    fn truncate(&mut self, len: usize) {
+        match self {
+            Self::Tasks(tasks) => tasks.truncate(len),
+            Self::Projects(projects) => projects.truncate(len),
+        }
+    }
+}
+
+/// Returns the condition matching items for search arguments. If searching project names, matches
+/// tasks assigned to projects with names containing every term. Otherwise, if a single term is
+/// given and is a positive integer, matches the item id, or else matches items with names, or
+/// notes if enabled, containing every term
+fn search_condition(
+    args: &flags::SearchArgs,
+    config: &config::Config,
+) -> toado::QueryConditions<'static, String> {
+    if args.in_project {
+        let project_condition = toado::QueryConditions::And(
+            args.term
+                .iter()
+                .map(|term| name_like_condition(term, config))
+                .collect(),
+        );
+
+        return toado::QueryConditions::In {
+            col: "id",
+            values: vec![toado::assigned_task_ids_query(
+                &project_condition.to_string(),
+            )],
+        };
+    }
+
+    match args.term.as_slice() {
+        // If single search term is number, select by id
+        [term] if term.parse::<usize>().is_ok() => term_condition(term, config),
+        // Otherwise, select by names, or notes if enabled, containing every term
+        terms => toado::QueryConditions::And(
+            terms
+                .iter()
+                .map(|term| match args.in_notes {
+                    true => toado::QueryConditions::Or(vec![
+                        name_like_condition(term, config),
+                        notes_like_condition(term, config),
+                    ]),
+                    false => name_like_condition(term, config),
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Returns the regular expression of search arguments if regex search is enabled, joining multiple
+/// terms with spaces. If case insensitive search is enabled, the expression ignores case
+///
+/// # Errors
+///
+/// Will return an error if the terms are not a valid regular expression
+fn search_regex(
+    args: &flags::SearchArgs,
+    config: &config::Config,
+) -> Result<Option<Regex>, toado::ToadoError> {
+    if !args.regex {
+        return Ok(None);
+    }
+
+    let pattern = args.term.join(" ");
+    RegexBuilder::new(&pattern)
+        .case_insensitive(config.search.ignore_case)
+        .build()
+        .map(Some)
+        .map_err(|e| toado::ToadoError::Input(format!("invalid regex '{pattern}': {e}")))
+}
+
+/// Returns true if an item name, or notes if enabled, matches a search regular expression
+fn regex_matches(regex: &Regex, name: Option<&str>, notes: Option<&str>, in_notes: bool) -> bool {
+    name.is_some_and(|name| regex.is_match(name))
+        || (in_notes && notes.is_some_and(|notes| regex.is_match(notes)))
+}
+
+/// Returns a page of items, skipping an offset number of items and taking up to a limit of items
+fn page_items<T>(items: Vec<T>, limit: Option<usize>, offset: Option<usize>) -> Vec<T> {
+    items
+        .into_iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Returns a condition matching items for a search term. If the term is a positive integer, matches
+/// the item id, otherwise matches items with names containing the term
+fn term_condition(term: &str, config: &config::Config) -> toado::QueryConditions<'static, String> {
+    match term.parse::<usize>() {
+        Ok(id) => toado::QueryConditions::Equal {
+            col: "id",
+            value: id.to_string(),
+        },
+        Err(_) => name_like_condition(term, config),
+    }
+}
+
+/// Returns a condition matching item names containing a search term. If case insensitive search is
+/// enabled, both the name and term are case folded before matching
+fn name_like_condition(
+    term: &str,
+    config: &config::Config,
+) -> toado::QueryConditions<'static, String> {
+    like_condition("name", "fold_case(name)", term, config)
+}
+
+/// Returns a condition matching item notes containing a search term. If case insensitive search is
+/// enabled, both the notes and term are case folded before matching
+fn notes_like_condition(
+    term: &str,
+    config: &config::Config,
+) -> toado::QueryConditions<'static, String> {
+    like_condition("notes", "fold_case(notes)", term, config)
+}
+
+/// Returns a condition matching a column containing a search term, using the case folded column
+/// if case insensitive search is enabled
+fn like_condition(
+    col: &'static str,
+    folded_col: &'static str,
+    term: &str,
+    config: &config::Config,
+) -> toado::QueryConditions<'static, String> {
+    let pattern = toado::queries::quote_string(&format!("%{term}%"));
+
+    if config.search.ignore_case {
+        toado::QueryConditions::Like {
+            col: folded_col,
+            value: format!("fold_case({pattern})"),
+        }
+    } else {
+        toado::QueryConditions::Like {
+            col,
+            value: pattern,
+        }
+    }
 }
 
 /// Prompt the user to select an item (Task or Project) from list of items from a toado
@@ -124,55 +421,56 @@ fn prompt_select_item(
     projects: bool,
     config: &config::Config,
 ) -> Result<TasksOrProjects, toado::Error> {
-    let condition = match &term {
-        Some(term) => match term.parse::<usize>() {
-            Ok(num) => Some(
-                toado::QueryConditions::Equal {
-                    col: "id",
-                    value: num.to_string(),
-                }
-                .to_string(),
-            ),
-            Err(_) => Some(
-                toado::QueryConditions::Like {
-                    col: "name",
-                    value: format!("'%{term}%'"),
-                }
-                .to_string(),
-            ),
-        },
-        None => None,
-    };
+    let condition = term
+        .as_ref()
+        .map(|term| term_condition(term, config).to_string());
 
-    let items = if !projects {
-        TasksOrProjects::Tasks(app.select_tasks(
-            toado::QueryCols::Some(vec!["id", "name", "priority", "status"]),
-            condition,
-            Some(toado::OrderBy::Name),
-            None,
-            None,
-            None,
-        )?)
-    } else {
-        TasksOrProjects::Projects(app.select_project(
-            toado::QueryCols::Some(vec!["id", "name", "start_time", "end_time"]),
-            condition,
-            Some(toado::OrderBy::Name),
-            None,
-            None,
-            None,
-        )?)
+    let select_items = |condition: Option<String>, limit: Option<toado::RowLimit>| {
+        Ok::<TasksOrProjects, toado::Error>(if !projects {
+            TasksOrProjects::Tasks(app.select_tasks(
+                toado::QueryCols::Some(vec!["id", "name", "priority", "status"]),
+                condition,
+                Some(toado::OrderBy::Name),
+                None,
+                limit,
+                None,
+            )?)
+        } else {
+            TasksOrProjects::Projects(app.select_project(
+                toado::QueryCols::Some(vec!["id", "name", "start_time", "end_time"]),
+                condition,
+                Some(toado::OrderBy::Name),
+                None,
+                limit,
+                None,
+            )?)
+        })
     };
 
+    let mut items = select_items(condition, None)?;
+
+    // If no item names contain the search term, fall back to the closest fuzzy matches
+    let mut fuzzy = false;
+    if let Some(term) = &term {
+        if items.is_empty() && config.search.fuzzy && term.parse::<usize>().is_err() {
+            items = select_items(None, Some(toado::RowLimit::All))?.fuzzy_rank(term);
+            items.truncate(MAX_FUZZY_MATCHES);
+            fuzzy = true;
+        }
+    }
+
     if items.is_empty() {
         if let Some(term) = term {
-            return Err(Into::into(format!("no {} match {term}", items.name())));
+            return Err(
+                toado::ToadoError::NotFound(format!("no {} match {term}", items.name())).into(),
+            );
         }
 
-        return Err(Into::into(format!("no {} found", items.name())));
+        return Err(toado::ToadoError::NotFound(format!("no {} found", items.name())).into());
     }
 
-    if items.len() == 1 {
+    // Fuzzy matches are never selected without confirmation from the user
+    if items.len() == 1 && !fuzzy {
         return Ok(match items {
             TasksOrProjects::Tasks(tasks) => TasksOrProjects::Tasks(vec![tasks[0].clone()]),
             TasksOrProjects::Projects(projects) => {
@@ -183,10 +481,10 @@ fn prompt_select_item(
 
     let list_string = match &items {
         TasksOrProjects::Tasks(tasks) => {
-            formatting::format_task_list(tasks.clone(), false, &config.table)
+            formatting::format_task_list(tasks.clone(), false, false, config)
         }
         TasksOrProjects::Projects(projects) => {
-            formatting::format_project_list(projects.clone(), false, &config.table)
+            formatting::format_project_list(projects.clone(), false, false, config)
         }
     };
 
@@ -245,6 +543,53 @@ fn prompt_select_item(
     }
 }
 
+/// Renames an item in a toado application, updating only the item name. Prompts the user to
+/// select the item if multiple items match the search term
+///
+/// # Errors
+///
+/// Will return an error if the new name is invalid, if user input fails, or if updating the item
+/// fails
+pub fn rename_item(
+    args: flags::RenameArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<u64, toado::Error> {
+    validate_name(&args.name).map_err(toado::ToadoError::Input)?;
+
+    if args.project && !args.task {
+        let project = prompt_project_selection(
+            &app,
+            args.term,
+            toado::QueryCols::Some(vec!["id", "name", "start_time", "end_time"]),
+            &get_input_theme(),
+            config,
+        )?;
+
+        return match project.id {
+            Some(id) => app.rename_project(id, args.name),
+            None => Err(Into::into("project id should exist")),
+        };
+    }
+
+    let update_args = flags::UpdateArgs {
+        term: Some(args.term),
+        task: args.task,
+        project: args.project,
+        name: Some(args.name),
+        item_priority: None,
+        start_time: None,
+        end_time: None,
+        notes: None,
+        append_notes: None,
+        repeat: None,
+        status: None,
+        dry_run: false,
+    };
+
+    update_task(update_args, app, config)
+}
+
 /// Validate an item name
 fn validate_name(input: &str) -> Result<(), String> {
     let r = Regex::new(r"(^[0-9]+$|^\d)").expect("Regex creation should not fail");
@@ -255,39 +600,547 @@ fn validate_name(input: &str) -> Result<(), String> {
     }
 }
 
+/// Validate an item time, either an ISO 8601 time or a relative time expression. Empty input is
+/// valid, as it leaves the time unset
+fn validate_time(input: &str) -> Result<(), toado::ToadoError> {
+    canonical_time(input.to_string()).map(|_| ())
+}
+
+/// Validate that an item end time is not before its start time. Times that are unset or are not
+/// valid times are not compared, and times are compared by date if either is a date only time
+fn validate_time_order(start_time: Option<&str>, end_time: Option<&str>) -> Result<(), String> {
+    let (Some(start_time), Some(end_time)) = (start_time, end_time) else {
+        return Ok(());
+    };
+    let (Some(start), Some(end)) = (
+        toado::time::parse_time(start_time),
+        toado::time::parse_time(end_time),
+    ) else {
+        return Ok(());
+    };
+
+    let reversed = if toado::time::is_date(start_time) || toado::time::is_date(end_time) {
+        end.date() < start.date()
+    } else {
+        end < start
+    };
+
+    if reversed {
+        Err(format!(
+            "end time '{end_time}' is before start time '{start_time}'"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the value of an item time once updated, keeping the current value if the time is not
+/// updated
+fn updated_time(update: &toado::UpdateAction<String>, current: Option<String>) -> Option<String> {
+    match update {
+        toado::UpdateAction::Some(time) => Some(time.clone()),
+        toado::UpdateAction::Null => None,
+        toado::UpdateAction::None => current,
+    }
+}
+
+/// Resolves an item time into an ISO 8601 time, converting relative time expressions (ie.
+/// "tomorrow" or "+3d") into the time they refer to. Empty input is left empty
+fn canonical_time(input: String) -> Result<String, toado::ToadoError> {
+    if input.is_empty() {
+        return Ok(input);
+    }
+
+    toado::time::resolve_time(&input, &chrono::Local::now().naive_local()).ok_or_else(|| {
+        toado::ToadoError::Input(format!(
+            "'{input}' is not a valid time (eg. 2024-12-31, 2024-12-31T17:30, tomorrow, or +3d)"
+        ))
+    })
+}
+
+/// Resolves a time filter, either an ISO 8601 time or a relative time expression, into an ISO 8601
+/// time to compare with item times
+///
+/// # Errors
+///
+/// Will return an error if the filter is not a valid time
+fn resolve_time_filter(input: &str) -> Result<String, toado::Error> {
+    toado::time::resolve_time(input, &chrono::Local::now().naive_local()).ok_or_else(|| {
+        toado::ToadoError::Input(format!(
+            "'{input}' is not a valid time (eg. 2024-12-31, 2024-12-31T17:30, tomorrow, or +3d)"
+        ))
+        .into()
+    })
+}
+
+/// Validate an item recurrence. Empty input is valid, as it leaves the item not repeating
+fn validate_repeat(input: &str) -> Result<(), toado::ToadoError> {
+    canonical_repeat(input.to_string()).map(|_| ())
+}
+
+/// Validates that a task with an id exists
+///
+/// # Errors
+///
+/// Will return an error if no task has the id, or if selecting task ids fails
+fn validate_task_id(id: i64, app: &toado::Server) -> Result<(), toado::Error> {
+    let ids = app.select_task_ids(Some(
+        toado::QueryConditions::Equal {
+            col: "id",
+            value: id,
+        }
+        .to_string(),
+    ))?;
+
+    if ids.is_empty() {
+        return Err(toado::ToadoError::NotFound(format!("no task with id {id}")).into());
+    }
+
+    Ok(())
+}
+
+/// Converts a recurrence string into its canonical form (ie. "every 1 week" into "weekly")
+fn canonical_repeat(input: String) -> Result<String, toado::ToadoError> {
+    if input.is_empty() {
+        Ok(input)
+    } else {
+        input
+            .parse::<toado::recurrence::Recurrence>()
+            .map(|recurrence| recurrence.to_string())
+            .map_err(toado::ToadoError::Input)
+    }
+}
+
+/// Parsed list command arguments tuple type
+type ListFilters<'a> = (
+    toado::QueryCols<'a>,    // Selected columns
+    Option<String>,          // Condition
+    Option<toado::OrderBy>,  // Order by col
+    Option<toado::OrderDir>, // Order direction
+    Option<toado::RowLimit>, // Row limit
+    Option<usize>,           // Row offset
+);
+
 /// Parse list command CLI arguments into their respecitve data types
+///
+/// # Errors
+///
+/// Will return an error if the order column or a listed column is not a column of the listed table
 fn parse_list_args<'a>(
     args: &flags::ListArgs,
-) -> (
-    toado::QueryCols<'a>,
-    Option<toado::OrderBy>,
-    Option<toado::OrderDir>,
-    Option<toado::RowLimit>,
-    Option<usize>,
-) {
+    config: &config::Config,
+) -> Result<ListFilters<'a>, toado::Error> {
+    let listing_tasks = args.task || !args.project;
+
+    if let Some(order_by) = args.order_by.filter(|order_by| {
+        !listing_tasks
+            && !toado::Tables::Projects
+                .columns()
+                .contains(&order_by.to_string().as_str())
+    }) {
+        return Err(
+            toado::ToadoError::Input(format!("projects cannot be ordered by {order_by}")).into(),
+        );
+    }
+
+    // Use configured default order column if none is set by arguments, ignoring columns that the
+    // listed table does not have
+    let order_by = match (args.order_by, args.overdue) {
+        (Some(order_by), _) => Some(order_by),
+        // Order overdue tasks by end time, most overdue first, unless set by arguments
+        (None, true) => Some(toado::OrderBy::EndTime),
+        // Order completed tasks by completion time, most recent first, unless set by arguments
+        (None, false) if args.completed_since.is_some() => Some(toado::OrderBy::CompletedAt),
+        (None, false) => config.list.default_order_by.filter(|order_by| {
+            listing_tasks
+                || toado::Tables::Projects
+                    .columns()
+                    .contains(&order_by.to_string().as_str())
+        }),
+    };
+
     let order_dir = match (args.asc, args.desc) {
         (true, _) => Some(toado::OrderDir::Asc),
         (false, true) => Some(toado::OrderDir::Desc),
+        // Use configured default direction only if the order column is also not set by arguments
+        (false, false)
+            if args.order_by.is_none() && !args.overdue && args.completed_since.is_none() =>
+        {
+            config.list.default_order_dir
+        }
         (false, false) => None,
     };
 
+    // Flip the effective order direction, falling back to the default order column and its default
+    // direction when they are not otherwise set
+    let (order_by, order_dir) = match args.reverse {
+        true => {
+            let order_by = order_by.unwrap_or(match listing_tasks {
+                true => toado::OrderBy::Priority,
+                false => toado::OrderBy::Name,
+            });
+            let order_dir = order_dir.unwrap_or(order_by.default_dir()).reverse();
+            (Some(order_by), Some(order_dir))
+        }
+        false => (order_by, order_dir),
+    };
+
+    // Page through items after an id in order of id
+    let (order_by, order_dir) = match args.after_id {
+        Some(_) => (Some(toado::OrderBy::Id), Some(toado::OrderDir::Asc)),
+        None => (order_by, order_dir),
+    };
+
     // Determin columns to select
-    let cols = if args.verbose {
+    let cols = if let Some(columns) = &args.columns {
+        let valid_columns = if listing_tasks {
+            toado::Tables::Tasks.columns()
+        } else {
+            toado::Tables::Projects.columns()
+        };
+
+        toado::QueryCols::Some(parse_columns(columns, valid_columns)?)
+    } else if args.verbose {
         toado::QueryCols::All
-    } else if args.task || !args.project {
+    } else if listing_tasks {
         toado::QueryCols::Some(Vec::from(["id", "name", "priority", "status"]))
     } else {
         toado::QueryCols::Some(Vec::from(["id", "name", "start_time", "end_time"]))
     };
 
     // Determin selection row limit
-    let limit = match (args.full, args.limit) {
-        (true, _) => Some(toado::RowLimit::All), // Select all
-        (false, Some(val)) => Some(toado::RowLimit::Limit(val)), // Select set number
-        _ => None,                               // Select default number
+    let limit = match (args.full, args.limit, config.list.default_limit) {
+        (true, _, _) => Some(toado::RowLimit::All), // Select all
+        (false, Some(limit), _) => Some(limit),     // Select set number or all
+        (false, None, 0) => Some(toado::RowLimit::All), // Select all by default
+        (false, None, val) => Some(toado::RowLimit::Limit(val)), // Select default number
+    };
+
+    // Determin selection conditions
+    let mut conditions: Vec<String> = Vec::new();
+
+    // Items with a null end time never satisfy a comparison, so are excluded by due filters
+    if let Some(due_before) = &args.due_before {
+        conditions.push(
+            toado::QueryConditions::LessThan {
+                col: "end_time",
+                value: format!("'{}'", resolve_time_filter(due_before)?),
+            }
+            .to_string(),
+        );
+    }
+
+    if let Some(due_after) = &args.due_after {
+        conditions.push(
+            toado::QueryConditions::GreaterThan {
+                col: "end_time",
+                value: format!("'{}'", resolve_time_filter(due_after)?),
+            }
+            .to_string(),
+        );
+    }
+
+    // Empty values are stored as null, so items with empty notes are listed as without notes
+    let mut presence_conditions: Vec<toado::QueryConditions<String>> = Vec::new();
+    if args.no_due {
+        presence_conditions.push(toado::QueryConditions::IsNull { col: "end_time" });
+    }
+    if args.has_due {
+        presence_conditions.push(toado::QueryConditions::IsNotNull { col: "end_time" });
+    }
+    if args.no_notes {
+        presence_conditions.push(toado::QueryConditions::IsNull { col: "notes" });
+    }
+    if args.has_notes {
+        presence_conditions.push(toado::QueryConditions::IsNotNull { col: "notes" });
+    }
+    conditions.extend(presence_conditions.iter().map(ToString::to_string));
+
+    // Items added before creation times were tracked have a null creation time, so are excluded
+    if let Some(since) = &args.since {
+        conditions.push(
+            toado::QueryConditions::GreaterThanOrEqual {
+                col: "created_at",
+                value: format!("'{}'", resolve_time_filter(since)?),
+            }
+            .to_string(),
+        );
+    }
+
+    if let Some(until) = &args.until {
+        conditions.push(
+            toado::QueryConditions::LessThan {
+                col: "created_at",
+                value: format!("'{}'", resolve_time_filter(until)?),
+            }
+            .to_string(),
+        );
+    }
+
+    // Tasks not updated since update times were tracked have a null update time, so are excluded
+    if let Some(updated_since) = &args.updated_since {
+        conditions.push(
+            toado::QueryConditions::GreaterThanOrEqual {
+                col: "updated_at",
+                value: format!("'{}'", resolve_time_filter(updated_since)?),
+            }
+            .to_string(),
+        );
+    }
+
+    if let Some(completed_since) = &args.completed_since {
+        conditions.push(
+            toado::QueryConditions::GreaterThanOrEqual {
+                col: "completed_at",
+                value: format!("'{}'", resolve_time_filter(completed_since)?),
+            }
+            .to_string(),
+        );
+    }
+
+    if args.overdue {
+        conditions.push(toado::overdue_condition());
+    }
+
+    // Use the configured default status only if no status or completion filter is set by arguments
+    let status = match (args.all_statuses, args.status) {
+        (true, _) => None,
+        (false, Some(status)) => Some(status),
+        // Tasks completed since a time are complete, unless since archived
+        (false, None) if args.completed_since.is_some() => Some(toado::ItemStatus::Complete),
+        (false, None) if !args.overdue => config.list.default_status,
+        (false, None) => None,
+    };
+
+    // Projects are filtered by status only if set by arguments
+    let status = match listing_tasks {
+        true => status,
+        false => args.status,
+    };
+
+    if let Some(status) = status {
+        conditions.push(
+            toado::QueryConditions::Equal {
+                col: "status",
+                value: u32::from(status),
+            }
+            .to_string(),
+        );
+    }
+
+    if let Some(after_id) = args.after_id {
+        conditions.push(
+            toado::QueryConditions::GreaterThan {
+                col: "id",
+                value: after_id,
+            }
+            .to_string(),
+        );
+    }
+
+    if let Some(parent_id) = args.children {
+        conditions.push(
+            toado::QueryConditions::Equal {
+                col: "parent_id",
+                value: parent_id,
+            }
+            .to_string(),
+        );
+    }
+
+    if let Some(project_id) = args.project_id {
+        conditions.push(toado::assigned_to_condition(project_id));
+    }
+
+    if let Some(owner) = &args.owner {
+        conditions.push(
+            toado::QueryConditions::Equal {
+                col: "owner",
+                value: toado::queries::quote_string(owner),
+            }
+            .to_string(),
+        );
+    }
+
+    let condition = if conditions.is_empty() {
+        None
+    } else {
+        Some(conditions.join(" AND "))
     };
 
-    (cols, args.order_by, order_dir, limit, args.offset)
+    Ok((cols, condition, order_by, order_dir, limit, args.offset))
+}
+
+/// Parse a list of column names, validating each against the valid columns of a table
+///
+/// # Errors
+///
+/// Will return an error if a column name is not a valid column, or if no columns are given
+fn parse_columns(
+    columns: &[String],
+    valid_columns: &[&'static str],
+) -> Result<Vec<&'static str>, toado::Error> {
+    let columns = columns
+        .iter()
+        .map(|column| column.trim().to_lowercase())
+        .filter(|column| !column.is_empty())
+        .map(|column| {
+            valid_columns
+                .iter()
+                .find(|valid| **valid == column)
+                .copied()
+                .ok_or_else(|| {
+                    format!(
+                        "unknown column '{column}', expected one of: {}",
+                        valid_columns.join(", ")
+                    )
+                })
+        })
+        .collect::<Result<Vec<&str>, String>>()
+        .map_err(toado::ToadoError::Input)?;
+
+    if columns.is_empty() {
+        return Err(toado::ToadoError::Input("no columns provided".to_string()).into());
+    }
+
+    Ok(columns)
+}
+
+/// Serializes items as a JSON array of objects. If only some columns are selected, each object
+/// contains only the fields of those columns
+///
+/// # Errors
+///
+/// Will return an error if an item does not serialize to a JSON object
+fn json_list<T: serde::Serialize>(
+    items: &[T],
+    cols: &toado::QueryCols,
+) -> Result<String, toado::Error> {
+    Ok(serde_json::to_string(&json_rows(items, cols)?)?)
+}
+
+/// Formats items as newline delimited JSON, one compact object per line containing only the
+/// selected columns
+///
+/// # Errors
+///
+/// Will return an error if an item does not serialize to a JSON object
+fn ndjson_list<T: serde::Serialize>(
+    items: &[T],
+    cols: &toado::QueryCols,
+) -> Result<String, toado::Error> {
+    Ok(json_rows(items, cols)?
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<String>, serde_json::Error>>()?
+        .join("\n"))
+}
+
+/// Converts items into JSON objects containing only the selected columns
+///
+/// # Errors
+///
+/// Will return an error if an item does not serialize to a JSON object
+fn json_rows<T: serde::Serialize>(
+    items: &[T],
+    cols: &toado::QueryCols,
+) -> Result<Vec<serde_json::Map<String, serde_json::Value>>, toado::Error> {
+    items
+        .iter()
+        .map(|item| {
+            let mut row: serde_json::Map<String, serde_json::Value> =
+                serde_json::from_value(serde_json::to_value(item)?)?;
+            if let toado::QueryCols::Some(cols) = cols {
+                row.retain(|key, _| cols.contains(&key.as_str()));
+            }
+            Ok(row)
+        })
+        .collect()
+}
+
+/// Formats items as tab seperated values, one item per line with the fields of the columns in
+/// order, preceded by a line of column names if header is true. Backslashes, tabs, and new lines
+/// within values are escaped
+///
+/// # Errors
+///
+/// Will return an error if an item does not serialize to a JSON object
+fn tsv_list<T: serde::Serialize>(
+    items: &[T],
+    cols: &[&str],
+    header: bool,
+) -> Result<String, toado::Error> {
+    let mut lines: Vec<String> = Vec::new();
+    if header {
+        lines.push(cols.join("\t"));
+    }
+
+    for item in items {
+        lines.push(
+            item_fields(item, cols)?
+                .iter()
+                .map(|value| {
+                    value
+                        .replace('\\', "\\\\")
+                        .replace('\t', "\\t")
+                        .replace('\n', "\\n")
+                        .replace('\r', "\\r")
+                })
+                .collect::<Vec<String>>()
+                .join("\t"),
+        );
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Formats items as plain text, one item per line with the fields of the columns in order
+/// seperated by tabs. Whitespace within values is collapsed to single spaces, so each item is
+/// always one line
+///
+/// # Errors
+///
+/// Will return an error if an item does not serialize to a JSON object
+fn plain_list<T: serde::Serialize>(items: &[T], cols: &[&str]) -> Result<String, toado::Error> {
+    Ok(items
+        .iter()
+        .map(|item| {
+            Ok(item_fields(item, cols)?
+                .iter()
+                .map(|value| value.split_whitespace().collect::<Vec<&str>>().join(" "))
+                .collect::<Vec<String>>()
+                .join("\t"))
+        })
+        .collect::<Result<Vec<String>, toado::Error>>()?
+        .join("\n"))
+}
+
+/// Returns the fields of an item for each column in order, as strings. Null or missing fields are
+/// empty strings
+///
+/// # Errors
+///
+/// Will return an error if the item does not serialize to a JSON object
+fn item_fields<T: serde::Serialize>(item: &T, cols: &[&str]) -> Result<Vec<String>, toado::Error> {
+    let row: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_value(serde_json::to_value(item)?)?;
+
+    Ok(cols
+        .iter()
+        .map(|col| match row.get(*col) {
+            Some(serde_json::Value::String(value)) => value.clone(),
+            Some(serde_json::Value::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        })
+        .collect())
+}
+
+/// Returns the names of selected columns, or every column of the table if all are selected
+fn col_names<'a>(cols: &toado::QueryCols<'a>, table: toado::Tables) -> Vec<&'a str> {
+    match cols {
+        toado::QueryCols::Some(cols) => cols.clone(),
+        toado::QueryCols::All => table.columns().to_vec(),
+    }
 }
 
 fn list_footer(offset: Option<usize>, count: usize, total: usize) -> String {
@@ -303,3 +1156,800 @@ fn nullable_into_update_action(flag: Option<flags::NullableString>) -> toado::Up
         None => toado::UpdateAction::None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// Opens an initialized server with an in memory database
+    pub(super) fn test_server() -> toado::Server {
+        let app = toado::Server::open(":memory:").expect("in memory database should open");
+        app.init().expect("database should initialize");
+        app
+    }
+
+    /// Returns arguments for adding an incomplete task with a name
+    pub(super) fn task_args(name: &str) -> toado::AddTaskArgs {
+        toado::AddTaskArgs {
+            name: name.to_string(),
+            priority: 0,
+            status: toado::ItemStatus::Incomplete,
+            start_time: None,
+            end_time: None,
+            repeat: None,
+            notes: None,
+            parent_id: None,
+            owner: None,
+        }
+    }
+
+    /// Returns a path in the temporary directory unique to the test process, removing any file
+    /// already at the path
+    pub(super) fn temp_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("toado-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    /// Adds incomplete tasks with names to a server, returning their ids
+    pub(super) fn add_tasks(
+        app: &toado::Server,
+        names: impl IntoIterator<Item = impl ToString>,
+    ) -> Vec<i64> {
+        names
+            .into_iter()
+            .map(|name| {
+                app.add_task(task_args(&name.to_string()))
+                    .expect("task should be added")
+            })
+            .collect()
+    }
+
+    /// Adds projects with names to a server, returning their ids
+    pub(super) fn add_projects(
+        app: &toado::Server,
+        names: impl IntoIterator<Item = impl ToString>,
+    ) -> Vec<i64> {
+        names
+            .into_iter()
+            .map(|name| {
+                app.add_project(toado::AddProjectArgs {
+                    name: name.to_string(),
+                    start_time: None,
+                    end_time: None,
+                    notes: None,
+                })
+                .expect("project should be added")
+            })
+            .collect()
+    }
+
+    /// Parses the command of command line arguments, without the program name
+    pub(super) fn parse_command(args: &[&str]) -> flags::Commands {
+        flags::Cli::parse_from(["toado"].iter().chain(args))
+            .command
+            .expect("arguments should include a command")
+    }
+
+    /// Parses list command arguments
+    fn list_args(args: &[&str]) -> flags::ListArgs {
+        match parse_command(&[&["ls"], args].concat()) {
+            flags::Commands::Ls(args) => args,
+            _ => panic!("ls should parse as the list command"),
+        }
+    }
+
+    /// Parses search command arguments
+    fn search_args(args: &[&str]) -> flags::SearchArgs {
+        match parse_command(&[&["search"], args].concat()) {
+            flags::Commands::Search(args) => args,
+            _ => panic!("search should parse as the search command"),
+        }
+    }
+
+    #[test]
+    fn interrupted_prompts_are_cancelled() {
+        let interrupted: toado::Error =
+            dialoguer::Error::IO(std::io::Error::from(std::io::ErrorKind::Interrupted)).into();
+        let failed: toado::Error =
+            dialoguer::Error::IO(std::io::Error::from(std::io::ErrorKind::BrokenPipe)).into();
+        let other: toado::Error = toado::ToadoError::Input("bad time".to_string()).into();
+
+        assert!(is_cancelled(&interrupted));
+        assert!(!is_cancelled(&failed));
+        assert!(!is_cancelled(&other));
+    }
+
+    #[test]
+    fn rename_item_changes_only_the_name() {
+        let config = config::Config::default();
+        let db_path = temp_path("rename.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let task_id = app
+            .add_task(toado::AddTaskArgs {
+                priority: 3,
+                end_time: Some("2024-06-01".to_string()),
+                notes: Some("notes".to_string()),
+                ..task_args("old task")
+            })
+            .unwrap();
+        let project_id = add_projects(&app, ["old project"])[0];
+
+        let rename = |args: &[&str]| match parse_command(&[&["rename"], args].concat()) {
+            flags::Commands::Rename(args) => {
+                rename_item(args, toado::Server::open(&db_path).unwrap(), &config)
+            }
+            _ => panic!("rename should parse as the rename command"),
+        };
+        assert_eq!(rename(&["old task", "new task"]).unwrap(), 1);
+        assert_eq!(rename(&["old project", "new project", "-p"]).unwrap(), 1);
+        assert!(rename(&["new task", "1st"]).is_err());
+
+        let app = toado::Server::open(&db_path).unwrap();
+        let task = app.get_task_by_id(task_id).unwrap().unwrap();
+        assert_eq!(task.name.as_deref(), Some("new task"));
+        assert_eq!(task.priority, Some(3));
+        assert_eq!(task.end_time.as_deref(), Some("2024-06-01"));
+        assert_eq!(task.notes.as_deref(), Some("notes"));
+        let project = app.get_project_by_id(project_id).unwrap().unwrap();
+        assert_eq!(project.name.as_deref(), Some("new project"));
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn ndjson_list_has_one_object_per_line() {
+        let items = [
+            serde_json::json!({"id": 1, "name": "first\nline", "notes": null}),
+            serde_json::json!({"id": 2, "name": "second", "notes": "notes"}),
+        ];
+
+        let output = ndjson_list(&items, &toado::QueryCols::Some(vec!["id", "name"])).unwrap();
+        assert!(!output.starts_with('['));
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, id) in lines.iter().zip([1, 2]) {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["id"], id);
+            assert_eq!(value.as_object().unwrap().len(), 2);
+        }
+    }
+
+    #[test]
+    fn tsv_list_escapes_separators() {
+        let items = [serde_json::json!({
+            "id": 1,
+            "name": "a\tb",
+            "notes": "line\nbreak \\ slash",
+            "end_time": null,
+        })];
+
+        assert_eq!(
+            tsv_list(&items, &["id", "name", "notes", "end_time"], true).unwrap(),
+            "id\tname\tnotes\tend_time\n1\ta\\tb\tline\\nbreak \\\\ slash\t"
+        );
+    }
+
+    #[test]
+    fn name_completion_completes_prefixes() {
+        use dialoguer::Completion;
+
+        let app = test_server();
+        add_tasks(&app, ["Buy milk", "buy bread", "call bank"]);
+        let mut config = config::Config::default();
+
+        let completion = NameCompletion::tasks(true, &app, &config).unwrap();
+        assert_eq!(completion.get("buy").as_deref(), Some("buy bread"));
+        assert_eq!(completion.get("Buy").as_deref(), Some("Buy milk"));
+        assert_eq!(completion.get("sell"), None);
+        assert_eq!(completion.get(""), None);
+
+        config.search.ignore_case = true;
+        let completion = NameCompletion::tasks(true, &app, &config).unwrap();
+        assert!(completion.get("CALL").is_some());
+
+        // Names are not read if the user is not prompted for a name
+        let completion = NameCompletion::tasks(false, &app, &config).unwrap();
+        assert_eq!(completion.get("buy"), None);
+    }
+
+    #[test]
+    fn zero_matched_rows_are_not_found() {
+        assert_eq!(matched_rows(2, "tasks").unwrap(), 2);
+        let err = matched_rows(0, "tasks").expect_err("no rows should fail");
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(toado::ToadoError::NotFound(_))
+        ));
+
+        // The task is selected, but a trigger skips its update so that no rows match
+        let db_path = temp_path("zero-matched.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let id = add_tasks(&app, ["task"])[0];
+        rusqlite::Connection::open(&db_path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TRIGGER skip_task_updates BEFORE UPDATE ON tasks
+                BEGIN SELECT RAISE(IGNORE); END",
+            )
+            .unwrap();
+
+        let id = id.to_string();
+        let flags::Commands::Update(args) = parse_command(&["update", &id, "--item-priority", "1"])
+        else {
+            panic!("update should parse as the update command");
+        };
+        let err = update_task(args, app, &config::Config::default())
+            .expect_err("unmatched task should fail");
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(toado::ToadoError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn term_condition_matches_ids_or_names() {
+        let mut config = config::Config::default();
+
+        assert_eq!(term_condition("42", &config).to_string(), "id = 42");
+        assert_eq!(term_condition("007", &config).to_string(), "id = 7");
+        assert_eq!(
+            term_condition("milk", &config).to_string(),
+            "name LIKE '%milk%'"
+        );
+        assert_eq!(
+            term_condition("-1", &config).to_string(),
+            "name LIKE '%-1%'"
+        );
+
+        config.search.ignore_case = true;
+        assert_eq!(
+            term_condition("milk", &config).to_string(),
+            "fold_case(name) LIKE fold_case('%milk%')"
+        );
+    }
+
+    #[test]
+    fn search_single_id_term_selects_by_id() {
+        let config = config::Config::default();
+
+        assert_eq!(
+            search_condition(&search_args(&["007"]), &config).to_string(),
+            term_condition("007", &config).to_string()
+        );
+        assert_eq!(
+            search_condition(&search_args(&["007"]), &config).to_string(),
+            search_condition(&search_args(&["7"]), &config).to_string()
+        );
+        assert_ne!(
+            search_condition(&search_args(&["7", "8"]), &config).to_string(),
+            term_condition("7", &config).to_string()
+        );
+    }
+
+    /// Returns the ids of tasks matching search arguments, in order of id
+    fn search_ids(app: &toado::Server, args: &[&str], config: &config::Config) -> Vec<i64> {
+        app.select_task_ids(Some(
+            search_condition(&search_args(args), config).to_string(),
+        ))
+        .expect("task ids should be selected")
+    }
+
+    #[test]
+    fn term_condition_quotes_terms() {
+        let mut config = config::Config::default();
+        assert_eq!(
+            term_condition("don't", &config).to_string(),
+            "name LIKE '%don''t%'"
+        );
+
+        config.search.ignore_case = true;
+        assert_eq!(
+            term_condition("don't", &config).to_string(),
+            "fold_case(name) LIKE fold_case('%don''t%')"
+        );
+
+        let app = test_server();
+        let ids = add_tasks(&app, ["don't forget", "do forget"]);
+        assert_eq!(search_ids(&app, &["don't"], &config), vec![ids[0]]);
+    }
+
+    #[test]
+    fn search_terms_must_all_match() {
+        let app = test_server();
+        let config = config::Config::default();
+        let ids = add_tasks(&app, ["buy milk", "buy bread", "drink milk"]);
+
+        assert_eq!(search_ids(&app, &["buy", "milk"], &config), vec![ids[0]]);
+    }
+
+    #[test]
+    fn in_notes_searches_notes_and_names() {
+        let app = test_server();
+        let config = config::Config::default();
+        let named = add_tasks(&app, ["invoice client"])[0];
+        let noted = app
+            .add_task(toado::AddTaskArgs {
+                notes: Some("send the invoice".to_string()),
+                ..task_args("billing")
+            })
+            .unwrap();
+
+        assert_eq!(search_ids(&app, &["invoice"], &config), vec![named]);
+        assert_eq!(
+            search_ids(&app, &["invoice", "--in-notes"], &config),
+            vec![named, noted]
+        );
+    }
+
+    #[test]
+    fn in_project_searches_assigned_project_names() {
+        let app = test_server();
+        let config = config::Config::default();
+        let task_ids = add_tasks(&app, ["report", "work out", "unassigned"]);
+        let project_ids = add_projects(&app, ["work", "home"]);
+        app.assign_task(task_ids[0], project_ids[0]).unwrap();
+        app.assign_task(task_ids[1], project_ids[1]).unwrap();
+
+        assert_eq!(
+            search_ids(&app, &["work", "--in-project"], &config),
+            vec![task_ids[0]]
+        );
+    }
+
+    #[test]
+    fn ignore_case_matches_accented_names() {
+        let app = test_server();
+        add_tasks(&app, ["café au lait"]);
+        let mut config = config::Config::default();
+        let select = |config: &config::Config| {
+            app.select_tasks(
+                toado::QueryCols::Some(vec!["id"]),
+                Some(name_like_condition("CAFÉ", config).to_string()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+            .len()
+        };
+
+        assert_eq!(select(&config), 0);
+        config.search.ignore_case = true;
+        assert_eq!(select(&config), 1);
+    }
+
+    #[test]
+    fn no_due_lists_only_tasks_without_end_times() {
+        let app = test_server();
+        let config = config::Config::default();
+        app.add_task(toado::AddTaskArgs {
+            end_time: Some("2024-06-01".to_string()),
+            ..task_args("due")
+        })
+        .expect("task should be added");
+        add_tasks(&app, ["undated"]);
+
+        assert_eq!(list_names(&app, &["--no-due"], &config), ["undated"]);
+    }
+
+    #[test]
+    fn presence_filters_list_tasks_by_present_fields() {
+        let app = test_server();
+        let config = config::Config::default();
+        app.add_task(toado::AddTaskArgs {
+            end_time: Some("2024-06-01".to_string()),
+            ..task_args("due")
+        })
+        .unwrap();
+        app.add_task(toado::AddTaskArgs {
+            notes: Some("notes".to_string()),
+            ..task_args("noted")
+        })
+        .unwrap();
+        add_tasks(&app, ["bare"]);
+
+        assert_eq!(list_names(&app, &["--has-due"], &config), ["due"]);
+        assert_eq!(list_names(&app, &["--has-notes"], &config), ["noted"]);
+        assert_eq!(
+            list_names(&app, &["--no-due", "--no-notes"], &config),
+            ["bare"]
+        );
+    }
+
+    #[test]
+    fn columns_select_listed_columns_in_order() {
+        let config = config::Config::default();
+
+        let (cols, ..) =
+            parse_list_args(&list_args(&["--columns", "name, ID,end_time"]), &config).unwrap();
+        let toado::QueryCols::Some(cols) = cols else {
+            panic!("listed columns should be selected");
+        };
+        assert_eq!(cols, ["name", "id", "end_time"]);
+
+        let (cols, ..) = parse_list_args(
+            &list_args(&["--project", "--columns", "id,name,end_time"]),
+            &config,
+        )
+        .unwrap();
+        assert!(matches!(cols, toado::QueryCols::Some(cols) if cols.len() == 3));
+
+        for args in [
+            &["--columns", "id,bogus"][..],
+            &["--columns", ","],
+            &["--project", "--columns", "id,priority"],
+        ] {
+            let err = parse_list_args(&list_args(args), &config)
+                .err()
+                .expect("unknown column should fail");
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(toado::ToadoError::Input(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn delete_all_guard_rejects_unmatched_confirmation() {
+        assert!(confirm_delete_all("DELETE", "tasks").is_ok());
+        for input in ["", "delete", "DELETE ", "yes"] {
+            assert!(confirm_delete_all(input, "tasks").is_err());
+        }
+        assert!(skip_or_confirm_delete_all(true, "tasks").is_ok());
+    }
+
+    #[test]
+    fn fuzzy_rank_ranks_close_names_first() {
+        let names = ["Laundry", "Grocery shopping", "Pay rent", "Gardening"].map(String::from);
+        let ranked = fuzzy_rank(names.to_vec(), "GROCRY", |name| Some(name));
+
+        assert_eq!(ranked.first().map(String::as_str), Some("Grocery shopping"));
+        assert!(!ranked.iter().any(|name| name == "Pay rent"));
+    }
+
+    #[test]
+    fn default_limit_limits_unconstrained_lists() {
+        let app = test_server();
+        add_tasks(&app, (0..12).map(|i| format!("task {i}")));
+
+        let mut config = config::Config::default();
+        config.list.default_limit = 5;
+        assert_eq!(list_names(&app, &[], &config).len(), 5);
+        assert_eq!(list_names(&app, &["--limit", "3"], &config).len(), 3);
+        assert_eq!(list_names(&app, &["--full"], &config).len(), 12);
+
+        config.list.default_limit = 0;
+        assert_eq!(list_names(&app, &[], &config).len(), 12);
+    }
+
+    #[test]
+    fn default_order_is_used_without_order_flags() {
+        let mut config = config::Config::default();
+        config.list.default_order_by = Some(toado::OrderBy::Name);
+        config.list.default_order_dir = Some(toado::OrderDir::Asc);
+
+        let (cols, condition, order_by, order_dir, limit, offset) =
+            parse_list_args(&list_args(&[]), &config).unwrap();
+        let query = toado::queries::SelectTasksQuery::new(
+            cols, condition, order_by, order_dir, limit, offset,
+        )
+        .to_string();
+        assert!(query.contains("ORDER BY name ASC"), "{query}");
+
+        let app = test_server();
+        add_tasks(&app, ["b", "c", "a"]);
+        assert_eq!(list_names(&app, &[], &config), ["a", "b", "c"]);
+        assert_eq!(list_names(&app, &["--desc"], &config), ["c", "b", "a"]);
+        assert_eq!(list_names(&app, &["id"], &config), ["b", "c", "a"]);
+    }
+
+    #[test]
+    fn project_id_lists_only_assigned_tasks() {
+        let app = test_server();
+        let config = config::Config::default();
+        let task_ids = add_tasks(&app, ["a", "b", "c"]);
+        let project_ids = add_projects(&app, ["chosen", "other"]);
+        app.assign_task(task_ids[0], project_ids[0]).unwrap();
+        app.assign_task(task_ids[2], project_ids[0]).unwrap();
+        app.assign_task(task_ids[1], project_ids[1]).unwrap();
+
+        let project_id = project_ids[0].to_string();
+        assert_eq!(
+            list_names(&app, &["--project-id", &project_id], &config),
+            ["a", "c"]
+        );
+        assert_eq!(
+            list_names(
+                &app,
+                &["--project-id", &project_id, "id", "--desc"],
+                &config
+            ),
+            ["c", "a"]
+        );
+    }
+
+    #[test]
+    fn reverse_flips_effective_order() {
+        let app = test_server();
+        let config = config::Config::default();
+        add_tasks(&app, ["a", "b", "c"]);
+
+        assert_eq!(list_names(&app, &["id"], &config), ["a", "b", "c"]);
+        assert_eq!(
+            list_names(&app, &["id", "--reverse"], &config),
+            ["c", "b", "a"]
+        );
+        assert_eq!(
+            list_names(&app, &["id", "--asc", "--reverse"], &config),
+            ["c", "b", "a"]
+        );
+        assert_eq!(
+            list_names(&app, &["id", "--desc", "-r"], &config),
+            ["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn default_status_filters_unless_overridden() {
+        let mut config = config::Config::default();
+        config.list.default_status = Some(toado::ItemStatus::Incomplete);
+        let condition = |args: &[&str]| {
+            parse_list_args(&list_args(args), &config)
+                .unwrap()
+                .1
+                .unwrap_or_default()
+        };
+
+        assert_eq!(condition(&[]), "status = 0");
+        assert_eq!(condition(&["--status", "complete"]), "status = 1");
+        assert_eq!(condition(&["--all-statuses"]), "");
+    }
+
+    #[test]
+    fn overdue_lists_most_overdue_first() {
+        let app = test_server();
+        let config = config::Config::default();
+        for (name, end_time) in [
+            ("recent", Some("2001-06-01")),
+            ("future", Some("2999-01-01")),
+            ("undated", None),
+            ("oldest", Some("2000-01-01T09:00")),
+        ] {
+            app.add_task(toado::AddTaskArgs {
+                end_time: end_time.map(String::from),
+                ..task_args(name)
+            })
+            .unwrap();
+        }
+        let done = app
+            .add_task(toado::AddTaskArgs {
+                end_time: Some("1999-01-01".to_string()),
+                ..task_args("done")
+            })
+            .unwrap();
+        app.update_tasks_bulk(
+            vec![done],
+            toado::UpdateTaskArgs::update_status(toado::ItemStatus::Complete),
+        )
+        .unwrap();
+
+        assert_eq!(
+            list_names(&app, &["--overdue"], &config),
+            ["oldest", "recent"]
+        );
+    }
+
+    #[test]
+    fn after_id_lists_rows_past_cursor_in_id_order() {
+        let app = test_server();
+        let mut config = config::Config::default();
+        config.list.default_order_by = Some(toado::OrderBy::Name);
+        config.list.default_order_dir = Some(toado::OrderDir::Desc);
+        let ids = add_tasks(&app, ["d", "c", "b", "a", "e"]);
+
+        let cursor = ids[1].to_string();
+        assert_eq!(
+            list_names(&app, &["--after-id", &cursor], &config),
+            ["b", "a", "e"]
+        );
+        assert_eq!(
+            list_names(&app, &["--after-id", &cursor, "--limit", "2"], &config),
+            ["b", "a"]
+        );
+    }
+
+    #[test]
+    fn completed_since_lists_recent_completions_latest_first() {
+        let config = config::Config::default();
+        let db_path = temp_path("completed-since.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let ids = add_tasks(&app, ["old", "earlier", "latest", "open"]);
+        app.update_tasks_bulk(
+            ids[..3].to_vec(),
+            toado::UpdateTaskArgs::update_status(toado::ItemStatus::Complete),
+        )
+        .unwrap();
+
+        let connection = rusqlite::Connection::open(&db_path).unwrap();
+        for (id, completed_at) in [
+            (ids[0], "2024-05-01T09:00:00"),
+            (ids[1], "2024-06-02T09:00:00"),
+            (ids[2], "2024-06-03T09:00:00"),
+        ] {
+            connection
+                .execute(
+                    "UPDATE tasks SET completed_at = ?1 WHERE id = ?2",
+                    (completed_at, id),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            list_names(&app, &["--completed-since", "2024-06-01"], &config),
+            ["latest", "earlier"]
+        );
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn updating_tasks_bumps_update_time() {
+        let config = config::Config::default();
+        let db_path = temp_path("updated-since.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let ids = add_tasks(&app, ["edited", "untouched"]);
+        rusqlite::Connection::open(&db_path)
+            .unwrap()
+            .execute("UPDATE tasks SET updated_at = '2000-01-01T00:00:00'", ())
+            .unwrap();
+
+        app.update_tasks_bulk(vec![ids[0]], toado::UpdateTaskArgs::update_priority(2))
+            .unwrap();
+
+        let updated_at = |id| app.get_task_by_id(id).unwrap().unwrap().updated_at;
+        assert_ne!(updated_at(ids[0]).as_deref(), Some("2000-01-01T00:00:00"));
+        assert_eq!(updated_at(ids[1]).as_deref(), Some("2000-01-01T00:00:00"));
+        assert_eq!(
+            list_names(&app, &["--updated-since", "today"], &config),
+            ["edited"]
+        );
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn since_filters_by_creation_time() {
+        let app = test_server();
+        let config = config::Config::default();
+        let ids = add_tasks(&app, ["new"]);
+
+        let task = app.get_task_by_id(ids[0]).unwrap().unwrap();
+        assert!(task.created_at.is_some());
+
+        assert_eq!(list_names(&app, &["--since", "today"], &config), ["new"]);
+        assert!(list_names(&app, &["--since", "tomorrow"], &config).is_empty());
+        assert_eq!(list_names(&app, &["--until", "tomorrow"], &config), ["new"]);
+        assert!(list_names(&app, &["--until", "2000-01-01"], &config).is_empty());
+    }
+
+    #[test]
+    fn validate_time_accepts_only_iso_8601_times() {
+        for time in [
+            "",
+            "2024-12-31",
+            "2024-12-31T17:30",
+            "2024-12-31 17:30:00",
+            "2024-12-31T17:30:00+02:00",
+        ] {
+            assert!(validate_time(time).is_ok(), "'{time}' should be valid");
+        }
+
+        for time in ["2024-13-40", "2024-12-31T25:00", "31/12/2024", "soon"] {
+            assert!(
+                matches!(validate_time(time), Err(toado::ToadoError::Input(_))),
+                "'{time}' should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn time_order_compares_dates_by_day() {
+        assert!(validate_time_order(Some("2024-01-02"), Some("2024-01-02 09:00")).is_ok());
+        assert!(validate_time_order(Some("2024-01-02 18:00"), Some("2024-01-02")).is_ok());
+        assert!(validate_time_order(Some("2024-01-02 18:00"), Some("2024-01-02 09:00")).is_err());
+        assert!(validate_time_order(Some("2024-01-02"), Some("2024-01-01")).is_err());
+        assert!(validate_time_order(Some("2024-01-02"), None).is_ok());
+    }
+
+    #[test]
+    fn time_order_allows_ordered_and_equal_times() {
+        assert!(validate_time_order(Some("2024-01-01 09:00"), Some("2024-01-02 09:00")).is_ok());
+        assert!(validate_time_order(Some("2024-01-02 09:00"), Some("2024-01-02 09:00")).is_ok());
+        assert!(validate_time_order(Some("2024-01-02"), Some("2024-01-02")).is_ok());
+        assert!(validate_time_order(Some("2024-01-02 09:01"), Some("2024-01-02 09:00")).is_err());
+    }
+
+    /// Lists the names of the tasks selected by list command arguments, in order
+    fn list_names(app: &toado::Server, args: &[&str], config: &config::Config) -> Vec<String> {
+        let (_, condition, order_by, order_dir, limit, offset) =
+            parse_list_args(&list_args(args), config).expect("list arguments should parse");
+
+        app.select_tasks(
+            toado::QueryCols::Some(vec!["id", "name"]),
+            condition,
+            order_by,
+            order_dir,
+            limit,
+            offset,
+        )
+        .expect("tasks should be selected")
+        .into_iter()
+        .filter_map(|task| task.name)
+        .collect()
+    }
+
+    #[test]
+    fn due_filters_exclude_tasks_without_end_times() {
+        let app = test_server();
+        let config = config::Config::default();
+        for (name, end_time) in [("early", "2024-06-01"), ("late", "2025-06-01")] {
+            app.add_task(toado::AddTaskArgs {
+                end_time: Some(end_time.to_string()),
+                ..task_args(name)
+            })
+            .expect("task should be added");
+        }
+        add_tasks(&app, ["undated"]);
+
+        let (_, condition, ..) =
+            parse_list_args(&list_args(&["--due-before", "2024-12-31"]), &config).unwrap();
+        assert_eq!(condition.as_deref(), Some("end_time < '2024-12-31'"));
+
+        assert_eq!(
+            list_names(&app, &["--due-before", "2024-12-31"], &config),
+            ["early"]
+        );
+        assert_eq!(
+            list_names(&app, &["--due-after", "2024-12-31"], &config),
+            ["late"]
+        );
+        assert_eq!(
+            list_names(
+                &app,
+                &["--due-after", "2024-01-01", "--due-before", "2030-01-01"],
+                &config
+            ),
+            ["early", "late"]
+        );
+    }
+
+    #[test]
+    fn time_filters_reject_invalid_times() {
+        let config = config::Config::default();
+
+        for flag in [
+            "--since",
+            "--until",
+            "--due-before",
+            "--due-after",
+            "--completed-since",
+            "--updated-since",
+        ] {
+            let err = parse_list_args(&list_args(&[flag, "x' OR 1=1 --"]), &config)
+                .err()
+                .expect("invalid time should fail");
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(toado::ToadoError::Input(_))
+            ));
+
+            assert!(parse_list_args(&list_args(&[flag, "2024-01-01"]), &config).is_ok());
+            assert!(parse_list_args(&list_args(&[flag, "tomorrow"]), &config).is_ok());
+        }
+    }
+}
@@ -1,18 +1,23 @@
-use crate::{config, flags};
+use crate::{config, flags, formatting};
+use std::collections::HashSet;
 
-use super::{get_input_theme, prompt_select_item};
+use super::{get_input_theme, prompt_select_item, skip_or_confirm, term_condition};
 
-// Assigns a single task to a single project in a toado application. Requires a search term to be
-// set for both task and project
-//
-// # Errors
-//
-// Will return an error if no search term is supplied for task or project, or if task or project
-// selection fails, or if no task or project matches respective search term, or if assignment of
-// task fails
+/// Maximum number of assignments created at once without confirmation from the user
+const MAX_UNCONFIRMED_ASSIGNMENTS: usize = 10;
+
+/// Assigns a single task to a single project in a toado application. Requires a search term to be
+/// set for both task and project
+///
+/// # Errors
+///
+/// Will return an error if no search term is supplied for task or project, or if task or project
+/// selection fails, or if no task or project matches respective search term, or if assignment of
+/// task fails
 pub fn assign_task(
     args: flags::AssignArgs,
     app: toado::Server,
+    config: &config::Config,
 ) -> Result<(String, String), toado::Error> {
     let (task_term, project_term) = parse_search_terms(&args);
     let task_term = match task_term {
@@ -25,24 +30,27 @@ pub fn assign_task(
     };
 
     let (task_id, task_name, project_id, project_name) =
-        match_single_task_and_project(task_term, project_term, &app)?;
+        match_single_task_and_project(task_term, project_term, &app, config)?;
 
     app.assign_task(task_id, project_id)?;
     Ok((task_name, project_name))
 }
 
-// Assigns one or more tasks to one or more projects in a toado app. Will prompt user for task and
-// or project selection if no search terms are provided as args, or if search terms don't match a
-// single task or project.
-//
-// # Errors
-//
-// Will return an error if selection of tasks or projects fails, or if task assignment fails
+/// Assigns one or more tasks to one or more projects in a toado app. Will prompt user for task and
+/// or project selection if no search terms are provided as args, or if search terms don't match a
+/// single task or project. Tasks already assigned to a project are skipped, and the user is asked
+/// to confirm creating more than MAX_UNCONFIRMED_ASSIGNMENTS assignments unless the yes flag is
+/// set. Returns the names of the assigned tasks and projects, or None if cancelled
+///
+/// # Errors
+///
+/// Will return an error if selection of tasks or projects fails, if user input fails, or if task
+/// assignment fails
 pub fn assign_multiple_tasks(
     args: flags::AssignArgs,
     app: toado::Server,
     config: &config::Config,
-) -> Result<Vec<(String, String)>, toado::Error> {
+) -> Result<Option<Vec<(String, String)>>, toado::Error> {
     let (task_term, project_term) = parse_search_terms(&args);
 
     let theme = get_input_theme();
@@ -55,25 +63,33 @@ pub fn assign_multiple_tasks(
     let (task_ids, task_names) = parse_task_names_and_ids(tasks)?;
     let (project_ids, project_names) = parse_project_names_and_ids(projects)?;
 
-    let assignment_ids = create_id_pairs(task_ids, project_ids);
+    let existing: HashSet<(i64, i64)> = app.select_assignments()?.into_iter().collect();
+    let (assignment_ids, assignment_names) = new_assignment_pairs(
+        (task_ids, task_names),
+        (project_ids, project_names),
+        &existing,
+    );
 
-    let assignment_names = create_name_pairs(task_names, project_names);
+    if !confirm_assignments(assignment_ids.len(), args.yes, &theme)? {
+        return Ok(None);
+    }
 
     // Assign tasks to projects
     app.batch_assign_tasks(assignment_ids)?;
-    Ok(assignment_names)
+    Ok(Some(assignment_names))
 }
 
-// Unassigns a task from a project in a toado app. Requires a search term for both tasks and
-// projects
-//
-// # Errors
-//
-// Will return an error if unable to match a task or project to respective search term, or if
-// unassigning fails
+/// Unassigns a task from a project in a toado app. Requires a search term for both tasks and
+/// projects
+///
+/// # Errors
+///
+/// Will return an error if unable to match a task or project to respective search term, or if
+/// unassigning fails
 pub fn unassign_task(
     args: flags::AssignArgs,
     app: toado::Server,
+    config: &config::Config,
 ) -> Result<(String, String), toado::Error> {
     let (task_term, project_term) = parse_search_terms(&args);
     let task_term = match task_term {
@@ -86,12 +102,19 @@ pub fn unassign_task(
     };
 
     let (task_id, task_name, project_id, project_name) =
-        match_single_task_and_project(task_term, project_term, &app)?;
+        match_single_task_and_project(task_term, project_term, &app, config)?;
 
     app.unassign_task(task_id, project_id)?;
     Ok((task_name, project_name))
 }
 
+/// Unassigns one or more tasks from one or more projects in a toado app. Will prompt user for task
+/// and or project selection if no search terms are provided as args, or if search terms don't
+/// match a single task or project. Returns the names of the unassigned tasks and projects
+///
+/// # Errors
+///
+/// Will return an error if selection of tasks or projects fails, or if unassigning tasks fails
 pub fn unassign_multiple_tasks(
     args: flags::AssignArgs,
     app: toado::Server,
@@ -117,10 +140,184 @@ pub fn unassign_multiple_tasks(
     Ok(unassignment_names)
 }
 
+/// Lists the projects a task is assigned to, or if only a project search term is provided, the
+/// tasks assigned to a project. Prompts the user to select the task or project if the search term
+/// matches multiple items
+///
+/// # Errors
+///
+/// Will return an error if selection of the task or project fails, or if selecting its
+/// assignments fails
+pub fn list_assignments(
+    args: flags::AssignArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<String, toado::Error> {
+    let (task_term, project_term) = parse_search_terms(&args);
+    let theme = get_input_theme();
+
+    if task_term.is_some() || project_term.is_none() {
+        // List projects of task
+        let mut task = match prompt_select_item(task_term, &app, &theme, false, false, config)?
+            .tasks()
+            .pop()
+        {
+            Some(task) => task,
+            None => return Err(Into::into("selected task should exist")),
+        };
+
+        let (task_id, task_name) = match (task.id, &task.name) {
+            (Some(id), Some(name)) => (id, name.clone()),
+            _ => return Err(Into::into("task should have id and name")),
+        };
+
+        task.projects = Some(app.select_task_projects(task_id)?);
+
+        match task.projects {
+            Some(projects) if !projects.is_empty() => Ok(format!(
+                "Projects of '{task_name}':\n{}",
+                formatting::format_project_list(projects, false, config.table.show_header, config)
+            )),
+            _ => Ok(format!("'{task_name}' is not assigned to any projects")),
+        }
+    } else {
+        // List tasks of project
+        let mut project = match prompt_select_item(project_term, &app, &theme, false, true, config)?
+            .projects()
+            .pop()
+        {
+            Some(project) => project,
+            None => return Err(Into::into("selected project should exist")),
+        };
+
+        let (project_id, project_name) = match (project.id, &project.name) {
+            (Some(id), Some(name)) => (id, name.clone()),
+            _ => return Err(Into::into("project should have id and name")),
+        };
+
+        project.tasks = Some(app.select_project_tasks(project_id)?);
+
+        match project.tasks {
+            Some(tasks) if !tasks.is_empty() => Ok(format!(
+                "Tasks of '{project_name}':\n{}",
+                formatting::format_task_list(tasks, false, config.table.show_header, config)
+            )),
+            _ => Ok(format!("'{project_name}' has no assigned tasks")),
+        }
+    }
+}
+
+/// Moves a task from one project to another in a toado app. Prompts the user to select the task
+/// and projects if search terms are not provided, or if search terms match multiple items. Returns
+/// the names of the task, and the projects it was moved from and to
+///
+/// # Errors
+///
+/// Will return an error if selection of the task or projects fails, if the task is not assigned to
+/// the project it is moved from, or if moving the task fails
+pub fn move_task(
+    args: flags::MoveArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<(String, String, String), toado::Error> {
+    let theme = get_input_theme();
+
+    let (task_id, task_name) = prompt_select_single_task(args.task, &app, &theme, config)?;
+    let (from_id, from_name) = prompt_select_single_project(args.from, &app, &theme, config)?;
+    let (to_id, to_name) = prompt_select_single_project(args.to, &app, &theme, config)?;
+
+    if !app.move_task(task_id, from_id, to_id)? {
+        return Err(Into::into(format!(
+            "'{task_name}' is not assigned to '{from_name}'"
+        )));
+    }
+
+    Ok((task_name, from_name, to_name))
+}
+
+/// Adds or removes a dependency of one task on another in a toado app. Will prompt user for task
+/// selection if search terms don't match a single task. Returns the names of the dependent task and
+/// the task it depends on
+///
+/// # Errors
+///
+/// Will return an error if task selection fails, if the dependency would create a cycle, or if
+/// removing a dependency that does not exist
+pub fn depend_task(
+    args: flags::DependArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<(String, String), toado::Error> {
+    let theme = get_input_theme();
+
+    let (task_id, task_name) = prompt_select_single_task(args.task, &app, &theme, config)?;
+    let (on_id, on_name) = prompt_select_single_task(args.on, &app, &theme, config)?;
+
+    if args.remove {
+        if app.remove_dependency(task_id, on_id)? == 0 {
+            return Err(Into::into(format!(
+                "'{task_name}' does not depend on '{on_name}'"
+            )));
+        }
+    } else {
+        app.add_dependency(task_id, on_id)?;
+    }
+
+    Ok((task_name, on_name))
+}
+
 //
 // Private Functions
 //
 
+/// Prompts the user to select a single task, returning its id and name
+///
+/// # Errors
+///
+/// Will return an error if selection of the task fails
+fn prompt_select_single_task(
+    term: Option<String>,
+    app: &toado::Server,
+    theme: &dyn dialoguer::theme::Theme,
+    config: &config::Config,
+) -> Result<(i64, String), toado::Error> {
+    match prompt_select_item(term, app, theme, false, false, config)?
+        .tasks()
+        .pop()
+    {
+        Some(toado::Task {
+            id: Some(id),
+            name: Some(name),
+            ..
+        }) => Ok((id, name)),
+        _ => Err(Into::into("task should have id and name")),
+    }
+}
+
+/// Prompts the user to select a single project, returning its id and name
+///
+/// # Errors
+///
+/// Will return an error if selection of the project fails
+fn prompt_select_single_project(
+    term: Option<String>,
+    app: &toado::Server,
+    theme: &dyn dialoguer::theme::Theme,
+    config: &config::Config,
+) -> Result<(i64, String), toado::Error> {
+    match prompt_select_item(term, app, theme, false, true, config)?
+        .projects()
+        .pop()
+    {
+        Some(toado::Project {
+            id: Some(id),
+            name: Some(name),
+            ..
+        }) => Ok((id, name)),
+        _ => Err(Into::into("project should have id and name")),
+    }
+}
+
 /// Parse assign args search terms
 fn parse_search_terms(args: &flags::AssignArgs) -> (Option<String>, Option<String>) {
     let task_term = match (&args.task, &args.task_term) {
@@ -148,23 +345,12 @@ fn match_single_task_and_project(
     task_term: String,
     project_term: String,
     app: &toado::Server,
+    config: &config::Config,
 ) -> Result<(i64, String, i64, String), toado::Error> {
     // Select tasks matching search term
     let tasks = app.select_tasks(
         toado::QueryCols::Some(vec!["id", "name"]),
-        Some(
-            match task_term.parse::<i64>() {
-                Ok(num) => toado::QueryConditions::Equal {
-                    col: "id",
-                    value: num.to_string(),
-                },
-                Err(_) => toado::QueryConditions::Like {
-                    col: "name",
-                    value: format!("'%{task_term}%'"),
-                },
-            }
-            .to_string(),
-        ),
+        Some(term_condition(&task_term, config).to_string()),
         Some(toado::OrderBy::Name),
         None,
         None,
@@ -172,25 +358,13 @@ fn match_single_task_and_project(
     )?;
 
     if tasks.is_empty() {
-        return Err(Into::into(format!("no tasks match '{task_term}'")));
+        return Err(toado::ToadoError::NotFound(format!("no tasks match '{task_term}'")).into());
     }
 
     // Select tasks matching search term
     let projects = app.select_project(
         toado::QueryCols::Some(vec!["id", "name"]),
-        Some(
-            match project_term.parse::<i64>() {
-                Ok(num) => toado::QueryConditions::Equal {
-                    col: "id",
-                    value: num.to_string(),
-                },
-                Err(_) => toado::QueryConditions::Like {
-                    col: "name",
-                    value: format!("'%{project_term}%'"),
-                },
-            }
-            .to_string(),
-        ),
+        Some(term_condition(&project_term, config).to_string()),
         Some(toado::OrderBy::Name),
         None,
         None,
@@ -198,7 +372,9 @@ fn match_single_task_and_project(
     )?;
 
     if projects.is_empty() {
-        return Err(Into::into(format!("no project match '{project_term}'")));
+        return Err(
+            toado::ToadoError::NotFound(format!("no project match '{project_term}'")).into(),
+        );
     }
 
     let task = &tasks[0];
@@ -269,6 +445,45 @@ fn parse_project_names_and_ids(
         .unzip())
 }
 
+/// Id pairs and name pairs of tasks and projects to assign
+type AssignmentPairs = (Vec<(i64, i64)>, Vec<(String, String)>);
+
+/// Pairs every task with every project, skipping pairs of tasks and projects that are already
+/// assigned. Returns the id pairs and name pairs of the new assignments
+fn new_assignment_pairs(
+    (task_ids, task_names): (Vec<i64>, Vec<String>),
+    (project_ids, project_names): (Vec<i64>, Vec<String>),
+    existing: &HashSet<(i64, i64)>,
+) -> AssignmentPairs {
+    create_id_pairs(task_ids, project_ids)
+        .into_iter()
+        .zip(create_name_pairs(task_names, project_names))
+        .filter(|(ids, _)| !existing.contains(ids))
+        .unzip()
+}
+
+/// Return true if creating a number of assignments is confirmed. Creating more than
+/// MAX_UNCONFIRMED_ASSIGNMENTS at once requires confirmation from the user unless skip is true, as
+/// every task is assigned to every project
+///
+/// # Errors
+///
+/// Returns error if getting user input fails
+fn confirm_assignments(
+    count: usize,
+    skip: bool,
+    theme: &dyn dialoguer::theme::Theme,
+) -> Result<bool, toado::Error> {
+    if count <= MAX_UNCONFIRMED_ASSIGNMENTS {
+        return Ok(true);
+    }
+
+    skip_or_confirm(
+        skip,
+        dialoguer::Confirm::with_theme(theme).with_prompt(format!("Create {count} assignments?")),
+    )
+}
+
 /// Create pairs of task ids with project ids
 fn create_id_pairs(task_ids: Vec<i64>, project_ids: Vec<i64>) -> Vec<(i64, i64)> {
     task_ids
@@ -296,3 +511,97 @@ fn create_name_pairs(task_names: Vec<String>, project_names: Vec<String>) -> Vec
         })
         .collect::<Vec<(String, String)>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::tests::{add_projects, add_tasks, parse_command, test_server};
+
+    /// Parses assign command arguments
+    fn assign_args(args: &[&str]) -> flags::AssignArgs {
+        match parse_command(&[&["assign"], args].concat()) {
+            flags::Commands::Assign(args) => args,
+            _ => panic!("assign should parse as the assign command"),
+        }
+    }
+
+    #[test]
+    fn new_assignment_pairs_pair_every_task_and_project() {
+        let ids_and_names = |ids: [i64; 3], prefix: &str| {
+            (
+                ids.to_vec(),
+                ids.iter().map(|id| format!("{prefix} {id}")).collect(),
+            )
+        };
+        let tasks = ids_and_names([1, 2, 3], "task");
+        let projects = ids_and_names([4, 5, 6], "project");
+
+        let (ids, names) = new_assignment_pairs(tasks.clone(), projects.clone(), &HashSet::new());
+        assert_eq!(ids.len(), 9);
+        assert_eq!(names[0], ("task 1".to_string(), "project 4".to_string()));
+
+        let (ids, names) = new_assignment_pairs(tasks, projects, &HashSet::from([(2, 5)]));
+        assert_eq!((ids.len(), names.len()), (8, 8));
+        assert!(!ids.contains(&(2, 5)));
+    }
+
+    #[test]
+    fn many_assignments_require_confirmation() {
+        let theme = get_input_theme();
+
+        assert!(confirm_assignments(9, false, &theme).unwrap());
+        assert!(confirm_assignments(MAX_UNCONFIRMED_ASSIGNMENTS, false, &theme).unwrap());
+        assert!(confirm_assignments(12, true, &theme).unwrap());
+        // Without a terminal to confirm with, prompting for confirmation fails
+        assert!(confirm_assignments(12, false, &theme).is_err());
+    }
+
+    /// Returns a server with a task assigned to two projects, and a task assigned to none
+    fn assigned_server() -> toado::Server {
+        let app = test_server();
+        let task_ids = add_tasks(&app, ["busy", "idle"]);
+        let project_ids = add_projects(&app, ["home", "work", "empty"]);
+        app.batch_assign_tasks(vec![
+            (task_ids[0], project_ids[0]),
+            (task_ids[0], project_ids[1]),
+        ])
+        .unwrap();
+        app
+    }
+
+    #[test]
+    fn list_assignments_lists_projects_of_task() {
+        let config = config::Config::default();
+
+        let listed =
+            list_assignments(assign_args(&["busy", "--list"]), assigned_server(), &config).unwrap();
+        assert!(listed.contains("home") && listed.contains("work"));
+        assert!(!listed.contains("empty"));
+
+        let listed =
+            list_assignments(assign_args(&["idle", "--list"]), assigned_server(), &config).unwrap();
+        assert_eq!(listed, "'idle' is not assigned to any projects");
+    }
+
+    #[test]
+    fn list_assignments_lists_tasks_of_project() {
+        let config = config::Config::default();
+
+        let listed = list_assignments(
+            assign_args(&["-p", "home", "--list"]),
+            assigned_server(),
+            &config,
+        )
+        .unwrap();
+        assert!(listed.contains("busy"));
+        assert!(!listed.contains("idle"));
+
+        let listed = list_assignments(
+            assign_args(&["-p", "empty", "--list"]),
+            assigned_server(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(listed, "'empty' has no assigned tasks");
+    }
+}
@@ -0,0 +1,32 @@
+use std::fs;
+
+use super::*;
+
+/// Exports the items in a toado server as a string in the given format
+///
+/// # Errors
+///
+/// Will return an error if selecting items from the server database fails, or if serializing
+/// them fails
+pub fn export_items(args: flags::ExportArgs, app: toado::Server) -> Result<String, toado::Error> {
+    Ok(match args.format {
+        flags::ExportFormat::Ics => formatting::export_ics(&app.export_database()?.tasks),
+        flags::ExportFormat::Json => serde_json::to_string_pretty(&app.export_database()?)?,
+    })
+}
+
+/// Imports the items in a JSON export file into a toado server. Either all items are imported, or
+/// none are. Returns the number of tasks and projects imported
+///
+/// # Errors
+///
+/// Will return an error if reading or parsing the file fails, or if importing the items fails
+pub fn import_items(
+    args: flags::ImportArgs,
+    app: toado::Server,
+) -> Result<(usize, usize), toado::Error> {
+    let contents = fs::read_to_string(&args.path)?;
+    let data: toado::DatabaseExport = serde_json::from_str(&contents)?;
+
+    app.import_database(data)
+}
@@ -0,0 +1,33 @@
+//! Shell completion script generation
+
+use crate::flags;
+use clap::CommandFactory;
+use std::io;
+
+/// Writes a completion script for a shell, generated from the CLI command definition
+pub fn generate_completions(shell: clap_complete::Shell, buf: &mut dyn io::Write) {
+    clap_complete::generate(shell, &mut flags::Cli::command(), "toado", buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completions_include_every_subcommand() {
+        for shell in [
+            clap_complete::Shell::Bash,
+            clap_complete::Shell::Zsh,
+            clap_complete::Shell::Fish,
+        ] {
+            let mut buf = Vec::new();
+            generate_completions(shell, &mut buf);
+            let script = String::from_utf8(buf).unwrap();
+
+            for subcommand in flags::Cli::command().get_subcommands() {
+                assert!(script.contains(subcommand.get_name()));
+            }
+            assert!(script.contains("due-before"));
+        }
+    }
+}
@@ -12,6 +12,11 @@ pub fn create_project(
 ) -> Result<(i64, String), toado::Error> {
     let theme = get_input_theme();
 
+    // Validate time arguments before prompting for input
+    for time in [&args.start_time, &args.end_time].into_iter().flatten() {
+        validate_time(time)?;
+    }
+
     // Get user Input
 
     let name = option_or_input(
@@ -26,8 +31,12 @@ pub fn create_project(
     } else {
         option_or_input_option(
             args.start_time,
-            dialoguer::Input::with_theme(&theme).with_prompt("Start Time (optional)"),
+            dialoguer::Input::with_theme(&theme)
+                .with_prompt("Start Time (optional)")
+                .validate_with(|input: &String| validate_time(input)),
         )?
+        .map(canonical_time)
+        .transpose()?
     };
 
     let end_time = if args.optional {
@@ -35,9 +44,15 @@ pub fn create_project(
     } else {
         option_or_input_option(
             args.end_time,
-            dialoguer::Input::with_theme(&theme).with_prompt("End Time (optional)"),
+            dialoguer::Input::with_theme(&theme)
+                .with_prompt("End Time (optional)")
+                .validate_with(|input: &String| validate_time(input)),
         )?
+        .map(canonical_time)
+        .transpose()?
     };
+    validate_time_order(start_time.as_deref(), end_time.as_deref())
+        .map_err(toado::ToadoError::Input)?;
 
     let notes = if args.optional {
         None
@@ -64,14 +79,25 @@ pub fn create_project(
 ///
 /// # Errors
 ///
-/// Will return an error if user input fails, or if project updating fails
+/// Will return an error if user input fails, if project updating fails, or if no project matches
+/// the update
 pub fn update_project(
-    args: flags::UpdateArgs,
+    mut args: flags::UpdateArgs,
     app: toado::Server,
     config: &config::Config,
 ) -> Result<u64, toado::Error> {
     let theme = dialoguer::theme::ColorfulTheme::default();
 
+    // Resolve time arguments before prompting for input
+    for time in [&mut args.start_time, &mut args.end_time]
+        .into_iter()
+        .flatten()
+    {
+        if let flags::NullableString::Some(time) = time {
+            *time = canonical_time(time.clone())?;
+        }
+    }
+
     let search_term = option_or_input(
         args.term.clone(),
         dialoguer::Input::with_theme(&theme).with_prompt("Project name"),
@@ -97,10 +123,14 @@ pub fn update_project(
     }
     .to_string();
 
-    let (name, start_time, end_time, notes) = if args.has_project_update_values() {
+    let current_start_time = project.start_time.clone();
+    let current_end_time = project.end_time.clone();
+
+    let (name, status, start_time, end_time, notes) = if args.has_project_update_values() {
         // If update values are set by command arguments, use those values
         (
             toado::UpdateAction::from(args.name),
+            toado::UpdateAction::from(args.status),
             nullable_into_update_action(args.start_time),
             nullable_into_update_action(args.end_time),
             nullable_into_update_action(args.notes),
@@ -126,12 +156,14 @@ pub fn update_project(
 
         let start_time: String = dialoguer::Input::with_theme(&theme)
             .with_prompt("Start Time (optional)")
+            .validate_with(|input: &String| validate_time(input))
             .with_initial_text(current_start_time)
             .allow_empty(true)
             .interact_text()?;
 
         let end_time: String = dialoguer::Input::with_theme(&theme)
             .with_prompt("End Time (optional)")
+            .validate_with(|input: &String| validate_time(input))
             .with_initial_text(current_end_time)
             .allow_empty(true)
             .interact_text()?;
@@ -144,13 +176,129 @@ pub fn update_project(
 
         (
             toado::UpdateAction::Some(name),
-            toado::UpdateAction::from(start_time),
-            toado::UpdateAction::from(end_time),
+            toado::UpdateAction::None,
+            toado::UpdateAction::from(canonical_time(start_time)?),
+            toado::UpdateAction::from(canonical_time(end_time)?),
             toado::UpdateAction::from(notes),
         )
     };
 
-    app.update_project(Some(condition), name, start_time, end_time, notes)
+    validate_time_order(
+        updated_time(&start_time, current_start_time).as_deref(),
+        updated_time(&end_time, current_end_time).as_deref(),
+    )
+    .map_err(toado::ToadoError::Input)?;
+
+    let affected_rows =
+        app.update_project(Some(condition), name, status, start_time, end_time, notes)?;
+
+    matched_rows(affected_rows, "projects")
+}
+
+/// Sets the completion status of a project in a toado server. Returns the name of the project and
+/// its new status
+///
+/// # Errors
+///
+/// Will return an error if more than one search term is given, if user input fails, or if updating
+/// the project fails
+pub fn check_project(
+    args: flags::CheckArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<(String, toado::ItemStatus), toado::Error> {
+    let theme = dialoguer::theme::ColorfulTheme::default();
+    let new_status = args.new_status();
+
+    if args.term.len() > 1 {
+        return Err(toado::ToadoError::Input(
+            "only one project can be checked at a time".to_string(),
+        )
+        .into());
+    }
+
+    let search_term = option_or_input(
+        args.term.into_iter().next(),
+        dialoguer::Input::with_theme(&theme).with_prompt("Project name"),
+    )?;
+
+    let project = prompt_project_selection(
+        &app,
+        search_term,
+        toado::QueryCols::Some(vec!["id", "name", "status"]),
+        &theme,
+        config,
+    )?;
+
+    let (id, name) = match (project.id, project.name) {
+        (Some(id), Some(name)) => (id, name),
+        _ => return Err(Into::into("project should have id and name")),
+    };
+
+    let affected_rows = app.update_project_status(id, new_status)?;
+    matched_rows(affected_rows, "projects")?;
+
+    Ok((name, new_status))
+}
+
+/// Deletes all projects in a toado server database. Requires the user to type DELETE to confirm,
+/// unless the yes flag is set. Returns the number of deleted projects
+///
+/// # Errors
+///
+/// Will return an error if the confirmation fails or does not match, or if the deletion fails
+pub fn delete_all_projects(
+    args: flags::DeleteArgs,
+    app: toado::Server,
+) -> Result<u64, toado::Error> {
+    skip_or_confirm_delete_all(args.yes, "projects")?;
+    app.delete_project(None)
+}
+
+/// Selects the projects a delete or update would affect without modifying them. Searches for a
+/// project with given search term, or prompts user for search term if one is not provided. If all
+/// is true, matches every project instead. Returns the ids and names of the matched projects
+///
+/// # Errors
+///
+/// Will return an error if user input fails, or if selecting projects fails
+pub fn preview_project_matches(
+    term: Option<String>,
+    all: bool,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Vec<(i64, String)>, toado::Error> {
+    if all {
+        return app.select_project_matches(None);
+    }
+
+    let theme = dialoguer::theme::ColorfulTheme::default();
+
+    let search_term = option_or_input(
+        term,
+        dialoguer::Input::with_theme(&theme).with_prompt("Project name"),
+    )?;
+
+    let project = prompt_project_selection(
+        &app,
+        search_term,
+        toado::QueryCols::Some(vec!["id", "name", "start_time"]),
+        &theme,
+        config,
+    )?;
+
+    let id = match project.id {
+        Some(id) => id,
+        None => return Err(Into::into("project id should exist")),
+    };
+
+    app.select_project_matches(Some(
+        toado::QueryConditions::Equal {
+            col: "id",
+            value: id,
+        }
+        .to_string(),
+    ))
 }
 
 pub fn delete_project(
@@ -179,6 +327,15 @@ pub fn delete_project(
         None => return Err(Into::into("project id should exist")),
     };
 
+    // Confirm deletion, unless skipped by the yes flag
+    let name = project.name.unwrap_or(id.to_string());
+    if !skip_or_confirm(
+        args.yes,
+        dialoguer::Confirm::with_theme(&theme).with_prompt(format!("Delete project '{name}'?")),
+    )? {
+        return Ok(None);
+    }
+
     let affected_rows = app.delete_project(Some(
         toado::QueryConditions::Equal {
             col: "id",
@@ -187,11 +344,9 @@ pub fn delete_project(
         .to_string(),
     ))?;
 
-    if affected_rows >= 1 {
-        Ok(Some(id))
-    } else {
-        Err(Into::into("no project deleted"))
-    }
+    matched_rows(affected_rows, "projects")?;
+
+    Ok(Some(id))
 }
 
 /// Get a list of projects from a toado app server
@@ -205,60 +360,216 @@ pub fn list_projects(
     app: toado::Server,
     config: &config::Config,
 ) -> Result<Option<String>, toado::Error> {
-    let (cols, order_by, order_dir, limit, offset) = parse_list_args(&args);
-
-    let projects = app.select_project(cols, None, order_by, order_dir, limit, offset)?;
+    let (cols, condition, order_by, order_dir, limit, offset) = parse_list_args(&args, config)?;
+
+    let projects = app.select_project(
+        cols.clone(),
+        condition.clone(),
+        order_by,
+        order_dir,
+        limit,
+        offset,
+    )?;
     let num_projects = projects.len();
 
-    let mut table_string = formatting::format_project_list(projects, args.verbose, &config.table);
+    // Verbose lists display the progress of each project
+    let projects = match cols {
+        toado::QueryCols::All => with_progress(projects, &app)?,
+        _ => projects,
+    };
+
+    match args.format {
+        flags::OutputFormat::Json => return Ok(Some(json_list(&projects, &cols)?)),
+        flags::OutputFormat::Ndjson => return Ok(Some(ndjson_list(&projects, &cols)?)),
+        flags::OutputFormat::Tsv => {
+            let cols = col_names(&cols, toado::Tables::Projects);
+            return Ok(Some(tsv_list(&projects, &cols, args.header)?));
+        }
+        flags::OutputFormat::Plain => {
+            let cols = match args.columns.is_some() || args.verbose {
+                true => col_names(&cols, toado::Tables::Projects),
+                false => vec!["id", "name"],
+            };
+            return Ok(Some(plain_list(&projects, &cols)?));
+        }
+        flags::OutputFormat::Table => {}
+    }
+
+    // An empty table displays only its footer, so describe the empty list instead
+    if projects.is_empty() {
+        return Ok(Some("No projects found".to_string()));
+    }
+
+    let mut table_string = formatting::format_project_table(projects, &cols, args.header, config);
 
     // If not selecting all projects, display number of tasks selected
     if !args.full {
         table_string.push_str(&list_footer(
             offset,
             num_projects,
-            app.get_table_row_count(toado::Tables::Projects)?,
+            app.get_row_count(toado::Tables::Projects, condition)?,
         ));
     }
 
     Ok(Some(table_string))
 }
 
+/// Searches for a project in a toado server database with provided search terms. If a single term
+/// is given and is a positive integer, searches by project id, otherwise searches for projects
+/// with names containing all terms. If searching notes, terms may be contained in either the name
+/// or notes. If regex search is enabled, matches projects against the terms as a regular expression
+///
+/// # Errors
+///
+/// Will return an error if the regular expression is invalid, or if project selection fails
+pub fn search_projects(
+    args: flags::SearchArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    let condition = search_condition(&args, config);
+
+    // If matching a regular expression, filter all projects and page through matches. Otherwise
+    // select matching projects from the database
+    let (projects, regex_total) = match search_regex(&args, config)? {
+        Some(regex) => {
+            let projects: Vec<toado::Project> = app
+                .select_project(
+                    toado::QueryCols::All,
+                    None,
+                    Some(toado::OrderBy::Id),
+                    None,
+                    Some(toado::RowLimit::All),
+                    None,
+                )?
+                .into_iter()
+                .filter(|project| {
+                    regex_matches(
+                        &regex,
+                        project.name.as_deref(),
+                        project.notes.as_deref(),
+                        args.in_notes,
+                    )
+                })
+                .collect();
+            let total = projects.len();
+            (page_items(projects, args.limit, args.offset), Some(total))
+        }
+        None => (
+            app.select_project(
+                toado::QueryCols::All,
+                Some(condition.to_string()),
+                Some(toado::OrderBy::Id),
+                None,
+                Some(
+                    args.limit
+                        .map_or(toado::RowLimit::All, toado::RowLimit::Limit),
+                ),
+                args.offset,
+            )?,
+            None,
+        ),
+    };
+    let projects = with_progress(projects, &app)?;
+
+    // If paging through results, always display results as a list
+    let paged = args.limit.is_some() || args.offset.is_some();
+
+    if args.format == flags::OutputFormat::Json {
+        // A single match is displayed as an object, same as with table formatting
+        Ok(Some(if projects.len() == 1 && !paged {
+            serde_json::to_string(&projects[0])?
+        } else {
+            serde_json::to_string(&projects)?
+        }))
+    } else if args.format == flags::OutputFormat::Tsv {
+        Ok(Some(tsv_list(
+            &projects,
+            toado::Tables::Projects.columns(),
+            false,
+        )?))
+    } else if args.format == flags::OutputFormat::Ndjson {
+        Ok(Some(ndjson_list(&projects, &toado::QueryCols::All)?))
+    } else if args.format == flags::OutputFormat::Plain {
+        Ok(Some(plain_list(&projects, &["id", "name"])?))
+    } else if projects.is_empty() {
+        Ok(None)
+    } else if projects.len() == 1 && !paged {
+        Ok(Some(formatting::format_project(
+            projects[0].clone(),
+            config,
+        )))
+    } else {
+        let num_projects = projects.len();
+        let mut table_string = formatting::format_project_list(
+            projects,
+            args.verbose,
+            config.table.show_header,
+            config,
+        );
+
+        // Display number of projects selected out of all matching projects
+        table_string.push_str(&list_footer(
+            args.offset,
+            num_projects,
+            match regex_total {
+                Some(total) => total,
+                None => app.get_row_count(toado::Tables::Projects, Some(condition.to_string()))?,
+            },
+        ));
+
+        Ok(Some(table_string))
+    }
+}
+
 //
 // Private Methods
 //
 
+/// Sets the progress of projects from the statuses of their assigned tasks. Projects without
+/// assigned tasks have a progress of 0/0
+///
+/// # Errors
+///
+/// Will return an error if selecting the progress of projects fails
+fn with_progress(
+    mut projects: Vec<toado::Project>,
+    app: &toado::Server,
+) -> Result<Vec<toado::Project>, toado::Error> {
+    let progress = app.select_project_progress()?;
+    for project in &mut projects {
+        project.progress = Some(
+            project
+                .id
+                .and_then(|id| progress.get(&id).copied())
+                .unwrap_or_default(),
+        );
+    }
+
+    Ok(projects)
+}
+
 /// Selects projects from an application database given a search term. If multiple projects match the
 /// term, prompts the user to select one of the matching projects and returns it. If one project matches
-/// inputed name, returns said project
+/// inputed name, returns said project. If fuzzy search is enabled and no project names contain the
+/// term, prompts the user to select from the closest fuzzy matches
 ///
 /// # Errors
 ///
 /// Will return an error if no projects match the search term
-fn prompt_project_selection(
+pub(super) fn prompt_project_selection(
     app: &toado::Server,
     search_term: String,
     cols: toado::QueryCols,
     theme: &dyn dialoguer::theme::Theme,
     config: &config::Config,
 ) -> Result<toado::Project, toado::Error> {
-    let select_condition = match search_term.parse::<usize>() {
-        // If search term is number, select by id
-        Ok(num) => toado::QueryConditions::Equal {
-            col: "id",
-            value: num.to_string(),
-        },
-        // If search term is not number, select by name
-        Err(_) => toado::QueryConditions::Like {
-            col: "name",
-            value: format!("'%{search_term}%'"),
-        },
-    };
+    let select_condition = term_condition(&search_term, config);
 
     // Get tasks matching name argument
     let mut projects = app.select_project(
         // toado::QueryCols::Some(vec!["id", "name", "priority", "status"]),
-        cols,
+        cols.clone(),
         Some(select_condition.to_string()),
         Some(toado::OrderBy::Name),
         None,
@@ -266,19 +577,46 @@ fn prompt_project_selection(
         None,
     )?;
 
-    // If no tasks match search term, return error
+    let mut fuzzy = false;
+    if config.search.fuzzy && search_term.parse::<usize>().is_err() {
+        if projects.is_empty() {
+            // If no names contain the search term, fall back to the closest fuzzy matches
+            projects = fuzzy_rank(
+                app.select_project(
+                    cols,
+                    None,
+                    Some(toado::OrderBy::Name),
+                    None,
+                    Some(toado::RowLimit::All),
+                    None,
+                )?,
+                &search_term,
+                |project| project.name.as_ref(),
+            );
+            projects.truncate(MAX_FUZZY_MATCHES);
+            fuzzy = true;
+        } else if projects.len() > MAX_FUZZY_MATCHES {
+            // Order large numbers of matches by relevance
+            projects = fuzzy_rank(projects, &search_term, |project| project.name.as_ref());
+        }
+    }
+
+    // If no projects match search term, return error
     if projects.is_empty() {
-        return Err(Into::into(format!("no project matches {search_term}")));
+        return Err(
+            toado::ToadoError::NotFound(format!("no project matches {search_term}")).into(),
+        );
     }
 
-    if projects.len() == 1 {
+    // Fuzzy matches are never selected without confirmation from the user
+    if projects.len() == 1 && !fuzzy {
         Ok(projects.remove(0))
     }
     // If multiple tasks match name argument, prompt user to select one
     else {
         // Format matching tasks into vector of strings
         let project_strings: Vec<String> =
-            formatting::format_project_list(projects.clone(), false, &config.table)
+            formatting::format_project_list(projects.clone(), false, false, config)
                 .split('\n')
                 .map(|line| line.to_string())
                 .collect();
@@ -295,3 +633,81 @@ fn prompt_project_selection(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::tests::{add_projects, parse_command, temp_path, test_server};
+
+    /// Parses delete command arguments
+    fn delete_args(args: &[&str]) -> flags::DeleteArgs {
+        match parse_command(&[&["delete"], args].concat()) {
+            flags::Commands::Delete(args) => args,
+            _ => panic!("delete should parse as the delete command"),
+        }
+    }
+
+    #[test]
+    fn delete_project_with_yes_deletes_without_prompting() {
+        let config = config::Config::default();
+        let db_path = temp_path("delete-project.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let ids = add_projects(&app, ["doomed", "spared"]);
+
+        let deleted =
+            delete_project(delete_args(&["doomed", "--project", "--yes"]), app, &config).unwrap();
+        assert_eq!(deleted, Some(ids[0]));
+
+        let app = toado::Server::open(&db_path).unwrap();
+        assert!(app.get_project_by_id(ids[0]).unwrap().is_none());
+        assert!(app.get_project_by_id(ids[1]).unwrap().is_some());
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn check_project_sets_status_used_by_list_filter() {
+        let config = config::Config::default();
+        let db_path = temp_path("project-status.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let ids = add_projects(&app, ["finished", "ongoing"]);
+
+        let flags::Commands::Check(args) = parse_command(&["check", "-p", "finished", "--archive"])
+        else {
+            panic!("check should parse as the check command");
+        };
+        let (name, status) =
+            check_project(args, toado::Server::open(&db_path).unwrap(), &config).unwrap();
+        assert_eq!(name, "finished");
+        assert!(matches!(status, toado::ItemStatus::Archived));
+
+        let list = |status: &str| {
+            let flags::Commands::Ls(args) =
+                parse_command(&["ls", "-p", "--status", status, "--format", "plain"])
+            else {
+                panic!("ls should parse as the list command");
+            };
+            list_projects(args, toado::Server::open(&db_path).unwrap(), &config)
+                .unwrap()
+                .unwrap_or_default()
+        };
+        assert_eq!(list("archived"), format!("{}\tfinished", ids[0]));
+        assert_eq!(list("incomplete"), format!("{}\tongoing", ids[1]));
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn list_projects_describes_empty_lists() {
+        let config = config::Config::default();
+        let list = |args: &[&str]| {
+            let flags::Commands::Ls(args) = parse_command(&[&["ls", "-p"], args].concat()) else {
+                panic!("ls should parse as the list command");
+            };
+            list_projects(args, test_server(), &config).unwrap()
+        };
+
+        assert_eq!(list(&[]).as_deref(), Some("No projects found"));
+        assert_eq!(list(&["--format", "json"]).as_deref(), Some("[]"));
+    }
+}
@@ -1,23 +1,66 @@
 use crate::config;
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    io::Write,
+    path, process,
+};
 
 use super::*;
 
 /// Creates a new task in a toado server with provided arguments. Prompts the user to input any task
-/// information not provided in the arguments.
+/// information not provided in the arguments. If a project to assign the task to is provided, the
+/// task is assigned to it when created. Returns the id and name of the task, and the name of the
+/// project it is assigned to
 ///
 /// # Errors
-/// Will return an error if any of the user input prompts fail, or if the creation of the task
-/// fails.
+/// Will return an error if any of the user input prompts fail, if no project matches the project to
+/// assign to, or if the creation of the task fails.
 pub fn create_task(
     args: flags::AddArgs,
     app: toado::Server,
-) -> Result<(i64, String), toado::Error> {
+    config: &config::Config,
+) -> Result<(i64, String, Option<String>), toado::Error> {
     let theme = get_input_theme();
 
+    // Validate parent task and time arguments before prompting for input
+    if let Some(parent_id) = args.parent {
+        validate_task_id(parent_id, &app)?;
+    }
+    for time in [&args.start_time, &args.end_time].into_iter().flatten() {
+        validate_time(time)?;
+    }
+    if let Some(repeat) = &args.repeat {
+        validate_repeat(repeat)?;
+    }
+
+    // Select the project to assign to before adding the task, so nothing is added if no project
+    // matches
+    let project = match args.assign {
+        Some(term) => match prompt_project_selection(
+            &app,
+            term,
+            toado::QueryCols::Some(vec!["id", "name"]),
+            &theme,
+            config,
+        )? {
+            toado::Project {
+                id: Some(id),
+                name: Some(name),
+                ..
+            } => Some((id, name)),
+            _ => return Err(Into::into("project should have id and name")),
+        },
+        None => None,
+    };
+
+    let completion = NameCompletion::tasks(args.name.is_none(), &app, config)?;
     let name = option_or_input(
         args.name,
         dialoguer::Input::with_theme(&theme)
             .with_prompt("Name")
+            .completion_with(&completion)
             .validate_with(|input: &String| validate_name(input)),
     )?;
 
@@ -33,8 +76,12 @@ pub fn create_task(
     } else {
         option_or_input_option(
             args.start_time,
-            dialoguer::Input::with_theme(&theme).with_prompt("Start Time (optional)"),
+            dialoguer::Input::with_theme(&theme)
+                .with_prompt("Start Time (optional)")
+                .validate_with(|input: &String| validate_time(input)),
         )?
+        .map(canonical_time)
+        .transpose()?
     };
 
     let end_time = if args.optional {
@@ -42,17 +89,27 @@ pub fn create_task(
     } else {
         option_or_input_option(
             args.end_time,
-            dialoguer::Input::with_theme(&theme).with_prompt("End Time (optional)"),
+            dialoguer::Input::with_theme(&theme)
+                .with_prompt("End Time (optional)")
+                .validate_with(|input: &String| validate_time(input)),
         )?
+        .map(canonical_time)
+        .transpose()?
     };
+    validate_time_order(start_time.as_deref(), end_time.as_deref())
+        .map_err(toado::ToadoError::Input)?;
 
     let repeat = if args.optional {
         None
     } else {
         option_or_input_option(
             args.repeat,
-            dialoguer::Input::with_theme(&theme).with_prompt("Repeats (optional)"),
+            dialoguer::Input::with_theme(&theme)
+                .with_prompt("Repeats (optional)")
+                .validate_with(|input: &String| validate_repeat(input)),
         )?
+        .map(canonical_repeat)
+        .transpose()?
     };
 
     let notes = if args.optional {
@@ -64,7 +121,7 @@ pub fn create_task(
         )?
     };
 
-    let task_id = app.add_task(toado::AddTaskArgs {
+    let task_args = toado::AddTaskArgs {
         name: String::from(&name),
         priority,
         status: toado::ItemStatus::Incomplete,
@@ -72,13 +129,70 @@ pub fn create_task(
         end_time,
         repeat,
         notes,
-    })?;
+        parent_id: args.parent,
+        owner: config.user.owner(),
+    };
+
+    let task_id = match &project {
+        Some((project_id, _)) => app.add_assigned_task(task_args, *project_id)?,
+        None => app.add_task(task_args)?,
+    };
+
+    Ok((task_id, name, project.map(|(_, project_name)| project_name)))
+}
+
+/// Creates new tasks in a toado server from a file. If the file has a .toml extension, tasks are
+/// read from its tasks array, otherwise each non-empty line of the file is used as a task name.
+/// Either all tasks are created, or none are. Returns the number of tasks created
+///
+/// # Errors
+///
+/// Will return an error if reading or parsing the file fails, if any task has invalid values, or
+/// if the creation of the tasks fails
+pub fn import_tasks(
+    path: &str,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<usize, toado::Error> {
+    let contents = fs::read_to_string(path)?;
+
+    let tasks = if path::Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension == "toml")
+    {
+        toml::from_str::<ImportFile>(&contents)?.tasks
+    } else {
+        contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| ImportTask {
+                name: line.to_string(),
+                priority: None,
+                start_time: None,
+                end_time: None,
+                repeat: None,
+                notes: None,
+            })
+            .collect()
+    };
 
-    Ok((task_id, name))
+    // Validate all tasks before adding any
+    let tasks = tasks
+        .into_iter()
+        .enumerate()
+        .map(|(i, task)| {
+            task.into_add_args(config.user.owner())
+                .map_err(|e| toado::ToadoError::Input(format!("task {}: {e}", i + 1)))
+        })
+        .collect::<Result<Vec<toado::AddTaskArgs>, toado::ToadoError>>()?;
+
+    Ok(app.add_tasks(tasks)?.len())
 }
 
 /// Deletes a task in a toado server database. Searches for task to delete with given search term,
-/// or prompts user for search term if one is not provided
+/// or prompts user for search term if one is not provided. Asks the user to confirm the deletion
+/// unless the yes flag is set. Returns the id of the deleted task, or None if cancelled
 ///
 /// # Errors
 ///
@@ -91,9 +205,12 @@ pub fn delete_task(
 ) -> Result<Option<i64>, toado::Error> {
     let theme = dialoguer::theme::ColorfulTheme::default();
 
+    let completion = NameCompletion::tasks(args.term.is_none(), &app, config)?;
     let search_term = option_or_input(
         args.term,
-        dialoguer::Input::with_theme(&theme).with_prompt("Task name"),
+        dialoguer::Input::with_theme(&theme)
+            .with_prompt("Task name")
+            .completion_with(&completion),
     )?;
 
     let task = prompt_task_selection(
@@ -110,6 +227,15 @@ pub fn delete_task(
         None => return Err(Into::into("task id should exist")),
     };
 
+    // Confirm deletion, unless skipped by the yes flag
+    let name = task.name.unwrap_or(id.to_string());
+    if !skip_or_confirm(
+        args.yes,
+        dialoguer::Confirm::with_theme(&theme).with_prompt(format!("Delete task '{name}'?")),
+    )? {
+        return Ok(None);
+    }
+
     let affected_rows = app.delete_task(Some(
         toado::QueryConditions::Equal {
             col: "id",
@@ -117,29 +243,140 @@ pub fn delete_task(
         }
         .to_string(),
     ))?;
+    matched_rows(affected_rows, "tasks")?;
 
-    if affected_rows >= 1 {
-        Ok(Some(id))
-    } else {
-        Err(Into::into("no tasks deleted"))
+    Ok(Some(id))
+}
+
+/// Deletes all tasks in a toado server database. Requires the user to type DELETE to confirm,
+/// unless the yes flag is set. Returns the number of deleted tasks
+///
+/// # Errors
+///
+/// Will return an error if the confirmation fails or does not match, or if the deletion fails
+pub fn delete_all_tasks(args: flags::DeleteArgs, app: toado::Server) -> Result<u64, toado::Error> {
+    skip_or_confirm_delete_all(args.yes, "tasks")?;
+    app.delete_task(None)
+}
+
+/// Permanently deletes all archived tasks in a toado server database. Prompts the user to confirm,
+/// unless the yes flag is set or there are no archived tasks. Returns the number of deleted tasks,
+/// or None if the purge is cancelled
+///
+/// # Errors
+///
+/// Will return an error if counting archived tasks fails, if user input fails, or if the deletion
+/// fails
+pub fn purge_tasks(
+    args: flags::PurgeArgs,
+    app: toado::Server,
+) -> Result<Option<u64>, toado::Error> {
+    let theme = dialoguer::theme::ColorfulTheme::default();
+
+    let condition = toado::QueryConditions::Equal {
+        col: "status",
+        value: u32::from(toado::ItemStatus::Archived),
+    }
+    .to_string();
+
+    let count = app.get_row_count(toado::Tables::Tasks, Some(condition.clone()))?;
+    if count == 0 {
+        return Ok(Some(0));
+    }
+
+    if !skip_or_confirm(
+        args.yes,
+        dialoguer::Confirm::with_theme(&theme)
+            .with_prompt(format!("Permanently delete {count} archived task(s)?")),
+    )? {
+        return Ok(None);
+    }
+
+    Ok(Some(app.delete_task(Some(condition))?))
+}
+
+/// Selects the tasks a delete or update would affect without modifying them. Searches for a task
+/// with given search term, or prompts user for search term if one is not provided. If all is true,
+/// matches every task instead. Returns the ids and names of the matched tasks
+///
+/// # Errors
+///
+/// Will return an error if user input fails, or if selecting tasks fails
+pub fn preview_task_matches(
+    term: Option<String>,
+    all: bool,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Vec<(i64, String)>, toado::Error> {
+    if all {
+        return app.select_task_matches(None);
     }
+
+    let theme = dialoguer::theme::ColorfulTheme::default();
+
+    let completion = NameCompletion::tasks(term.is_none(), &app, config)?;
+    let search_term = option_or_input(
+        term,
+        dialoguer::Input::with_theme(&theme)
+            .with_prompt("Task name")
+            .completion_with(&completion),
+    )?;
+
+    let task = prompt_task_selection(
+        &app,
+        search_term,
+        toado::QueryCols::Some(vec!["id", "name", "priority", "status"]),
+        &theme,
+        config,
+    )?;
+
+    let id = match task.id {
+        Some(id) => id,
+        None => return Err(Into::into("task id should exist")),
+    };
+
+    app.select_task_matches(Some(
+        toado::QueryConditions::Equal {
+            col: "id",
+            value: id,
+        }
+        .to_string(),
+    ))
 }
 
 /// Update a task in a toado server
 ///
 /// # Errors
 ///
-/// Will return an error if user input fails, if task updating fails, or if no task is updated
+/// Will return an error if user input fails, if task updating fails, or if no task matches the
+/// update
 pub fn update_task(
-    args: flags::UpdateArgs,
+    mut args: flags::UpdateArgs,
     app: toado::Server,
     config: &config::Config,
 ) -> Result<u64, toado::Error> {
     let theme = dialoguer::theme::ColorfulTheme::default();
 
+    // Resolve time arguments before prompting for input
+    for time in [&mut args.start_time, &mut args.end_time]
+        .into_iter()
+        .flatten()
+    {
+        if let flags::NullableString::Some(time) = time {
+            *time = canonical_time(time.clone())?;
+        }
+    }
+    if let Some(flags::NullableString::Some(repeat)) = &args.repeat {
+        let repeat = canonical_repeat(repeat.clone())?;
+        args.repeat = Some(flags::NullableString::Some(repeat));
+    }
+
+    let completion = NameCompletion::tasks(args.term.is_none(), &app, config)?;
     let search_term = option_or_input(
         args.term.clone(),
-        dialoguer::Input::with_theme(&theme).with_prompt("Task name"),
+        dialoguer::Input::with_theme(&theme)
+            .with_prompt("Task name")
+            .completion_with(&completion),
     )?;
 
     let task = prompt_task_selection(
@@ -156,12 +393,23 @@ pub fn update_task(
         None => return Err(Into::into("task id should exist")),
     };
 
-    let (name, priority, start_time, end_time, repeat, notes) = {
+    // Append to the current task notes, seperated by a new line
+    if let Some(text) = args.append_notes.take() {
+        let current_notes = app.get_task_by_id(task_id)?.and_then(|task| task.notes);
+
+        args.notes = Some(flags::NullableString::Some(match current_notes {
+            Some(notes) if !notes.is_empty() => format!("{notes}\n{text}"),
+            _ => text,
+        }));
+    }
+
+    let (name, priority, status, start_time, end_time, repeat, notes) = {
         if args.has_task_update_values() {
             // If update values are set by command arguments, use those values
             (
                 toado::UpdateAction::from(args.name),
                 toado::UpdateAction::from(args.item_priority),
+                toado::UpdateAction::from(args.status),
                 nullable_into_update_action(args.start_time),
                 nullable_into_update_action(args.end_time),
                 nullable_into_update_action(args.repeat),
@@ -199,18 +447,21 @@ pub fn update_task(
 
             let start_time: String = dialoguer::Input::with_theme(&theme)
                 .with_prompt("Start Time (optional)")
+                .validate_with(|input: &String| validate_time(input))
                 .with_initial_text(current_start_time)
                 .allow_empty(true)
                 .interact_text()?;
 
             let end_time: String = dialoguer::Input::with_theme(&theme)
                 .with_prompt("End Time (optional)")
+                .validate_with(|input: &String| validate_time(input))
                 .with_initial_text(current_end_time)
                 .allow_empty(true)
                 .interact_text()?;
 
             let repeat: String = dialoguer::Input::with_theme(&theme)
                 .with_prompt("Repeat (optional)")
+                .validate_with(|input: &String| validate_repeat(input))
                 .with_initial_text(current_repeat)
                 .allow_empty(true)
                 .interact_text()?;
@@ -221,26 +472,33 @@ pub fn update_task(
                 .allow_empty(true)
                 .interact_text()?;
 
-            fn string_to_update_action(s: String) -> toado::UpdateAction<String> {
-                if s.is_empty() {
-                    toado::UpdateAction::Null
-                } else {
-                    toado::UpdateAction::Some(format!("'{s}'"))
-                }
-            }
-
             (
                 toado::UpdateAction::Some(name),
                 toado::UpdateAction::Some(priority),
-                string_to_update_action(start_time),
-                string_to_update_action(end_time),
-                string_to_update_action(repeat),
-                string_to_update_action(notes),
+                toado::UpdateAction::None,
+                toado::UpdateAction::from(canonical_time(start_time)?),
+                toado::UpdateAction::from(canonical_time(end_time)?),
+                toado::UpdateAction::from(canonical_repeat(repeat)?),
+                toado::UpdateAction::from(notes),
             )
         }
     };
 
-    app.update_task(
+    // Compare updated times with the current times of the task, if either is updated
+    if !start_time.is_none() || !end_time.is_none() {
+        let (current_start_time, current_end_time) = app
+            .get_task_by_id(task_id)?
+            .map(|task| (task.start_time, task.end_time))
+            .unwrap_or_default();
+
+        validate_time_order(
+            updated_time(&start_time, current_start_time).as_deref(),
+            updated_time(&end_time, current_end_time).as_deref(),
+        )
+        .map_err(toado::ToadoError::Input)?;
+    }
+
+    let affected_rows = app.update_task(
         Some(
             toado::QueryConditions::Equal {
                 col: "id",
@@ -251,58 +509,232 @@ pub fn update_task(
         toado::UpdateTaskArgs {
             name,
             priority,
-            status: toado::UpdateAction::None,
+            status,
             start_time,
             end_time,
             repeat,
             notes,
         },
-    )
+    )?;
+
+    matched_rows(affected_rows, "tasks")
+}
+
+/// Copies a task in a toado server. Prompts the user to select the task if a search term is not
+/// provided, or if multiple tasks match it. Returns the name of the copied task and the ids of the
+/// copies
+///
+/// # Errors
+///
+/// Will return an error if the number of copies is zero, if task selection fails, or if adding the
+/// copies fails
+pub fn clone_task(
+    args: flags::CloneArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<(String, Vec<i64>), toado::Error> {
+    if args.times == 0 {
+        return Err(
+            toado::ToadoError::Input("number of copies must be at least 1".to_string()).into(),
+        );
+    }
+
+    let theme = get_input_theme();
+
+    let completion = NameCompletion::tasks(args.term.is_none(), &app, config)?;
+    let search_term = option_or_input(
+        args.term,
+        dialoguer::Input::with_theme(&theme)
+            .with_prompt("Task name")
+            .completion_with(&completion),
+    )?;
+
+    let task = prompt_task_selection(
+        &app,
+        search_term,
+        toado::QueryCols::Some(vec!["id", "name", "priority", "status"]),
+        &theme,
+        config,
+    )?;
+
+    let (id, name) = match (task.id, task.name) {
+        (Some(id), Some(name)) => (id, name),
+        _ => return Err(Into::into("task id and name should exist")),
+    };
+
+    let ids = app.clone_task(id, args.times, args.with_assignments)?;
+    Ok((name, ids))
+}
+
+/// Edits a task in a text editor. The selected task is written to a temporary TOML file and
+/// opened with the editor set by $VISUAL or $EDITOR. Once the editor exits, the edited values are
+/// applied to the task. Returns the name of the updated task, or None if the file was left empty
+/// or unchanged
+///
+/// # Errors
+///
+/// Will return an error if the editor fails, if the edited file is not a valid task, or if updating
+/// the task fails
+pub fn edit_task(
+    args: flags::EditArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    let theme = get_input_theme();
+
+    let completion = NameCompletion::tasks(args.term.is_none(), &app, config)?;
+    let search_term = option_or_input(
+        args.term,
+        dialoguer::Input::with_theme(&theme)
+            .with_prompt("Task name")
+            .completion_with(&completion),
+    )?;
+
+    let task = prompt_task_selection(&app, search_term, toado::QueryCols::All, &theme, config)?;
+
+    // Get selected task id
+    let task_id = match task.id {
+        Some(id) => id,
+        None => return Err(Into::into("task id should exist")),
+    };
+
+    let original = toml::to_string(&EditTask::try_from(task)?)?;
+
+    // Write task to a new temporary file and open it in the users editor. The file is created
+    // exclusively, so an existing file or symlink at the path is never written through
+    let path = env::temp_dir().join(format!("toado-{}-task-{task_id}.toml", process::id()));
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    let edited = file
+        .write_all(original.as_bytes())
+        .map_err(toado::Error::from)
+        .and_then(|_| open_editor(&path))
+        .and_then(|_| Ok(fs::read_to_string(&path)?));
+    fs::remove_file(&path)?;
+    let edited = edited?;
+
+    // Abort if file is emptied or left unchanged
+    if edited.trim().is_empty() || edited == original {
+        return Ok(None);
+    }
+
+    let edit_task: EditTask =
+        toml::from_str(&edited).map_err(|e| format!("invalid task file: {e}"))?;
+    let name = edit_task.name.clone();
+
+    app.update_task(
+        Some(
+            toado::QueryConditions::Equal {
+                col: "id",
+                value: task_id,
+            }
+            .to_string(),
+        ),
+        edit_task.into_update_args()?,
+    )?;
+
+    Ok(Some(name))
 }
 
-/// Searches for a task in a toado server database with provided search term. If term is a positive
-/// integer, searches by task id, otherwise searches by name
+/// Searches for a task in a toado server database with provided search terms. If a single term is
+/// given and is a positive integer, searches by task id, otherwise searches for tasks with names
+/// containing all terms. If searching notes, terms may be contained in either the name or notes. If
+/// regex search is enabled, matches tasks against the terms as a regular expression
 ///
 /// # Errors
 ///
-/// Will return an error if task selection fails
+/// Will return an error if the regular expression is invalid, or if task selection fails
 pub fn search_tasks(
     args: flags::SearchArgs,
     app: toado::Server,
     config: &config::Config,
 ) -> Result<Option<String>, toado::Error> {
-    let condition = match args.term.parse::<usize>() {
-        // If search term is number, select by id
-        Ok(value) => toado::QueryConditions::Equal {
-            col: "id",
-            value: value.to_string(),
-        },
-        // If search term is not number, select by name
-        Err(_) => toado::QueryConditions::Like {
-            col: "name",
-            value: format!("'%{}%'", args.term),
-        },
+    let condition = search_condition(&args, config);
+
+    // If matching a regular expression, filter all tasks and page through matches. Otherwise
+    // select matching tasks from the database
+    let (tasks, regex_total) = match search_regex(&args, config)? {
+        Some(regex) => {
+            let tasks: Vec<toado::Task> = app
+                .select_tasks(
+                    toado::QueryCols::All,
+                    None,
+                    Some(toado::OrderBy::Id),
+                    None,
+                    Some(toado::RowLimit::All),
+                    None,
+                )?
+                .into_iter()
+                .filter(|task| {
+                    regex_matches(
+                        &regex,
+                        task.name.as_deref(),
+                        task.notes.as_deref(),
+                        args.in_notes,
+                    )
+                })
+                .collect();
+            let total = tasks.len();
+            (page_items(tasks, args.limit, args.offset), Some(total))
+        }
+        None => (
+            app.select_tasks(
+                toado::QueryCols::All,
+                Some(condition.to_string()),
+                Some(toado::OrderBy::Id),
+                None,
+                Some(
+                    args.limit
+                        .map_or(toado::RowLimit::All, toado::RowLimit::Limit),
+                ),
+                args.offset,
+            )?,
+            None,
+        ),
     };
 
-    let tasks = app.select_tasks(
-        toado::QueryCols::All,
-        Some(condition.to_string()),
-        Some(toado::OrderBy::Id),
-        None,
-        Some(toado::RowLimit::All),
-        None,
-    )?;
+    // If paging through results, always display results as a list
+    let paged = args.limit.is_some() || args.offset.is_some();
 
-    if tasks.is_empty() {
+    if args.format == flags::OutputFormat::Json {
+        // A single match is displayed as an object, same as with table formatting
+        Ok(Some(if tasks.len() == 1 && !paged {
+            serde_json::to_string(&tasks[0])?
+        } else {
+            serde_json::to_string(&tasks)?
+        }))
+    } else if args.format == flags::OutputFormat::Tsv {
+        Ok(Some(tsv_list(
+            &tasks,
+            toado::Tables::Tasks.columns(),
+            false,
+        )?))
+    } else if args.format == flags::OutputFormat::Ndjson {
+        Ok(Some(ndjson_list(&tasks, &toado::QueryCols::All)?))
+    } else if args.format == flags::OutputFormat::Plain {
+        Ok(Some(plain_list(&tasks, &["id", "name"])?))
+    } else if tasks.is_empty() {
         Ok(None)
-    } else if tasks.len() == 1 {
+    } else if tasks.len() == 1 && !paged {
         Ok(Some(formatting::format_task(tasks[0].clone(), config)))
     } else {
-        Ok(Some(formatting::format_task_list(
-            tasks,
-            args.verbose,
-            &config.table,
-        )))
+        let num_tasks = tasks.len();
+        let mut table_string =
+            formatting::format_task_list(tasks, args.verbose, config.table.show_header, config);
+
+        // Display number of tasks selected out of all matching tasks
+        table_string.push_str(&list_footer(
+            args.offset,
+            num_tasks,
+            match regex_total {
+                Some(total) => total,
+                None => app.get_row_count(toado::Tables::Tasks, Some(condition.to_string()))?,
+            },
+        ));
+
+        Ok(Some(table_string))
     }
 }
 
@@ -316,88 +748,677 @@ pub fn list_tasks(
     app: toado::Server,
     config: &config::Config,
 ) -> Result<Option<String>, toado::Error> {
-    let (cols, order_by, order_dir, limit, offset) = parse_list_args(&args);
+    let (cols, condition, order_by, order_dir, limit, offset) = parse_list_args(&args, config)?;
+
+    // Select statuses and end times to mark overdue tasks, and ids and parent ids to group tasks
+    // or order them as a tree, even if they are not displayed
+    let query_cols = match &cols {
+        toado::QueryCols::Some(cols) => {
+            let mut query_cols = cols.clone();
+            for col in ["status", "end_time", "id", "parent_id"] {
+                if !query_cols.contains(&col) {
+                    query_cols.push(col);
+                }
+            }
+            toado::QueryCols::Some(query_cols)
+        }
+        toado::QueryCols::All => toado::QueryCols::All,
+    };
 
     // Get tasks from application database
-    let tasks = app.select_tasks(cols, None, order_by, order_dir, limit, offset)?;
+    let tasks = app.select_tasks(
+        query_cols,
+        condition.clone(),
+        order_by,
+        order_dir,
+        limit,
+        offset,
+    )?;
     let num_tasks = tasks.len();
 
-    // Format tasks into a table string to display
-    let mut table_string = formatting::format_task_list(tasks, args.verbose, &config.table);
+    match args.format {
+        flags::OutputFormat::Json => return Ok(Some(json_list(&tasks, &cols)?)),
+        flags::OutputFormat::Ndjson => return Ok(Some(ndjson_list(&tasks, &cols)?)),
+        flags::OutputFormat::Tsv => {
+            let cols = col_names(&cols, toado::Tables::Tasks);
+            return Ok(Some(tsv_list(&tasks, &cols, args.header)?));
+        }
+        flags::OutputFormat::Plain => {
+            let cols = match args.columns.is_some() || args.verbose {
+                true => col_names(&cols, toado::Tables::Tasks),
+                false => vec!["id", "name"],
+            };
+            return Ok(Some(plain_list(&tasks, &cols)?));
+        }
+        flags::OutputFormat::Table => {}
+    }
+
+    // An empty table displays only its footer, so describe the empty list instead
+    if tasks.is_empty() {
+        return Ok(Some("No tasks found".to_string()));
+    }
+
+    let tasks = match args.tree {
+        true => tree_tasks(tasks),
+        false => tasks,
+    };
+
+    // Format tasks into a table string, or a table for each group of tasks, to display
+    let now = chrono::Local::now().naive_local();
+    let mut table_string = match args.group_by {
+        Some(flags::ListGroup::Project) => formatting::format_grouped_task_tables(
+            group_tasks_by_project(tasks, &app)?,
+            &cols,
+            args.header,
+            &now,
+            config,
+        ),
+        None => formatting::format_task_table(tasks, &cols, args.header, &now, config),
+    };
 
     // If not selecting all tasks, display number of tasks selected
     if !args.full {
         table_string.push_str(&list_footer(
             offset,
             num_tasks,
-            app.get_table_row_count(toado::Tables::Tasks)?,
+            app.get_row_count(toado::Tables::Tasks, condition)?,
         ));
     }
 
     Ok(Some(table_string))
 }
 
-pub fn check_task(
-    args: flags::CheckArgs,
+/// Groups tasks under the names of the projects they are assigned to, in order of project name,
+/// followed by a group of unassigned tasks. Tasks assigned to several projects are in each of their
+/// groups, and projects with no tasks are left out
+///
+/// # Errors
+///
+/// Will return an error if selecting projects or task assignments fails
+fn group_tasks_by_project(
+    tasks: Vec<toado::Task>,
+    app: &toado::Server,
+) -> Result<Vec<(String, Vec<toado::Task>)>, toado::Error> {
+    let assignments: HashSet<(i64, i64)> = app.select_assignments()?.into_iter().collect();
+    let projects = app.select_project(
+        toado::QueryCols::Some(vec!["id", "name"]),
+        None,
+        Some(toado::OrderBy::Name),
+        Some(toado::OrderDir::Asc),
+        Some(toado::RowLimit::All),
+        None,
+    )?;
+
+    let mut groups: Vec<(String, Vec<toado::Task>)> = projects
+        .into_iter()
+        .filter_map(|project| {
+            let project_id = project.id?;
+            let project_tasks: Vec<toado::Task> = tasks
+                .iter()
+                .filter(|task| {
+                    task.id
+                        .is_some_and(|id| assignments.contains(&(id, project_id)))
+                })
+                .cloned()
+                .collect();
+
+            (!project_tasks.is_empty()).then(|| (project.name.unwrap_or_default(), project_tasks))
+        })
+        .collect();
+
+    let assigned_ids: HashSet<i64> = assignments.iter().map(|(task_id, _)| *task_id).collect();
+    let unassigned: Vec<toado::Task> = tasks
+        .into_iter()
+        .filter(|task| !task.id.is_some_and(|id| assigned_ids.contains(&id)))
+        .collect();
+
+    if !unassigned.is_empty() {
+        groups.push(("Unassigned".to_string(), unassigned));
+    }
+
+    Ok(groups)
+}
+
+/// Orders tasks as a tree, with each task followed by its subtasks, and indents task names by two
+/// spaces per depth. Tasks keep their order among their siblings. Tasks with a parent that is not
+/// listed, or with parents forming a cycle, are placed at the root of the tree
+fn tree_tasks(tasks: Vec<toado::Task>) -> Vec<toado::Task> {
+    let ids: HashSet<i64> = tasks.iter().filter_map(|task| task.id).collect();
+
+    // Indices of the subtasks of each listed task, and of tasks without a listed parent
+    let mut children: HashMap<i64, Vec<usize>> = HashMap::new();
+    let mut roots: Vec<usize> = Vec::new();
+    for (i, task) in tasks.iter().enumerate() {
+        match task
+            .parent_id
+            .filter(|parent_id| ids.contains(parent_id) && task.id != Some(*parent_id))
+        {
+            Some(parent_id) => children.entry(parent_id).or_default().push(i),
+            None => roots.push(i),
+        }
+    }
+
+    // Visit tasks depth first from the roots, then from any tasks left unvisited by a cycle
+    let mut visited = vec![false; tasks.len()];
+    let mut order: Vec<(usize, usize)> = Vec::with_capacity(tasks.len());
+    for root in roots.into_iter().chain(0..tasks.len()) {
+        let mut stack = vec![(root, 0)];
+        while let Some((i, depth)) = stack.pop() {
+            if visited[i] {
+                continue;
+            }
+            visited[i] = true;
+            order.push((i, depth));
+
+            if let Some(subtasks) = tasks[i].id.and_then(|id| children.get(&id)) {
+                stack.extend(subtasks.iter().rev().map(|subtask| (*subtask, depth + 1)));
+            }
+        }
+    }
+
+    let mut tasks: Vec<Option<toado::Task>> = tasks.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|(i, depth)| {
+            let mut task = tasks[i].take()?;
+            task.name = task
+                .name
+                .map(|name| format!("{}{name}", "  ".repeat(depth)));
+            Some(task)
+        })
+        .collect()
+}
+
+/// Computes the upcoming occurrences of a repeating task from its end time, or its start time if
+/// it has no end time, without modifying the task. Returns the name of the task, its recurrence,
+/// and the upcoming occurrence times
+///
+/// # Errors
+///
+/// Will return an error if user input fails, if selecting the task fails, or if the task does not
+/// repeat or has no start or end time
+pub fn upcoming_task(
+    args: flags::UpcomingArgs,
     app: toado::Server,
     config: &config::Config,
-) -> Result<(String, toado::ItemStatus), toado::Error> {
+) -> Result<(String, String, Vec<String>), toado::Error> {
     let theme = dialoguer::theme::ColorfulTheme::default();
 
+    let completion = NameCompletion::tasks(args.term.is_none(), &app, config)?;
     let search_term = option_or_input(
         args.term,
-        dialoguer::Input::with_theme(&theme).with_prompt("Task name"),
+        dialoguer::Input::with_theme(&theme)
+            .with_prompt("Task name")
+            .completion_with(&completion),
     )?;
 
     let task = prompt_task_selection(
         &app,
         search_term,
-        toado::QueryCols::Some(vec!["id", "name", "priority", "status"]),
+        toado::QueryCols::Some(vec!["id", "name", "start_time", "end_time", "repeat"]),
         &theme,
         config,
     )?;
 
-    // Get selected task id
-    let id = match task.id {
-        Some(id) => id,
-        None => return Err(Into::into("task id should exist")),
-    };
-
     let name = match task.name {
         Some(name) => name,
         None => return Err(Into::into("task name should exist")),
     };
 
-    let new_status = match args.incomplete {
-        true => toado::ItemStatus::Incomplete,
-        false => toado::ItemStatus::Complete,
+    let recurrence = match task.repeat.as_deref().filter(|repeat| !repeat.is_empty()) {
+        Some(repeat) => repeat.parse::<toado::recurrence::Recurrence>()?,
+        None => return Err(Into::into(format!("'{name}' does not repeat"))),
     };
 
-    let affected_rows = app.update_task(
-        Some(
-            toado::QueryConditions::Equal {
-                col: "id",
-                value: id,
-            }
-            .to_string(),
-        ),
-        toado::UpdateTaskArgs::update_status(new_status),
-    )?;
-
-    if affected_rows == 0 {
-        Err(Into::into("no rows affected by update"))
+    let occurrences = match task.end_time.or(task.start_time) {
+        Some(time) => match recurrence.next_occurrences(&time, args.count) {
+            Some(occurrences) => occurrences,
+            None => return Err(Into::into(format!("'{time}' is not a valid time"))),
+        },
+        None => return Err(Into::into(format!("'{name}' has no start or end time"))),
+    };
+
+    Ok((name, recurrence.to_string(), occurrences))
+}
+
+/// Reorders the priorities of the incomplete and in progress tasks in a toado server, giving them
+/// sequential priorities from highest to lowest. Tasks are ordered by the ids set in the arguments,
+/// followed by any unlisted tasks in their current order. If no order is set, prompts the user to
+/// select tasks from highest to lowest priority. Returns the number of tasks reprioritized
+///
+/// # Errors
+///
+/// Will return an error if an id is not an incomplete or in progress task or is listed more than
+/// once, if user input fails, or if updating the tasks fails
+pub fn prioritize_tasks(
+    args: flags::PrioritizeArgs,
+    app: toado::Server,
+) -> Result<u64, toado::Error> {
+    let mut tasks: Vec<(i64, String)> = app
+        .select_tasks(
+            toado::QueryCols::Some(vec!["id", "name", "priority"]),
+            Some(toado::open_condition(None)),
+            Some(toado::OrderBy::Priority),
+            Some(toado::OrderDir::Desc),
+            Some(toado::RowLimit::All),
+            None,
+        )?
+        .into_iter()
+        .filter_map(|task| Some((task.id?, task.name?)))
+        .collect();
+
+    let mut order: Vec<i64> = Vec::with_capacity(tasks.len());
+
+    if let Some(ids) = args.order {
+        for id in ids {
+            if order.contains(&id) {
+                return Err(Into::into(format!("task {id} is listed more than once")));
+            }
+            match tasks.iter().position(|(task_id, _)| *task_id == id) {
+                Some(i) => order.push(tasks.remove(i).0),
+                None => {
+                    return Err(toado::ToadoError::NotFound(format!(
+                        "no incomplete or in progress task with id {id}"
+                    ))
+                    .into())
+                }
+            }
+        }
     } else {
-        Ok((name, new_status))
+        let theme = get_input_theme();
+
+        // Select the highest priority of the remaining tasks until one task remains
+        while tasks.len() > 1 {
+            let selected_idx = dialoguer::Select::with_theme(&theme)
+                .with_prompt(format!("Select priority {} task", order.len() + 1))
+                .items(
+                    &tasks
+                        .iter()
+                        .map(|(id, name)| format!("{name} ({id})"))
+                        .collect::<Vec<String>>(),
+                )
+                .default(0)
+                .interact()?;
+
+            order.push(tasks.remove(selected_idx).0);
+        }
     }
+
+    order.extend(tasks.into_iter().map(|(id, _)| id));
+    app.reprioritize_tasks(&order)
+}
+
+/// Sets the completion status of a task in a toado server. Returns the name of the task, its new
+/// status, and the id of the next occurrence of the task if it was rescheduled
+///
+/// # Errors
+///
+/// Will return an error if user input fails, or if updating the task fails
+pub fn check_task(
+    args: flags::CheckArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<(String, toado::ItemStatus, Option<i64>), toado::Error> {
+    let theme = dialoguer::theme::ColorfulTheme::default();
+    let new_status = args.new_status();
+
+    let term = args.term.into_iter().next();
+    let completion = NameCompletion::tasks(term.is_none(), &app, config)?;
+    let search_term = option_or_input(
+        term,
+        dialoguer::Input::with_theme(&theme)
+            .with_prompt("Task name")
+            .completion_with(&completion),
+    )?;
+
+    let task = prompt_task_selection(
+        &app,
+        search_term,
+        toado::QueryCols::Some(vec!["id", "name", "priority", "status"]),
+        &theme,
+        config,
+    )?;
+
+    // Get selected task id
+    let id = match task.id {
+        Some(id) => id,
+        None => return Err(Into::into("task id should exist")),
+    };
+
+    let name = match task.name {
+        Some(name) => name,
+        None => return Err(Into::into("task name should exist")),
+    };
+
+    // Completing a repeating task reschedules its next occurrence
+    if let toado::ItemStatus::Complete = new_status {
+        let rescheduled_id = app.complete_and_reschedule(id)?;
+        return Ok((name, new_status, rescheduled_id));
+    }
+
+    let affected_rows = app.update_task(
+        Some(
+            toado::QueryConditions::Equal {
+                col: "id",
+                value: id,
+            }
+            .to_string(),
+        ),
+        toado::UpdateTaskArgs::update_status(new_status),
+    )?;
+
+    matched_rows(affected_rows, "tasks")?;
+
+    Ok((name, new_status, None))
+}
+
+/// Result of checking multiple tasks
+pub type CheckedTasks = (
+    Vec<String>,        // Names of updated tasks
+    toado::ItemStatus,  // New status of tasks
+    Vec<(String, i64)>, // Names and ids of rescheduled tasks
+);
+
+/// Sets the status of multiple tasks in a single update. Tasks are given by id, or if multi select
+/// is enabled, selected by the user from the tasks matching the search term. Completing a repeating
+/// task reschedules its next occurrence. Returns the names of the updated tasks, the new status,
+/// and the names and ids of rescheduled tasks
+///
+/// # Errors
+///
+/// Will return an error if any term is not the id of a task, if user selection fails, or if
+/// updating the tasks fails
+pub fn check_tasks(
+    args: flags::CheckArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<CheckedTasks, toado::Error> {
+    let new_status = args.new_status();
+    let ids = if args.multi {
+        let theme = get_input_theme();
+        prompt_select_item(
+            args.term.into_iter().next(),
+            &app,
+            &theme,
+            true,
+            false,
+            config,
+        )?
+        .tasks()
+        .into_iter()
+        .filter_map(|task| task.id)
+        .collect()
+    } else {
+        args.term
+            .iter()
+            .map(|term| {
+                term.parse::<i64>()
+                    .map_err(|_| toado::ToadoError::Input(format!("'{term}' is not a task id")))
+            })
+            .collect::<Result<Vec<i64>, toado::ToadoError>>()?
+    };
+
+    set_tasks_status(ids, new_status, &app)
+}
+
+/// Sets the status of tasks by id in a single update, rescheduling completed repeating tasks.
+/// Returns the names of the updated tasks, the new status, and the names and ids of rescheduled
+/// tasks
+///
+/// # Errors
+///
+/// Will return an error if any id is not the id of a task, or if updating the tasks fails
+fn set_tasks_status(
+    ids: Vec<i64>,
+    new_status: toado::ItemStatus,
+    app: &toado::Server,
+) -> Result<CheckedTasks, toado::Error> {
+    let tasks = app.select_tasks(
+        toado::QueryCols::Some(vec!["id", "name", "repeat"]),
+        Some(
+            toado::QueryConditions::In {
+                col: "id",
+                values: ids.clone(),
+            }
+            .to_string(),
+        ),
+        Some(toado::OrderBy::Id),
+        None,
+        Some(toado::RowLimit::All),
+        None,
+    )?;
+
+    if let Some(id) = ids
+        .iter()
+        .find(|id| !tasks.iter().any(|task| task.id == Some(**id)))
+    {
+        return Err(toado::ToadoError::NotFound(format!("no task with id {id}")).into());
+    }
+
+    let names = tasks.iter().filter_map(|task| task.name.clone()).collect();
+
+    // Completing a repeating task reschedules it, so repeating tasks are completed individually
+    let (repeating, other): (Vec<toado::Task>, Vec<toado::Task>) =
+        tasks.into_iter().partition(|task| {
+            matches!(new_status, toado::ItemStatus::Complete) && task.repeat.is_some()
+        });
+
+    app.update_tasks_bulk(
+        other.iter().filter_map(|task| task.id).collect(),
+        toado::UpdateTaskArgs::update_status(new_status),
+    )?;
+
+    let mut rescheduled = Vec::new();
+    for task in repeating {
+        if let (Some(id), Some(name)) = (task.id, task.name) {
+            if let Some(rescheduled_id) = app.complete_and_reschedule(id)? {
+                rescheduled.push((name, rescheduled_id));
+            }
+        }
+    }
+
+    Ok((names, new_status, rescheduled))
+}
+
+/// Raises or lowers the priority of a task in a toado server by a step, to a minimum of 0. Prompts
+/// the user to select the task if a search term is not provided, or if multiple tasks match it.
+/// Returns the name of the task and its new priority
+///
+/// # Errors
+///
+/// Will return an error if no direction is set, if user input fails, or if updating the task fails
+pub fn bump_task(
+    args: flags::BumpArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<(String, u64), toado::Error> {
+    let step = match (args.up, args.down) {
+        (true, _) => i64::from(args.by),
+        (false, true) => -i64::from(args.by),
+        (false, false) => {
+            return Err(toado::ToadoError::Input(
+                "no direction provided, try 'toado bump --up' or 'toado bump --down'".to_string(),
+            )
+            .into())
+        }
+    };
+
+    let theme = dialoguer::theme::ColorfulTheme::default();
+
+    let completion = NameCompletion::tasks(args.term.is_none(), &app, config)?;
+    let search_term = option_or_input(
+        args.term,
+        dialoguer::Input::with_theme(&theme)
+            .with_prompt("Task name")
+            .completion_with(&completion),
+    )?;
+
+    let task = prompt_task_selection(
+        &app,
+        search_term,
+        toado::QueryCols::Some(vec!["id", "name", "priority", "status"]),
+        &theme,
+        config,
+    )?;
+
+    let (id, name) = match (task.id, task.name) {
+        (Some(id), Some(name)) => (id, name),
+        _ => return Err(Into::into("task id and name should exist")),
+    };
+
+    match app.move_task_priority(id, step)? {
+        Some(priority) => Ok((name, priority)),
+        None => Err(toado::ToadoError::NotFound(format!("no task with id {id}")).into()),
+    }
+}
+
+/// Gets the next due task from a toado server, being the incomplete or in progress task with the
+/// earliest end time. Ties are broken by highest priority. Returns None if there are no upcoming
+/// tasks
+///
+/// # Errors
+///
+/// Will return an error if selecting tasks from the server database fails
+pub fn next_task(
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    let has_end_time: toado::QueryConditions<u32> =
+        toado::QueryConditions::IsNotNull { col: "end_time" };
+    let condition = format!("{} AND {has_end_time}", toado::open_condition(None));
+
+    let mut tasks = app.select_tasks(
+        toado::QueryCols::All,
+        Some(condition),
+        Some(toado::OrderBy::EndTime),
+        Some(toado::OrderDir::Asc),
+        Some(toado::RowLimit::Limit(1)),
+        None,
+    )?;
+
+    Ok(tasks
+        .pop()
+        .map(|task| formatting::format_task(task, config)))
 }
 
 //
 // Private Methods
 //
 
+/// Task import file data
+#[derive(Deserialize)]
+struct ImportFile {
+    tasks: Vec<ImportTask>,
+}
+
+/// Imported task data
+#[derive(Deserialize)]
+struct ImportTask {
+    name: String,
+    priority: Option<u64>,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    repeat: Option<String>,
+    notes: Option<String>,
+}
+
+impl ImportTask {
+    /// Validates imported task values and converts them into task creation arguments
+    fn into_add_args(self, owner: Option<String>) -> Result<toado::AddTaskArgs, toado::ToadoError> {
+        validate_name(&self.name).map_err(toado::ToadoError::Input)?;
+
+        Ok(toado::AddTaskArgs {
+            name: self.name,
+            priority: self.priority.unwrap_or(0),
+            status: toado::ItemStatus::Incomplete,
+            start_time: self.start_time.map(canonical_time).transpose()?,
+            end_time: self.end_time.map(canonical_time).transpose()?,
+            repeat: self.repeat.map(canonical_repeat).transpose()?,
+            notes: self.notes,
+            parent_id: None,
+            owner,
+        })
+    }
+}
+
+/// Task data edited in a text editor
+#[derive(Serialize, Deserialize)]
+struct EditTask {
+    name: String,
+    priority: u64,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    repeat: Option<String>,
+    notes: Option<String>,
+}
+
+impl TryFrom<toado::Task> for EditTask {
+    type Error = String;
+
+    fn try_from(task: toado::Task) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: task.name.ok_or("task name should exist")?,
+            priority: task.priority.ok_or("task priority should exist")?,
+            start_time: task.start_time,
+            end_time: task.end_time,
+            repeat: task.repeat,
+            notes: task.notes,
+        })
+    }
+}
+
+impl EditTask {
+    /// Validates edited task values and converts them into task update arguments. Missing
+    /// optional values are set to null
+    fn into_update_args(self) -> Result<toado::UpdateTaskArgs, toado::ToadoError> {
+        validate_name(&self.name).map_err(toado::ToadoError::Input)?;
+
+        Ok(toado::UpdateTaskArgs {
+            name: toado::UpdateAction::Some(self.name),
+            priority: toado::UpdateAction::Some(self.priority),
+            status: toado::UpdateAction::None,
+            start_time: toado::UpdateAction::from(canonical_time(
+                self.start_time.unwrap_or_default(),
+            )?),
+            end_time: toado::UpdateAction::from(canonical_time(self.end_time.unwrap_or_default())?),
+            repeat: toado::UpdateAction::from(canonical_repeat(self.repeat.unwrap_or_default())?),
+            notes: toado::UpdateAction::from(self.notes.unwrap_or_default()),
+        })
+    }
+}
+
+/// Opens a file in the editor set by $VISUAL or $EDITOR, defaulting to vi, and waits for the
+/// editor to exit
+///
+/// # Errors
+///
+/// Will return an error if the editor fails to start or exits unsuccessfully
+fn open_editor(path: &path::Path) -> Result<(), toado::Error> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or("vi".to_string());
+
+    // Editor variable may include arguments (eg. "code --wait")
+    let mut editor_args = editor.split_whitespace();
+    let program = editor_args.next().ok_or("editor should not be empty")?;
+
+    let status = process::Command::new(program)
+        .args(editor_args)
+        .arg(path)
+        .status()
+        .map_err(|e| format!("failed to open editor '{program}': {e}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Into::into(format!("editor exited with {status}")))
+    }
+}
+
 /// Selects tasks from an application database given a search term. If multiple tasks match the
 /// term, prompts the user to select one of the matching tasks and returns it. If one task matches
-/// inputed name, returns said task
+/// inputed name, returns said task. If fuzzy search is enabled and no task names contain the term,
+/// prompts the user to select from the closest fuzzy matches
 ///
 /// # Errors
 /// Will return an error if no tasks match the search term
@@ -408,23 +1429,12 @@ fn prompt_task_selection(
     theme: &dyn dialoguer::theme::Theme,
     config: &config::Config,
 ) -> Result<toado::Task, toado::Error> {
-    let select_condition = match search_term.parse::<usize>() {
-        // If search term is number, select by id
-        Ok(num) => toado::QueryConditions::Equal {
-            col: "id",
-            value: num.to_string(),
-        },
-        // If search term is not number, select by name
-        Err(_) => toado::QueryConditions::Like {
-            col: "name",
-            value: format!("'%{search_term}%'"),
-        },
-    };
+    let select_condition = term_condition(&search_term, config);
 
     // Get tasks matching name argument
     let mut tasks = app.select_tasks(
         // toado::QueryCols::Some(vec!["id", "name", "priority", "status"]),
-        cols,
+        cols.clone(),
         Some(select_condition.to_string()),
         Some(toado::OrderBy::Name),
         None,
@@ -432,19 +1442,44 @@ fn prompt_task_selection(
         None,
     )?;
 
+    let mut fuzzy = false;
+    if config.search.fuzzy && search_term.parse::<usize>().is_err() {
+        if tasks.is_empty() {
+            // If no names contain the search term, fall back to the closest fuzzy matches
+            tasks = fuzzy_rank(
+                app.select_tasks(
+                    cols,
+                    None,
+                    Some(toado::OrderBy::Name),
+                    None,
+                    Some(toado::RowLimit::All),
+                    None,
+                )?,
+                &search_term,
+                |task| task.name.as_ref(),
+            );
+            tasks.truncate(MAX_FUZZY_MATCHES);
+            fuzzy = true;
+        } else if tasks.len() > MAX_FUZZY_MATCHES {
+            // Order large numbers of matches by relevance
+            tasks = fuzzy_rank(tasks, &search_term, |task| task.name.as_ref());
+        }
+    }
+
     // If no tasks match search term, return error
     if tasks.is_empty() {
-        return Err(Into::into(format!("no task matches {search_term}")));
+        return Err(toado::ToadoError::NotFound(format!("no task matches {search_term}")).into());
     }
 
-    if tasks.len() == 1 {
+    // Fuzzy matches are never selected without confirmation from the user
+    if tasks.len() == 1 && !fuzzy {
         Ok(tasks.remove(0))
     }
     // If multiple tasks match name argument, prompt user to select one
     else {
         // Format matching tasks into vector of strings
         let task_strings: Vec<String> =
-            formatting::format_task_list(tasks.clone(), false, &config.table)
+            formatting::format_task_list(tasks.clone(), false, false, config)
                 .split('\n')
                 .map(|line| line.to_string())
                 .collect();
@@ -461,3 +1496,672 @@ fn prompt_task_selection(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::tests::{
+        add_projects, add_tasks, parse_command, task_args, temp_path, test_server,
+    };
+
+    /// Returns arguments for checking items matching terms
+    fn check_args(term: impl IntoIterator<Item = impl ToString>) -> flags::CheckArgs {
+        flags::CheckArgs {
+            term: term.into_iter().map(|term| term.to_string()).collect(),
+            incomplete: false,
+            start: false,
+            archive: false,
+            multi: false,
+            project: false,
+        }
+    }
+
+    #[test]
+    fn check_tasks_checks_every_id() {
+        let app = test_server();
+        let ids = add_tasks(&app, (0..12).map(|i| format!("task {i}")));
+
+        let (checked, _, _) =
+            check_tasks(check_args(ids), app, &config::Config::default()).unwrap();
+        assert_eq!(checked.len(), 12);
+    }
+
+    #[test]
+    fn set_tasks_status_updates_every_selected_task() {
+        let app = test_server();
+        let ids = add_tasks(&app, ["report a", "report b", "report c"]);
+
+        // Stands in for the user selecting the first two matches of a multi select
+        let selected = vec![ids[0], ids[1]];
+        let (names, _, _) = set_tasks_status(selected, toado::ItemStatus::Complete, &app).unwrap();
+        assert_eq!(names, vec!["report a", "report b"]);
+
+        for (i, id) in ids.iter().enumerate() {
+            let status = app.get_task_by_id(*id).unwrap().unwrap().status;
+            assert_eq!(matches!(status, Some(toado::ItemStatus::Complete)), i < 2);
+        }
+    }
+
+    #[test]
+    fn check_tasks_errors_by_cause() {
+        let config = config::Config::default();
+
+        let app = test_server();
+        add_tasks(&app, ["task"]);
+        let err = check_tasks(check_args(["1", "x"]), app, &config)
+            .err()
+            .expect("non numeric id should fail");
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(toado::ToadoError::Input(_))
+        ));
+
+        let app = test_server();
+        add_tasks(&app, ["task"]);
+        let err = check_tasks(check_args(["1", "2"]), app, &config)
+            .err()
+            .expect("missing id should fail");
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(toado::ToadoError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn prioritize_tasks_orders_every_open_task() {
+        let app = test_server();
+        let ids = add_tasks(&app, (0..12).map(|i| format!("task {i}")));
+
+        let args = flags::PrioritizeArgs {
+            order: Some(vec![ids[11]]),
+        };
+        assert_eq!(prioritize_tasks(args, app).unwrap(), 12);
+    }
+
+    #[test]
+    fn prioritize_tasks_writes_descending_priorities_in_order() {
+        let db_path = temp_path("prioritize.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let ids = add_tasks(&app, ["first", "second", "third"]);
+
+        let args = flags::PrioritizeArgs {
+            order: Some(vec![ids[2], ids[0], ids[1]]),
+        };
+        prioritize_tasks(args, app).unwrap();
+
+        let app = toado::Server::open(&db_path).unwrap();
+        let priority = |id| app.get_task_by_id(id).unwrap().unwrap().priority;
+        assert_eq!(priority(ids[2]), Some(3));
+        assert_eq!(priority(ids[0]), Some(2));
+        assert_eq!(priority(ids[1]), Some(1));
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn create_task_rejects_invalid_values_as_input() {
+        let config = config::Config::default();
+
+        for args in [
+            ["add", "task", "--start-time", "bogus"],
+            ["add", "task", "--repeat", "bogus"],
+        ] {
+            let flags::Commands::Add(args) = parse_command(&args) else {
+                panic!("add should parse as the add command");
+            };
+            let err =
+                create_task(args, test_server(), &config).expect_err("invalid value should fail");
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(toado::ToadoError::Input(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn create_task_assigns_to_project_or_adds_nothing() {
+        let config = config::Config::default();
+        let db_path = temp_path("add-assign.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let project_id = add_projects(&app, ["home"])[0];
+
+        let add = |project: &str| {
+            let flags::Commands::Add(args) = parse_command(&[
+                "add",
+                "task",
+                "--item-priority",
+                "0",
+                "--optional",
+                "--assign",
+                project,
+            ]) else {
+                panic!("add should parse as the add command");
+            };
+            create_task(args, toado::Server::open(&db_path).unwrap(), &config)
+        };
+
+        let (task_id, _, project_name) = add("home").unwrap();
+        assert_eq!(project_name.as_deref(), Some("home"));
+        let err = add("zzz").expect_err("missing project should fail");
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(toado::ToadoError::NotFound(_))
+        ));
+
+        let app = toado::Server::open(&db_path).unwrap();
+        assert_eq!(app.select_assignments().unwrap(), [(task_id, project_id)]);
+        assert_eq!(app.get_table_row_count(toado::Tables::Tasks).unwrap(), 1);
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn create_task_records_configured_owner() {
+        let mut config = config::Config::default();
+        config.user.name = Some("ada".to_string());
+        let db_path = temp_path("owner.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        add_tasks(&app, ["unowned"]);
+
+        let flags::Commands::Add(args) =
+            parse_command(&["add", "owned", "--item-priority", "0", "--optional"])
+        else {
+            panic!("add should parse as the add command");
+        };
+        let (id, _, _) = create_task(args, app, &config).unwrap();
+
+        let app = toado::Server::open(&db_path).unwrap();
+        let task = app.get_task_by_id(id).unwrap().unwrap();
+        assert_eq!(task.owner.as_deref(), Some("ada"));
+
+        let flags::Commands::Ls(args) =
+            parse_command(&["ls", "--owner", "ada", "--format", "plain"])
+        else {
+            panic!("ls should parse as the list command");
+        };
+        assert_eq!(
+            list_tasks(args, app, &config).unwrap().as_deref(),
+            Some(format!("{id}\towned").as_str())
+        );
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn next_task_selects_earliest_open_due_task() {
+        let config = config::Config::default();
+
+        let app = test_server();
+        add_tasks(&app, ["undated"]);
+        assert!(next_task(app, &config).unwrap().is_none());
+
+        let app = test_server();
+        for (name, priority, end_time) in [
+            ("later", 9, "2024-02-01"),
+            ("low tie", 1, "2024-01-01"),
+            ("high tie", 5, "2024-01-01"),
+            ("done", 9, "2023-01-01"),
+        ] {
+            app.add_task(toado::AddTaskArgs {
+                priority,
+                end_time: Some(end_time.to_string()),
+                ..task_args(name)
+            })
+            .unwrap();
+        }
+        add_tasks(&app, ["undated"]);
+        app.update_task(
+            Some("name = 'done'".to_string()),
+            toado::UpdateTaskArgs::update_status(toado::ItemStatus::Complete),
+        )
+        .unwrap();
+
+        let next = next_task(app, &config)
+            .unwrap()
+            .expect("a task should be due");
+        assert!(next.contains("high tie"));
+        for name in ["later", "low tie", "done", "undated"] {
+            assert!(!next.contains(name), "'{name}' should not be next");
+        }
+    }
+
+    #[test]
+    fn import_tasks_adds_every_task_or_none() {
+        let config = config::Config::default();
+        let db_path = temp_path("import.db");
+        toado::Server::open(&db_path).unwrap().init().unwrap();
+        let count = || {
+            toado::Server::open(&db_path)
+                .unwrap()
+                .get_table_row_count(toado::Tables::Tasks)
+                .unwrap()
+        };
+
+        let lines_path = temp_path("import.txt");
+        fs::write(&lines_path, "first\n\nsecond\n  third  \n").unwrap();
+        let imported = import_tasks(
+            lines_path.to_str().unwrap(),
+            toado::Server::open(&db_path).unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(imported, 3);
+        assert_eq!(count(), 3);
+
+        let toml_path = temp_path("import.toml");
+        fs::write(
+            &toml_path,
+            "[[tasks]]\nname = \"valid\"\n\n[[tasks]]\nname = \"invalid\"\nend_time = \"bogus\"\n",
+        )
+        .unwrap();
+        assert!(import_tasks(
+            toml_path.to_str().unwrap(),
+            toado::Server::open(&db_path).unwrap(),
+            &config,
+        )
+        .is_err());
+        assert_eq!(count(), 3);
+
+        for path in [db_path, lines_path, toml_path] {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn edit_task_file_round_trips() {
+        let app = test_server();
+        let task_id = app
+            .add_task(toado::AddTaskArgs {
+                priority: 3,
+                start_time: Some("2024-01-01".to_string()),
+                end_time: Some("2024-01-02T17:30:00".to_string()),
+                repeat: Some("weekly".to_string()),
+                notes: Some("line one\nline \"two\"".to_string()),
+                ..task_args("task")
+            })
+            .unwrap();
+        let select = || app.get_task_by_id(task_id).unwrap().unwrap();
+        let update = |edited: &str| {
+            let edit_task: EditTask = toml::from_str(edited).unwrap();
+            app.update_task(
+                Some(format!("id = {task_id}")),
+                edit_task.into_update_args().unwrap(),
+            )
+            .unwrap();
+        };
+
+        let original = toml::to_string(&EditTask::try_from(select()).unwrap()).unwrap();
+        update(&original);
+        assert_eq!(
+            toml::to_string(&EditTask::try_from(select()).unwrap()).unwrap(),
+            original
+        );
+
+        // Removed optional values are unset
+        update("name = \"renamed\"\npriority = 1\n");
+        let task = select();
+        assert_eq!(task.name.as_deref(), Some("renamed"));
+        assert_eq!(task.priority, Some(1));
+        assert!(task.start_time.is_none() && task.repeat.is_none() && task.notes.is_none());
+    }
+
+    /// Parses delete command arguments
+    fn delete_args(args: &[&str]) -> flags::DeleteArgs {
+        match parse_command(&[&["delete"], args].concat()) {
+            flags::Commands::Delete(args) => args,
+            _ => panic!("delete should parse as the delete command"),
+        }
+    }
+
+    #[test]
+    fn delete_task_with_yes_deletes_without_prompting() {
+        let config = config::Config::default();
+        let db_path = temp_path("delete-task.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let ids = add_tasks(&app, ["doomed", "spared"]);
+
+        let deleted = delete_task(delete_args(&["doomed", "--yes"]), app, &config).unwrap();
+        assert_eq!(deleted, Some(ids[0]));
+
+        let app = toado::Server::open(&db_path).unwrap();
+        assert!(app.get_task_by_id(ids[0]).unwrap().is_none());
+        assert!(app.get_task_by_id(ids[1]).unwrap().is_some());
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn update_task_appends_notes() {
+        let config = config::Config::default();
+        let db_path = temp_path("append-notes.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let noted = app
+            .add_task(toado::AddTaskArgs {
+                notes: Some("first".to_string()),
+                ..task_args("noted")
+            })
+            .unwrap();
+        let empty = add_tasks(&app, ["empty"])[0];
+
+        for id in [noted, empty] {
+            let args = match parse_command(&["update", &id.to_string(), "--append-notes", "second"])
+            {
+                flags::Commands::Update(args) => args,
+                _ => panic!("update should parse as the update command"),
+            };
+            update_task(args, toado::Server::open(&db_path).unwrap(), &config).unwrap();
+        }
+
+        let app = toado::Server::open(&db_path).unwrap();
+        let notes = |id| app.get_task_by_id(id).unwrap().unwrap().notes;
+        assert_eq!(notes(noted).as_deref(), Some("first\nsecond"));
+        assert_eq!(notes(empty).as_deref(), Some("second"));
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn update_task_sets_status_only() {
+        let config = config::Config::default();
+        let db_path = temp_path("update-status.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let id = app
+            .add_task(toado::AddTaskArgs {
+                priority: 2,
+                notes: Some("notes".to_string()),
+                ..task_args("task")
+            })
+            .unwrap();
+
+        let flags::Commands::Update(args) =
+            parse_command(&["update", &id.to_string(), "--status", "archived"])
+        else {
+            panic!("update should parse as the update command");
+        };
+        update_task(args, app, &config).unwrap();
+
+        let task = toado::Server::open(&db_path)
+            .unwrap()
+            .get_task_by_id(id)
+            .unwrap()
+            .unwrap();
+        assert!(matches!(task.status, Some(toado::ItemStatus::Archived)));
+        assert_eq!(task.name.as_deref(), Some("task"));
+        assert_eq!(task.priority, Some(2));
+        assert_eq!(task.notes.as_deref(), Some("notes"));
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn purge_tasks_deletes_only_archived_tasks() {
+        let db_path = temp_path("purge.db");
+        let app = toado::Server::open(&db_path).unwrap();
+        app.init().unwrap();
+        let ids = add_tasks(&app, ["open", "done", "old", "older"]);
+        app.update_tasks_bulk(
+            vec![ids[1]],
+            toado::UpdateTaskArgs::update_status(toado::ItemStatus::Complete),
+        )
+        .unwrap();
+        app.update_tasks_bulk(
+            vec![ids[2], ids[3]],
+            toado::UpdateTaskArgs::update_status(toado::ItemStatus::Archived),
+        )
+        .unwrap();
+
+        let purge = || {
+            purge_tasks(
+                flags::PurgeArgs { yes: true },
+                toado::Server::open(&db_path).unwrap(),
+            )
+        };
+        assert_eq!(purge().unwrap(), Some(2));
+        assert_eq!(purge().unwrap(), Some(0));
+
+        let app = toado::Server::open(&db_path).unwrap();
+        let remaining: Vec<bool> = ids
+            .iter()
+            .map(|id| app.get_task_by_id(*id).unwrap().is_some())
+            .collect();
+        assert_eq!(remaining, [true, true, false, false]);
+        let _ = fs::remove_file(db_path);
+    }
+
+    /// Parses search command arguments
+    fn search_args(args: &[&str]) -> flags::SearchArgs {
+        match parse_command(&[&["search"], args].concat()) {
+            flags::Commands::Search(args) => args,
+            _ => panic!("search should parse as the search command"),
+        }
+    }
+
+    #[test]
+    fn search_tasks_footer_counts_offset_page() {
+        let config = config::Config::default();
+        let app = test_server();
+        add_tasks(&app, (0..12).map(|i| format!("task {i}")));
+        add_tasks(&app, ["other"]);
+
+        let output = search_tasks(
+            search_args(&["task", "--limit", "5", "--offset", "10"]),
+            app,
+            &config,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(output.ends_with("\n10-12 of 12"), "{output}");
+    }
+
+    #[test]
+    fn search_tasks_json_shows_single_match_as_object() {
+        let config = config::Config::default();
+        let app = test_server();
+        add_tasks(&app, ["alpha", "beta one", "beta two"]);
+
+        let output = search_tasks(search_args(&["alpha", "--format", "json"]), app, &config)
+            .unwrap()
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["name"], "alpha");
+
+        let app = test_server();
+        add_tasks(&app, ["alpha", "beta one", "beta two"]);
+        let output = search_tasks(search_args(&["beta", "--format", "json"]), app, &config)
+            .unwrap()
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value.as_array().map(Vec::len), Some(2));
+    }
+
+    #[test]
+    fn list_tasks_json_has_only_selected_columns() {
+        let config = config::Config::default();
+        let app = test_server();
+        add_tasks(&app, ["a", "b"]);
+
+        let flags::Commands::Ls(args) =
+            parse_command(&["ls", "--format", "json", "--columns", "id,name"])
+        else {
+            panic!("ls should parse as the list command");
+        };
+        let output = list_tasks(args, app, &config).unwrap().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        let objects = value.as_array().unwrap();
+        assert_eq!(objects.len(), 2);
+        for object in objects {
+            let keys: Vec<&String> = object.as_object().unwrap().keys().collect();
+            assert_eq!(keys, ["id", "name"]);
+        }
+    }
+
+    #[test]
+    fn tree_tasks_indents_subtasks_under_parents() {
+        let task = |id: i64, name: &str, parent_id: Option<i64>| -> toado::Task {
+            serde_json::from_value(serde_json::json!({
+                "id": id,
+                "name": name,
+                "parent_id": parent_id,
+            }))
+            .unwrap()
+        };
+
+        let names: Vec<String> = tree_tasks(vec![
+            task(1, "parent", None),
+            task(2, "orphan", Some(99)),
+            task(3, "grandchild", Some(4)),
+            task(4, "child", Some(1)),
+            task(5, "cycle a", Some(6)),
+            task(6, "cycle b", Some(5)),
+        ])
+        .into_iter()
+        .filter_map(|task| task.name)
+        .collect();
+
+        assert_eq!(
+            names,
+            [
+                "parent",
+                "  child",
+                "    grandchild",
+                "orphan",
+                "cycle a",
+                "  cycle b"
+            ]
+        );
+    }
+
+    #[test]
+    fn list_tasks_plain_has_one_line_per_task() {
+        let config = config::Config::default();
+        let app = test_server();
+        let ids = add_tasks(&app, ["first task", "second"]);
+
+        let flags::Commands::Ls(args) = parse_command(&["ls", "id", "--format", "plain"]) else {
+            panic!("ls should parse as the list command");
+        };
+        let output = list_tasks(args, app, &config).unwrap().unwrap();
+
+        assert_eq!(
+            output,
+            format!("{}\tfirst task\n{}\tsecond", ids[0], ids[1])
+        );
+        assert!(!output.contains(['│', '─', '┼']));
+    }
+
+    #[test]
+    fn list_tasks_describes_empty_lists() {
+        let config = config::Config::default();
+        let list = |args: &[&str]| {
+            let flags::Commands::Ls(args) = parse_command(&[&["ls"], args].concat()) else {
+                panic!("ls should parse as the list command");
+            };
+            list_tasks(args, test_server(), &config).unwrap()
+        };
+
+        assert_eq!(list(&[]).as_deref(), Some("No tasks found"));
+        assert_eq!(list(&["--format", "json"]).as_deref(), Some("[]"));
+    }
+
+    #[test]
+    fn group_tasks_by_project_groups_under_project_names() {
+        let app = test_server();
+        let task_ids = add_tasks(&app, ["a", "b", "c"]);
+        let project_ids = add_projects(&app, ["second", "first", "empty"]);
+        app.assign_task(task_ids[0], project_ids[0]).unwrap();
+        app.assign_task(task_ids[1], project_ids[0]).unwrap();
+        app.assign_task(task_ids[1], project_ids[1]).unwrap();
+
+        let tasks = app
+            .select_tasks(
+                toado::QueryCols::Some(vec!["id", "name"]),
+                None,
+                Some(toado::OrderBy::Id),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        let groups: Vec<(String, Vec<String>)> = group_tasks_by_project(tasks, &app)
+            .unwrap()
+            .into_iter()
+            .map(|(name, tasks)| {
+                (
+                    name,
+                    tasks.into_iter().filter_map(|task| task.name).collect(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            groups,
+            [
+                ("first".to_string(), vec!["b".to_string()]),
+                ("second".to_string(), vec!["a".to_string(), "b".to_string()]),
+                ("Unassigned".to_string(), vec!["c".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_tasks_regex_matches_subset() {
+        let config = config::Config::default();
+        let app = test_server();
+        add_tasks(&app, ["task-1", "task-22", "task-x", "other"]);
+
+        let output = search_tasks(
+            search_args(&["^task-\\d+$", "--regex", "--format", "json"]),
+            app,
+            &config,
+        )
+        .unwrap()
+        .unwrap();
+        let tasks: Vec<toado::Task> = serde_json::from_str(&output).unwrap();
+        let names: Vec<String> = tasks.into_iter().filter_map(|task| task.name).collect();
+        assert_eq!(names, ["task-1", "task-22"]);
+
+        let err = search_tasks(search_args(&["(task", "--regex"]), test_server(), &config)
+            .expect_err("invalid regex should fail");
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(toado::ToadoError::Input(_))
+        ));
+    }
+
+    #[test]
+    fn upcoming_task_previews_next_occurrences() {
+        let config = config::Config::default();
+
+        for (repeat, end_time, count, expected) in [
+            (
+                "daily",
+                "2024-01-30",
+                "3",
+                &["2024-01-31", "2024-02-01", "2024-02-02"][..],
+            ),
+            (
+                "weekly",
+                "2024-02-19T09:00:00",
+                "2",
+                &["2024-02-26T09:00:00", "2024-03-04T09:00:00"],
+            ),
+        ] {
+            let app = test_server();
+            app.add_task(toado::AddTaskArgs {
+                end_time: Some(end_time.to_string()),
+                repeat: Some(repeat.to_string()),
+                ..task_args(repeat)
+            })
+            .unwrap();
+
+            let flags::Commands::Upcoming(args) = parse_command(&["upcoming", repeat, "-n", count])
+            else {
+                panic!("upcoming should parse as the upcoming command");
+            };
+            let (name, recurrence, occurrences) = upcoming_task(args, app, &config).unwrap();
+            assert_eq!((name.as_str(), recurrence.as_str()), (repeat, repeat));
+            assert_eq!(occurrences, expected);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{config, formatting};
+
+/// Formats a sample task table using the table config, displayed both with and without
+/// seperated rows
+pub fn preview_theme(config: &config::Config) -> String {
+    let tasks = vec![
+        sample_task(1, "Water the plants", 2, toado::ItemStatus::Incomplete),
+        sample_task(2, "Finish the report", 5, toado::ItemStatus::Complete),
+        sample_task(3, "Book flights", 1, toado::ItemStatus::Archived),
+        sample_task(4, "Plan the trip", 3, toado::ItemStatus::InProgress),
+    ];
+
+    [false, true]
+        .into_iter()
+        .map(|seperate_rows| {
+            let mut config = config.clone();
+            config.table.seperate_rows = seperate_rows;
+
+            format!(
+                "seperate_rows = {seperate_rows}\n{}",
+                formatting::format_task_list(
+                    tasks.clone(),
+                    false,
+                    config.table.show_header,
+                    &config
+                )
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+/// Creates a task with sample values
+fn sample_task(id: i64, name: &str, priority: u64, status: toado::ItemStatus) -> toado::Task {
+    toado::Task {
+        id: Some(id),
+        name: Some(name.to_string()),
+        priority: Some(priority),
+        status: Some(status),
+        start_time: None,
+        end_time: None,
+        repeat: None,
+        notes: None,
+        parent_id: None,
+        created_at: None,
+        completed_at: None,
+        owner: None,
+        updated_at: None,
+        projects: None,
+        blocked: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_uses_configured_characters() {
+        let mut config = config::Config::default();
+        config.table.vertical = '┃';
+        config.table.horizontal = '━';
+
+        let preview = preview_theme(&config);
+        assert!(preview.contains('┃'));
+        assert!(preview.contains('━'));
+        assert!(
+            preview.contains("seperate_rows = false") && preview.contains("seperate_rows = true")
+        );
+    }
+}
@@ -11,6 +11,9 @@ include!(concat!(env!("OUT_DIR"), "/config/default.rs"));
 struct ConfigData {
     pub table: Option<TableData>,
     pub list: Option<ListData>,
+    pub display: Option<DisplayData>,
+    pub search: Option<SearchData>,
+    pub user: Option<UserData>,
 }
 
 /// Table config data
@@ -18,6 +21,10 @@ struct ConfigData {
 struct TableData {
     pub seperate_columns: Option<bool>,
     pub seperate_rows: Option<bool>,
+    pub show_header: Option<bool>,
+    pub max_col_width: Option<usize>,
+    pub fit_to_terminal: Option<bool>,
+    pub empty_placeholder: Option<String>,
     pub characters: Option<TableCharsData>,
 }
 
@@ -41,12 +48,45 @@ struct TableCharsData {
 #[derive(Deserialize)]
 struct ListData {
     pub default_verbose: Option<bool>,
+    pub default_limit: Option<usize>,
+    pub default_order_by: Option<toado::OrderBy>,
+    pub default_order_dir: Option<toado::OrderDir>,
+    pub default_status: Option<toado::ItemStatus>,
+}
+
+/// Display config data
+#[derive(Deserialize)]
+struct DisplayData {
+    pub colors: Option<bool>,
+    pub date_format: Option<String>,
+    pub wrap_width: Option<usize>,
+    pub priority_high_threshold: Option<u64>,
+    pub status_symbols: Option<StatusSymbols>,
+}
+
+/// Search config data
+#[derive(Deserialize)]
+struct SearchData {
+    pub fuzzy: Option<bool>,
+    pub ignore_case: Option<bool>,
+}
+
+/// User config data
+#[derive(Deserialize)]
+struct UserData {
+    pub name: Option<String>,
 }
 
 /// Application config
+#[derive(Clone)]
 pub struct Config {
     pub table: TableConfig,
     pub list: ListConfig,
+    pub display: DisplayConfig,
+    pub search: SearchConfig,
+    pub user: UserConfig,
+    /// Path of the file the config was loaded from
+    pub path: Option<path::PathBuf>,
 }
 
 impl From<ConfigData> for Config {
@@ -62,6 +102,22 @@ impl From<ConfigData> for Config {
                 table.seperate_rows = value;
             }
 
+            if let Some(value) = table_data.show_header {
+                table.show_header = value;
+            }
+
+            if let Some(value) = table_data.max_col_width {
+                table.max_col_width = Some(value);
+            }
+
+            if let Some(value) = table_data.fit_to_terminal {
+                table.fit_to_terminal = value;
+            }
+
+            if let Some(value) = table_data.empty_placeholder {
+                table.empty_placeholder = value;
+            }
+
             if let Some(table_chars) = table_data.characters {
                 if let Some(value) = table_chars.horizontal {
                     table.horizontal = value
@@ -115,16 +171,87 @@ impl From<ConfigData> for Config {
             if let Some(value) = list_data.default_verbose {
                 list.default_verbose = value;
             }
+
+            if let Some(value) = list_data.default_limit {
+                list.default_limit = value;
+            }
+
+            if let Some(value) = list_data.default_order_by {
+                list.default_order_by = Some(value);
+            }
+
+            if let Some(value) = list_data.default_order_dir {
+                list.default_order_dir = Some(value);
+            }
+
+            if let Some(value) = list_data.default_status {
+                list.default_status = Some(value);
+            }
+        }
+
+        let mut display = DisplayConfig::default();
+
+        if let Some(display_data) = value.display {
+            if let Some(value) = display_data.colors {
+                display.colors = value;
+            }
+            if let Some(value) = display_data.date_format {
+                display.date_format = Some(value);
+            }
+            if let Some(value) = display_data.wrap_width {
+                display.wrap_width = Some(value);
+            }
+            if let Some(value) = display_data.priority_high_threshold {
+                display.priority_high_threshold = Some(value);
+            }
+            if let Some(value) = display_data.status_symbols {
+                display.status_symbols = value;
+            }
         }
 
-        Self { table, list }
+        let mut search = SearchConfig::default();
+
+        if let Some(search_data) = value.search {
+            if let Some(value) = search_data.fuzzy {
+                search.fuzzy = value;
+            }
+            if let Some(value) = search_data.ignore_case {
+                search.ignore_case = value;
+            }
+        }
+
+        let mut user = UserConfig::default();
+
+        if let Some(user_data) = value.user {
+            if let Some(value) = user_data.name {
+                user.name = Some(value);
+            }
+        }
+
+        Self {
+            table,
+            list,
+            display,
+            search,
+            user,
+            path: None,
+        }
     }
 }
 
 /// Application Table config
+#[derive(Clone)]
 pub struct TableConfig {
     pub seperate_cols: bool,
     pub seperate_rows: bool,
+    pub show_header: bool,
+    /// Maximum display width of table values, longer values are truncated. If None or 0, values
+    /// are not truncated
+    pub max_col_width: Option<usize>,
+    /// Shrinks tables wider than the terminal to fit
+    pub fit_to_terminal: bool,
+    /// Displayed in place of empty table values
+    pub empty_placeholder: String,
     pub horizontal: char,
     pub vertical: char,
     pub up_horizontal: char,
@@ -144,6 +271,10 @@ impl TableConfig {
         TableConfig {
             seperate_cols: true,
             seperate_rows: false,
+            show_header: false,
+            max_col_width: None,
+            fit_to_terminal: true,
+            empty_placeholder: "-".to_string(),
 
             horizontal: '─',
             up_horizontal: '┴',
@@ -161,51 +292,166 @@ impl TableConfig {
 }
 
 /// List command config
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct ListConfig {
     pub default_verbose: bool,
+    /// Number of items listed when no limit is given, 0 lists all items
+    pub default_limit: usize,
+    /// Column items are ordered by when no order is given. If None, uses the table default
+    pub default_order_by: Option<toado::OrderBy>,
+    /// Direction items are ordered in when no order is given. If None, uses the column default
+    pub default_order_dir: Option<toado::OrderDir>,
+    /// Status tasks are filtered by when no status is given. If None, lists tasks of any status
+    pub default_status: Option<toado::ItemStatus>,
 }
 
 impl ListConfig {
     pub fn default() -> Self {
         Self {
             default_verbose: false,
+            default_limit: 10,
+            default_order_by: None,
+            default_order_dir: None,
+            default_status: None,
         }
     }
 }
 
+/// Display config
+#[derive(Clone)]
+pub struct DisplayConfig {
+    pub colors: bool,
+    pub date_format: Option<String>,
+    pub wrap_width: Option<usize>,
+    pub priority_high_threshold: Option<u64>,
+    pub status_symbols: StatusSymbols,
+}
+
+impl DisplayConfig {
+    pub fn default() -> Self {
+        Self {
+            colors: true,
+            date_format: None,
+            wrap_width: None,
+            priority_high_threshold: None,
+            status_symbols: StatusSymbols::default(),
+        }
+    }
+}
+
+/// Symbols displayed in place of task statuses. Statuses without a symbol are displayed by name
+#[derive(Clone, Default, Deserialize)]
+pub struct StatusSymbols {
+    pub incomplete: Option<String>,
+    pub in_progress: Option<String>,
+    pub complete: Option<String>,
+    pub archived: Option<String>,
+    /// Displayed for incomplete or in progress tasks with incomplete dependencies
+    pub blocked: Option<String>,
+}
+
+/// Search config
+#[derive(Clone)]
+pub struct SearchConfig {
+    pub fuzzy: bool,
+    pub ignore_case: bool,
+}
+
+impl SearchConfig {
+    pub fn default() -> Self {
+        Self {
+            fuzzy: true,
+            ignore_case: false,
+        }
+    }
+}
+
+/// User config
+#[derive(Clone)]
+pub struct UserConfig {
+    /// Name recorded as the owner of added tasks. If None, the USER environment variable is used
+    pub name: Option<String>,
+}
+
+impl UserConfig {
+    pub fn default() -> Self {
+        Self { name: None }
+    }
+
+    /// Returns the name of the owner of added tasks, the configured name or else the USER
+    /// environment variable. Returns None if neither is set or they are empty
+    pub fn owner(&self) -> Option<String> {
+        self.name
+            .clone()
+            .or_else(|| env::var("USER").ok())
+            .filter(|name| !name.is_empty())
+    }
+}
+
 /// Gets the application config file and returns it as a Config struct. If path is none, gets the
-/// config from the default location creating the default file if it doesn't exist
+/// config from the path set by the TOADO_CONFIG environment variable, or else from the default
+/// location creating the default file if it doesn't exist
 ///
 /// # Errors
 ///
 /// Will return an error if Some path is not able to be read, or if creation of config file fails
 pub fn get_config(path: Option<path::PathBuf>) -> Result<Config, toado::Error> {
-    let contents = if let Some(path) = path {
-        fs::read_to_string(path)?
-    } else {
-        let home_dir = env::var("HOME")?;
-        let mut path = path::PathBuf::from(format!("{home_dir}/.config/toado/"));
+    let path = path.or_else(|| env::var_os("TOADO_CONFIG").map(path::PathBuf::from));
 
-        fs::create_dir_all(path.clone())?;
+    let (path, contents) = if let Some(path) = path {
+        let contents = fs::read_to_string(&path)?;
+        (path, contents)
+    } else {
+        let path = default_config_path()?;
 
-        path.push("config.toml");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
 
-        if path.try_exists().unwrap_or(false) {
+        let contents = if path.try_exists().unwrap_or(false) {
             // If config exists in default location, read files
-            fs::read_to_string(path)?
+            fs::read_to_string(&path)?
         } else {
             // Else write default config to file
             let contents = get_default_config();
-            fs::write(path, contents.clone())?;
+            fs::write(&path, contents.clone())?;
 
             // Return default config contents
             contents
-        }
+        };
+
+        (path, contents)
     };
 
     let data: ConfigData = toml::from_str(&contents)?;
-    Ok(Config::from(data))
+    let mut config = Config::from(data);
+    config.path = Some(path);
+    Ok(config)
+}
+
+/// The config used when a config file sets no values
+impl Default for Config {
+    fn default() -> Self {
+        Config::from(ConfigData {
+            table: None,
+            list: None,
+            display: None,
+            search: None,
+            user: None,
+        })
+    }
+}
+
+/// Gets the default location of the application config file
+///
+/// # Errors
+///
+/// Will return an error if getting the home directory fails
+fn default_config_path() -> Result<path::PathBuf, toado::Error> {
+    let home_dir = env::var("HOME")?;
+    Ok(path::PathBuf::from(format!(
+        "{home_dir}/.config/toado/config.toml"
+    )))
 }
 
 //
@@ -18,12 +18,24 @@ pub struct Cli {
     pub verbose: bool,
     #[command(subcommand)]
     pub command: Option<Commands>,
-    /// Path to database file
+    /// Path to database file [env: TOADO_DB]
     #[arg(short, long, value_name = "PATH")]
     pub file: Option<String>,
-    /// Path to config file
+    /// Path to config file [env: TOADO_CONFIG]
     #[arg(short, long, value_name = "PATH")]
     pub config: Option<String>,
+    /// Print ids and counts as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+    /// Match item names regardless of case
+    #[arg(long, global = true)]
+    pub ignore_case: bool,
+    /// Disable colored output [env: NO_COLOR]
+    #[arg(long, global = true)]
+    pub no_color: bool,
+    /// Do not print the output of successful commands, unless printing JSON
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
 }
 
 /// Application subcommands
@@ -35,20 +47,59 @@ pub enum Commands {
     Add(AddArgs),
     /// Remove an item
     Delete(DeleteArgs),
+    /// Permanently delete all archived tasks
+    Purge(PurgeArgs),
     /// Update an item
     Update(UpdateArgs),
+    /// Rename an item
+    Rename(RenameArgs),
+    /// Edit a task in a text editor
+    Edit(EditArgs),
+    /// Copy a task
+    Clone(CloneArgs),
     /// Display a list of items
     Ls(ListArgs),
     /// Complete a task
     Check(CheckArgs),
     /// Assigns a task to a project
     Assign(AssignArgs),
+    /// Moves a task from one project to another
+    Move(MoveArgs),
+    /// Makes a task depend on another task
+    Depend(DependArgs),
+    /// Display the next due task
+    Next,
+    /// Display the upcoming occurrences of a repeating task
+    Upcoming(UpcomingArgs),
+    /// Reorder the priorities of incomplete and in progress tasks
+    Prioritize(PrioritizeArgs),
+    /// Raise or lower the priority of a task
+    Bump(BumpArgs),
+    /// Display the number of items
+    Count(CountArgs),
+    /// Display a summary of tasks and projects
+    Stats,
+    /// Display recent changes to items
+    Log(LogArgs),
+    /// Display the table theme
+    Theme(ThemeArgs),
+    /// Export items to another format
+    Export(ExportArgs),
+    /// Import items from a JSON export
+    Import(ImportArgs),
+    /// Display the database and config files in use
+    Info,
+    /// Tidy up the database
+    Maintenance(MaintenanceArgs),
+    /// Print a shell completion script
+    Completions(CompletionsArgs),
 }
 
 #[derive(Args)]
 pub struct SearchArgs {
-    /// Search term for item
-    pub term: String,
+    /// Search terms for item, matching items with names containing all terms
+    #[arg(required = true)]
+    pub term: Vec<String>,
     /// Search for tasks (default behaviour)
     #[arg(short, long)]
     pub task: bool,
@@ -58,6 +109,47 @@ pub struct SearchArgs {
     /// List all item information
     #[arg(short, long)]
     pub verbose: bool,
+    /// Limit the number of items listed
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+    /// Offset start of list
+    #[arg(short, long)]
+    pub offset: Option<usize>,
+    /// Format to display matching items as
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+    /// Also match terms in item notes
+    #[arg(long)]
+    pub in_notes: bool,
+    /// Match item names, or notes if enabled, against terms as a regular expression
+    #[arg(long)]
+    pub regex: bool,
+    /// Match terms in the names of projects tasks are assigned to (tasks only)
+    #[arg(long, conflicts_with_all = ["project", "in_notes", "regex"])]
+    pub in_project: bool,
+}
+
+/// Item listings are grouped by
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListGroup {
+    /// Projects tasks are assigned to, followed by unassigned tasks
+    Project,
+}
+
+/// Format items are displayed as
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Table of items, or all information of a single item
+    Table,
+    /// JSON object for a single item, or array of objects for multiple items
+    Json,
+    /// Tab seperated values, one item per line
+    Tsv,
+    /// Plain text, one item per line with only its id and name, or its listed columns
+    Plain,
+    /// Newline delimited JSON, one compact object per line
+    #[value(alias = "json-lines")]
+    Ndjson,
 }
 
 #[derive(Args)]
@@ -74,10 +166,10 @@ pub struct AddArgs {
     #[arg(short, long)]
     pub item_priority: Option<u64>,
     /// Start time of item
-    #[arg(short, long)]
+    #[arg(short, long, allow_hyphen_values = true)]
     pub start_time: Option<String>,
     /// End time of item
-    #[arg(short, long)]
+    #[arg(short, long, allow_hyphen_values = true)]
     pub end_time: Option<String>,
     /// Notes to add to item
     #[arg(short, long)]
@@ -88,6 +180,15 @@ pub struct AddArgs {
     /// Skip optional fields
     #[arg(short, long)]
     pub optional: bool,
+    /// Id of the parent task, making the new task a subtask (tasks only)
+    #[arg(long, value_name = "ID", conflicts_with = "project")]
+    pub parent: Option<i64>,
+    /// Add tasks from a file, either one task name per line or a TOML file of tasks
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["name", "project"])]
+    pub from: Option<String>,
+    /// Name or id of a project to assign the new task to (tasks only)
+    #[arg(long, value_name = "NAME|ID", conflicts_with_all = ["project", "from"])]
+    pub assign: Option<String>,
 }
 
 #[derive(Args)]
@@ -100,6 +201,15 @@ pub struct DeleteArgs {
     /// Delete project
     #[arg(short, long)]
     pub project: bool,
+    /// Delete all items
+    #[arg(long, conflicts_with = "term")]
+    pub all: bool,
+    /// Delete without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+    /// List the items that would be deleted without deleting them
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Args)]
@@ -119,17 +229,26 @@ pub struct UpdateArgs {
     #[arg(short, long, value_name = "PRIORITY")]
     pub item_priority: Option<u64>,
     /// Update Start time of item
-    #[arg(short, long, value_name = "TIME|NULL")]
+    #[arg(short, long, value_name = "TIME|NULL", allow_hyphen_values = true)]
     pub start_time: Option<NullableString>,
     /// Update End time of item
-    #[arg(short, long, value_name = "TIME|NULL")]
+    #[arg(short, long, value_name = "TIME|NULL", allow_hyphen_values = true)]
     pub end_time: Option<NullableString>,
     /// Update item notes
     #[arg(long, value_name = "NOTES|NULL")]
     pub notes: Option<NullableString>,
+    /// Append a line to item notes (tasks only)
+    #[arg(long, value_name = "TEXT", conflicts_with = "notes")]
+    pub append_notes: Option<String>,
     /// Update Repetition of item (tasks only)
     #[arg(short, long, value_name = "REPEAT|NULL")]
     pub repeat: Option<NullableString>,
+    /// Update Status of item
+    #[arg(long, value_enum)]
+    pub status: Option<toado::ItemStatus>,
+    /// List the items that would be updated without updating them
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 impl UpdateArgs {
@@ -140,7 +259,9 @@ impl UpdateArgs {
             || self.start_time.is_some()
             || self.end_time.is_some()
             || self.notes.is_some()
+            || self.append_notes.is_some()
             || self.repeat.is_some()
+            || self.status.is_some()
     }
 
     /// Returns true if any update value arguments are set for project values
@@ -151,9 +272,42 @@ impl UpdateArgs {
             || self.start_time.is_some()
             || self.end_time.is_some()
             || self.notes.is_some()
+            || self.status.is_some()
     }
 }
 
+#[derive(Args)]
+pub struct RenameArgs {
+    /// Search term for item to rename
+    pub term: String,
+    /// New name of item
+    pub name: String,
+    /// Rename task (default behaviour)
+    #[arg(short, long)]
+    pub task: bool,
+    /// Rename project
+    #[arg(short, long)]
+    pub project: bool,
+}
+
+#[derive(Args)]
+pub struct EditArgs {
+    /// Search term for task to edit
+    pub term: Option<String>,
+}
+
+#[derive(Args)]
+pub struct CloneArgs {
+    /// Search term for task to copy
+    pub term: Option<String>,
+    /// Number of copies to add
+    #[arg(short = 'n', long, default_value_t = 1)]
+    pub times: usize,
+    /// Assign copies to the projects the task is assigned to
+    #[arg(short, long)]
+    pub with_assignments: bool,
+}
+
 #[derive(Args)]
 pub struct ListArgs {
     /// List item order
@@ -173,24 +327,276 @@ pub struct ListArgs {
     /// List in descending order
     #[arg(short, long)]
     pub desc: bool,
-    /// Limit the number of items listed
+    /// Reverse the order of listed items
     #[arg(short, long)]
-    pub limit: Option<usize>,
+    pub reverse: bool,
+    /// Limit the number of items listed, or "all" to list all items
+    #[arg(short, long, value_name = "LIMIT|ALL")]
+    pub limit: Option<toado::RowLimit>,
     /// Offset start of list
     #[arg(short, long)]
     pub offset: Option<usize>,
-    /// List all items
+    /// List items with an id greater than ID, in order of id
+    #[arg(
+        long,
+        value_name = "ID",
+        conflicts_with_all = ["order_by", "asc", "desc", "reverse", "offset"]
+    )]
+    pub after_id: Option<i64>,
+    /// List all items (same as --limit all)
     #[arg(short, long)]
     pub full: bool,
+    /// List items with an end time before TIME
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    pub due_before: Option<String>,
+    /// List items with an end time after TIME
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    pub due_after: Option<String>,
+    /// List items without an end time
+    #[arg(long, conflicts_with_all = ["due_before", "due_after"])]
+    pub no_due: bool,
+    /// List items with an end time
+    #[arg(long, conflicts_with = "no_due")]
+    pub has_due: bool,
+    /// List items with notes
+    #[arg(long)]
+    pub has_notes: bool,
+    /// List items without notes
+    #[arg(long, conflicts_with = "has_notes")]
+    pub no_notes: bool,
+    /// List items added at or after TIME
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    pub since: Option<String>,
+    /// List items added before TIME
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    pub until: Option<String>,
+    /// List only incomplete or in progress tasks with an end time in the past, most overdue first
+    #[arg(long, conflicts_with_all = ["project", "no_due"])]
+    pub overdue: bool,
+    /// List only items with STATUS
+    #[arg(long)]
+    pub status: Option<toado::ItemStatus>,
+    /// List tasks of every status, ignoring the configured default status
+    #[arg(long, conflicts_with_all = ["project", "status"])]
+    pub all_statuses: bool,
+    /// List complete tasks completed at or after TIME, most recently completed first
+    #[arg(
+        long,
+        alias = "since-completed",
+        value_name = "TIME",
+        conflicts_with = "project",
+        allow_hyphen_values = true
+    )]
+    pub completed_since: Option<String>,
+    /// List tasks updated at or after TIME
+    #[arg(
+        long,
+        value_name = "TIME",
+        conflicts_with = "project",
+        allow_hyphen_values = true
+    )]
+    pub updated_since: Option<String>,
+    /// List the subtasks of the task with id ID
+    #[arg(long, value_name = "ID", conflicts_with = "project")]
+    pub children: Option<i64>,
+    /// List tasks owned by NAME
+    #[arg(long, value_name = "NAME", conflicts_with = "project")]
+    pub owner: Option<String>,
+    /// List the tasks assigned to the project with id ID
+    #[arg(long, value_name = "ID", conflicts_with = "project")]
+    pub project_id: Option<i64>,
+    /// Group listed tasks under headers
+    #[arg(long, value_name = "GROUP", conflicts_with = "project")]
+    pub group_by: Option<ListGroup>,
+    /// Display listed subtasks indented under their parent tasks
+    #[arg(long, conflicts_with_all = ["project", "group_by"])]
+    pub tree: bool,
+    /// Display a header row of column names
+    #[arg(long)]
+    pub header: bool,
+    /// Comma seperated list of columns to display, in order (eg. id,name,end_time)
+    #[arg(
+        long,
+        value_name = "COLS",
+        value_delimiter = ',',
+        conflicts_with = "verbose"
+    )]
+    pub columns: Option<Vec<String>>,
+    /// Format to display items as. JSON objects, TSV lines, and plain lines contain only the
+    /// listed columns
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Table,
+        conflicts_with = "group_by"
+    )]
+    pub format: OutputFormat,
 }
 
 #[derive(Args)]
-pub struct CheckArgs {
-    /// Search term for item to check
+pub struct MoveArgs {
+    /// Name or id of the task to move
+    pub task: Option<String>,
+    /// Name or id of the project to move the task from
+    #[arg(long, value_name = "NAME|ID")]
+    pub from: Option<String>,
+    /// Name or id of the project to move the task to
+    #[arg(long, value_name = "NAME|ID")]
+    pub to: Option<String>,
+}
+
+#[derive(Args)]
+pub struct DependArgs {
+    /// Name or id of the dependent task
+    pub task: Option<String>,
+    /// Name or id of the task it depends on
+    #[arg(long, value_name = "NAME|ID")]
+    pub on: Option<String>,
+    /// Remove the dependency instead of adding it
+    #[arg(short, long)]
+    pub remove: bool,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Format to export items as
+    #[arg(long, value_enum)]
+    pub format: ExportFormat,
+}
+
+/// Format items are exported as
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// iCalendar events for tasks with a start or end time
+    Ics,
+    /// All tasks, projects, and assignments, which can be restored with the import command
+    Json,
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// Path to a JSON export file
+    pub path: String,
+}
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for
+    pub shell: clap_complete::Shell,
+}
+
+#[derive(Args)]
+pub struct PrioritizeArgs {
+    /// Comma seperated list of task ids, from highest to lowest priority. Tasks not listed keep
+    /// their order below the listed tasks. Prompts for the order if not set
+    #[arg(long, value_name = "IDS", value_delimiter = ',')]
+    pub order: Option<Vec<i64>>,
+}
+
+#[derive(Args)]
+pub struct BumpArgs {
+    /// Search term for task to bump
     pub term: Option<String>,
+    /// Raise the priority of the task
+    #[arg(long, conflicts_with = "down")]
+    pub up: bool,
+    /// Lower the priority of the task, to a minimum of 0
+    #[arg(long)]
+    pub down: bool,
+    /// Amount to raise or lower the priority by
+    #[arg(long, value_name = "STEP", default_value_t = 1)]
+    pub by: u32,
+}
+
+#[derive(Args)]
+pub struct CountArgs {
+    /// Count tasks (default behaviour)
+    #[arg(short, long)]
+    pub task: bool,
+    /// Count projects
+    #[arg(short, long)]
+    pub project: bool,
+}
+
+#[derive(Args)]
+pub struct PurgeArgs {
+    /// Purge without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Args)]
+pub struct MaintenanceArgs {
+    /// Rebuild the database to reclaim space left by deleted items
+    #[arg(long)]
+    pub vacuum: bool,
+    /// Update the statistics used to speed up queries
+    #[arg(long)]
+    pub optimize: bool,
+}
+
+#[derive(Args)]
+pub struct ThemeArgs {
+    /// Display a sample table using the current table config
+    #[arg(long)]
+    pub preview: bool,
+}
+
+#[derive(Args)]
+pub struct CheckArgs {
+    /// Search term for item to check, or ids of multiple tasks to check
+    pub term: Vec<String>,
     /// Mark task as incomplete
     #[arg(short, long)]
     pub incomplete: bool,
+    /// Mark task as in progress
+    #[arg(short, long, conflicts_with = "incomplete")]
+    pub start: bool,
+    /// Mark task as archived
+    #[arg(short, long, conflicts_with_all = ["incomplete", "start"])]
+    pub archive: bool,
+    /// Select multiple tasks matching the search term to check
+    #[arg(short, long)]
+    pub multi: bool,
+    /// Check a project instead of a task
+    #[arg(short, long, conflicts_with = "multi")]
+    pub project: bool,
+}
+
+impl CheckArgs {
+    /// Returns the status checked items are set to
+    pub fn new_status(&self) -> toado::ItemStatus {
+        match (self.incomplete, self.start, self.archive) {
+            (true, _, _) => toado::ItemStatus::Incomplete,
+            (false, true, _) => toado::ItemStatus::InProgress,
+            (false, false, true) => toado::ItemStatus::Archived,
+            (false, false, false) => toado::ItemStatus::Complete,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct UpcomingArgs {
+    /// Search term for the repeating task
+    pub term: Option<String>,
+    /// Number of occurrences to display, at most 1000
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 5,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=1000)
+    )]
+    pub count: usize,
+}
+
+#[derive(Args)]
+pub struct LogArgs {
+    /// Number of entries to display
+    #[arg(short, long, default_value_t = 20)]
+    pub limit: usize,
+    /// Display all entries
+    #[arg(short, long)]
+    pub full: bool,
 }
 
 #[derive(Args)]
@@ -225,6 +631,12 @@ pub struct AssignArgs {
     /// Don't prompt item selection
     #[arg(short, long, requires = "task_search", requires = "project_search")]
     pub no_select: bool,
+    /// List the projects of a task, or the tasks of a project
+    #[arg(short, long, conflicts_with_all = ["unassign", "no_select"])]
+    pub list: bool,
+    /// Skip confirmation of assigning tasks to projects
+    #[arg(short, long)]
+    pub yes: bool,
 }
 
 /// CLI argument for a string value or Null
@@ -246,8 +658,7 @@ impl std::str::FromStr for NullableString {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.to_lowercase();
-        if s == "null" {
+        if s.to_lowercase() == "null" {
             Ok(Self::Null)
         } else {
             Ok(Self::Some(s.to_string()))
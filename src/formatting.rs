@@ -1,7 +1,163 @@
 //! Toado data formatting functions
+pub use export::*;
+pub use log::*;
 pub use projects::*;
+pub use stats::*;
 pub use tasks::*;
 
+pub mod export;
+pub mod log;
 pub mod projects;
+pub mod stats;
 pub mod table;
 pub mod tasks;
+
+use crate::config;
+
+/// Format a database column name as a table header (eg. "start_time" as "START TIME")
+fn format_col_name(col: &str) -> String {
+    col.replace('_', " ").to_uppercase()
+}
+
+/// Format an item name and optional id as a header, underlined by a table rule
+fn format_item_header(name: &str, id: Option<i64>, config: &config::Config) -> Vec<String> {
+    let name_l = console::measure_text_width(name);
+
+    if let Some(id) = id {
+        let id = id.to_string();
+        let id_l = id.len();
+
+        vec![
+            format!("{} {} {}", name, config.table.vertical, id),
+            format!(
+                "{}{}{}",
+                config.table.horizontal.to_string().repeat(name_l + 1),
+                config.table.up_horizontal,
+                config.table.horizontal.to_string().repeat(id_l + 1)
+            ),
+        ]
+    } else {
+        vec![
+            name.to_string(),
+            config.table.horizontal.to_string().repeat(name_l),
+        ]
+    }
+}
+
+/// Format an item time to be displayed using the configured date format. Times that can't be
+/// parsed or formatted are displayed unchanged
+fn format_display_time(time: &str, config: &config::Config) -> String {
+    use std::fmt::Write;
+
+    let (Some(format), Some(parsed)) = (&config.display.date_format, toado::time::parse_time(time))
+    else {
+        return time.to_string();
+    };
+
+    // Writing a date time with an invalid format string fails rather than panicking
+    let mut formatted = String::new();
+    match write!(formatted, "{}", parsed.format(format)) {
+        Ok(()) => formatted,
+        Err(_) => time.to_string(),
+    }
+}
+
+/// Formats an item's notes with a label, wrapping the notes on word boundaries to the configured
+/// wrap width, or else the width of the terminal. Wrapped lines are indented under the label
+fn format_notes(notes: &str, config: &config::Config) -> String {
+    const LABEL: &str = "Notes: ";
+    let indent = " ".repeat(LABEL.len());
+
+    let width = config.display.wrap_width.or_else(|| {
+        console::Term::stdout()
+            .size_checked()
+            .map(|(_, width)| usize::from(width))
+    });
+
+    let lines = match width {
+        Some(width) => notes
+            .lines()
+            .flat_map(|line| wrap_words(line, width.saturating_sub(LABEL.len()).max(1)))
+            .collect(),
+        None => notes.lines().map(String::from).collect::<Vec<String>>(),
+    };
+
+    format!("{LABEL}{}", lines.join(&format!("\n{indent}")))
+}
+
+/// Splits text into lines no wider than width, breaking only on whitespace. Words wider than width
+/// are kept whole on their own line
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let line_width = console::measure_text_width(&line);
+        if line.is_empty() {
+            line.push_str(word);
+        } else if line_width + 1 + console::measure_text_width(word) <= width {
+            line.push(' ');
+            line.push_str(word);
+        } else {
+            lines.push(std::mem::take(&mut line));
+            line.push_str(word);
+        }
+    }
+
+    lines.push(line);
+    lines
+}
+
+/// Gets the width of the terminal tables are fit to. Returns None if fitting tables is disabled or
+/// stdout is not a terminal
+fn table_max_width(config: &config::Config) -> Option<usize> {
+    if !config.table.fit_to_terminal {
+        return None;
+    }
+
+    console::Term::stdout()
+        .size_checked()
+        .map(|(_, width)| usize::from(width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_times_use_configured_date_format() {
+        let mut config = config::Config::default();
+        assert_eq!(
+            format_display_time("2024-06-01T12:30:00", &config),
+            "2024-06-01T12:30:00"
+        );
+
+        config.display.date_format = Some("%Y-%m-%d".to_string());
+        assert_eq!(
+            format_display_time("2024-06-01T12:30:00", &config),
+            "2024-06-01"
+        );
+        assert_eq!(format_display_time("bogus", &config), "bogus");
+
+        config.display.date_format = Some("%Q".to_string());
+        assert_eq!(
+            format_display_time("2024-06-01T12:30:00", &config),
+            "2024-06-01T12:30:00"
+        );
+    }
+
+    #[test]
+    fn notes_wrap_under_label() {
+        let mut config = config::Config::default();
+        config.display.wrap_width = Some(27);
+
+        assert_eq!(
+            format_notes("call the bank about the café invoice", &config),
+            "Notes: call the bank about\n       the café invoice"
+        );
+        assert_eq!(
+            format_notes("first line\nsecond", &config),
+            "Notes: first line\n       second"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::{Days, NaiveDateTime};
+use toado::recurrence::Recurrence;
+
+/// Maximum length of an iCalendar content line in octets, excluding the line break
+const ICS_LINE_LENGTH: usize = 75;
+
+/// Format tasks as an iCalendar file, with a VEVENT for each task that has a start or end time.
+/// Tasks without times are skipped
+pub fn export_ics(tasks: &[toado::Task]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//toado//toado {}//EN", env!("CARGO_PKG_VERSION")),
+    ];
+
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    for task in tasks {
+        lines.extend(ics_event(task, &timestamp));
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    // iCalendar lines are folded and each terminated by CRLF
+    lines
+        .iter()
+        .map(|line| format!("{}\r\n", fold_ics_line(line)))
+        .collect()
+}
+
+/// Format a task as the lines of an iCalendar VEVENT. Returns no lines if the task has no valid
+/// start or end time
+fn ics_event(task: &toado::Task, timestamp: &str) -> Vec<String> {
+    // Tasks with only an end time are events at their end time
+    let (start, end) = match (&task.start_time, &task.end_time) {
+        (Some(start), end) => (start, end.as_ref()),
+        (None, Some(end)) => (end, None),
+        (None, None) => return Vec::new(),
+    };
+
+    let (start_time, end_time) = match (toado::time::parse_time(start), end) {
+        (Some(start_time), Some(end)) => (start_time, toado::time::parse_time(end)),
+        (Some(start_time), None) => (start_time, None),
+        (None, _) => return Vec::new(),
+    };
+
+    // Events are all day only if all of their times are dates
+    let date_only = toado::time::is_date(start) && end.is_none_or(|end| toado::time::is_date(end));
+
+    let mut lines = vec!["BEGIN:VEVENT".to_string()];
+
+    if let Some(id) = task.id {
+        lines.push(format!("UID:task-{id}@toado"));
+    }
+    lines.push(format!("DTSTAMP:{timestamp}"));
+    lines.push(format!("DTSTART{}", ics_time(&start_time, date_only)));
+
+    // All day event end dates are exclusive, so end the day after the task end date
+    if let Some(end_time) = end_time {
+        let end_time = if date_only {
+            end_time.checked_add_days(Days::new(1)).unwrap_or(end_time)
+        } else {
+            end_time
+        };
+        lines.push(format!("DTEND{}", ics_time(&end_time, date_only)));
+    }
+
+    if let Some(name) = &task.name {
+        lines.push(format!("SUMMARY:{}", escape_ics_text(name)));
+    }
+    if let Some(notes) = task.notes.as_ref().filter(|notes| !notes.is_empty()) {
+        lines.push(format!("DESCRIPTION:{}", escape_ics_text(notes)));
+    }
+    if let Some(Ok(recurrence)) = task.repeat.as_deref().map(str::parse::<Recurrence>) {
+        lines.push(format!("RRULE:{}", ics_rule(recurrence)));
+    }
+
+    lines.push("END:VEVENT".to_string());
+    lines
+}
+
+/// Format a date time as an iCalendar property value, including the value type parameter for
+/// dates (eg. ":20240101T090000" or ";VALUE=DATE:20240101")
+fn ics_time(time: &NaiveDateTime, date_only: bool) -> String {
+    if date_only {
+        format!(";VALUE=DATE:{}", time.format("%Y%m%d"))
+    } else {
+        format!(":{}", time.format("%Y%m%dT%H%M%S"))
+    }
+}
+
+/// Format a recurrence as an iCalendar recurrence rule (eg. "FREQ=WEEKLY;INTERVAL=2")
+fn ics_rule(recurrence: Recurrence) -> String {
+    let (freq, interval) = match recurrence {
+        Recurrence::Days(n) => ("DAILY", n),
+        Recurrence::Weeks(n) => ("WEEKLY", n),
+        Recurrence::Months(n) => ("MONTHLY", n),
+        Recurrence::Years(n) => ("YEARLY", n),
+    };
+
+    format!("FREQ={freq};INTERVAL={interval}")
+}
+
+/// Escapes backslashes, semicolons, commas, and line breaks in iCalendar text values
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds an iCalendar content line longer than 75 octets into multiple lines, each continuation
+/// line starting with a space. Lines are only split between characters
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_length = 0;
+
+    for c in line.chars() {
+        if line_length + c.len_utf8() > ICS_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            line_length = 1;
+        }
+        folded.push(c);
+        line_length += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ics_lines_end_with_crlf() {
+        let task: toado::Task = serde_json::from_str(
+            r#"{"id": 1, "name": "task", "start_time": "2024-01-01", "end_time": "2024-01-02"}"#,
+        )
+        .expect("task should deserialize");
+        let ics = export_ics(&[task]);
+
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20240101\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20240103\r\n"));
+        assert!(!ics.replace("\r\n", "").contains('\n'));
+    }
+
+    #[test]
+    fn timed_tasks_are_exported_as_events() {
+        let tasks: Vec<toado::Task> = serde_json::from_str(
+            r#"[
+                {"id": 1, "name": "Call, then; email", "start_time": "2024-01-01T09:00:00",
+                    "end_time": "2024-01-01T10:30:00", "repeat": "every 2 weeks"},
+                {"id": 2, "name": "untimed"}
+            ]"#,
+        )
+        .expect("tasks should deserialize");
+        let ics = export_ics(&tasks);
+        let lines = ics.split("\r\n").collect::<Vec<&str>>();
+
+        let start = lines
+            .iter()
+            .position(|line| *line == "BEGIN:VEVENT")
+            .unwrap();
+        let end = lines.iter().position(|line| *line == "END:VEVENT").unwrap();
+        let event = &lines[start..end];
+        assert!(event.contains(&"UID:task-1@toado"));
+        assert!(event.contains(&"DTSTART:20240101T090000"));
+        assert!(event.contains(&"DTEND:20240101T103000"));
+        assert!(event.contains(&"SUMMARY:Call\\, then\\; email"));
+        assert!(event.contains(&"RRULE:FREQ=WEEKLY;INTERVAL=2"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    }
+}
@@ -0,0 +1,21 @@
+use crate::{config, formatting::table::AsciiTable};
+
+/// Format audit log entries as a table to be displayed to the user
+pub fn format_log(entries: &[toado::LogEntry], config: &config::Config) -> String {
+    let rows = entries
+        .iter()
+        .map(|entry| {
+            vec![
+                entry.timestamp.replace('T', " "),
+                entry.action.clone(),
+                entry.description.clone(),
+            ]
+        })
+        .collect();
+
+    AsciiTable::new(rows, &config.table)
+        .seperate_cols(config.table.seperate_cols)
+        .seperate_rows(config.table.seperate_rows)
+        .max_width(super::table_max_width(config))
+        .to_string()
+}
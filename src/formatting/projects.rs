@@ -1,43 +1,213 @@
-use crate::{config, formatting::table::AsciiTable};
+use crate::{
+    config,
+    formatting::{
+        format_col_name, format_display_time, format_item_header, format_notes,
+        table::AsciiTable,
+        table_max_width,
+        tasks::{format_status, status_string},
+    },
+};
 
 /// Format a single project as a string to be displayed to the user
-// pub fn format_project(_project: toado::Project) -> String {
-//     String::from("")
-// }
+pub fn format_project(project: toado::Project, config: &config::Config) -> String {
+    let mut lines: Vec<String> = Vec::new();
 
-/// Format a vector of projects as a string to be displayed to the user
+    // Push project id and or name
+    if let Some(name) = &project.name {
+        lines.extend(format_item_header(name, project.id, config));
+    }
+
+    // Push status
+    if let Some(status) = project.status {
+        lines.push(format!("Status: {}", status_string(status, false, config)));
+    }
+
+    // Push project start and or end time
+    let start_time = project.start_time.filter(|time| !time.is_empty());
+    let end_time = project.end_time.filter(|time| !time.is_empty());
+    if let Some(start_time) = &start_time {
+        lines.push(format!(
+            "Start: {}",
+            format_display_time(start_time, config)
+        ));
+        if let Some(end_time) = &end_time {
+            lines.push(format!(" End: {}", format_display_time(end_time, config)));
+        }
+    } else if let Some(end_time) = &end_time {
+        lines.push(format!("End: {}", format_display_time(end_time, config)));
+    }
+
+    // Push progress
+    if let Some(progress) = project.progress {
+        lines.push(format!("Progress: {}", format_progress(progress)));
+    }
+
+    // Push notes
+    if let Some(notes) = project.notes.filter(|notes| !notes.is_empty()) {
+        lines.push(format_notes(&notes, config));
+    }
+
+    if let Some(created_at) = project.created_at {
+        lines.push(format!("Created: {created_at}"))
+    }
+
+    lines.join("\n")
+}
+
+/// Project columns displayed in a non verbose project list
+const PROJECT_LIST_COLS: [&str; 4] = ["id", "name", "start_time", "end_time"];
+
+/// Project columns displayed in a verbose project list
+const PROJECT_LIST_VERBOSE_COLS: [&str; 7] = [
+    "id",
+    "name",
+    "status",
+    "start_time",
+    "end_time",
+    "progress",
+    "notes",
+];
+
+/// Format a vector of projects as a string to be displayed to the user. If header is true, the
+/// table is displayed with a row of column names
 pub fn format_project_list(
     projects: Vec<toado::Project>,
     verbose: bool,
-    config: &config::TableConfig,
+    header: bool,
+    config: &config::Config,
+) -> String {
+    let cols = if verbose {
+        PROJECT_LIST_VERBOSE_COLS.to_vec()
+    } else {
+        PROJECT_LIST_COLS.to_vec()
+    };
+
+    format_project_table(projects, &toado::QueryCols::Some(cols), header, config)
+}
+
+/// Format a vector of projects as a table of the given columns, in order, to be displayed to the
+/// user. If header is true, the table is displayed with a row of column names
+pub fn format_project_table(
+    projects: Vec<toado::Project>,
+    cols: &toado::QueryCols,
+    header: bool,
+    config: &config::Config,
 ) -> String {
+    let cols = match cols {
+        toado::QueryCols::All => PROJECT_LIST_VERBOSE_COLS.to_vec(),
+        toado::QueryCols::Some(cols) => cols.clone(),
+    };
+
     // Create table from project vector
-    let table = AsciiTable::new(
+    let mut table = AsciiTable::new(
         projects
             .into_iter()
             .map(|project| {
                 // Map project to vector of strings
-                let mut cols = vec![
-                    project
-                        .id
-                        .map_or_else(|| "-".to_string(), |v| v.to_string()),
-                    project.name.unwrap_or("-".to_string()),
-                    project.start_time.unwrap_or("-".to_string()),
-                    project.end_time.unwrap_or("-".to_string()),
-                ];
-
-                if verbose {
-                    cols.push(project.notes.unwrap_or("-".to_string()))
-                }
-
-                cols
+                cols.iter()
+                    .map(|col| format_project_col(&project, col, config))
+                    .collect::<Vec<String>>()
             })
             .collect::<Vec<Vec<String>>>(),
-        config,
+        &config.table,
+    )
+    .seperate_cols(config.table.seperate_cols)
+    .seperate_rows(config.table.seperate_rows)
+    .max_width(table_max_width(config))
+    // Shrink notes before other columns to fit the table
+    .shrink_first(
+        cols.iter()
+            .position(|col| *col == "notes")
+            .into_iter()
+            .collect(),
     );
 
-    table
-        .seperate_cols(config.seperate_cols)
-        .seperate_rows(config.seperate_rows)
-        .to_string()
+    if header {
+        table = table.header(cols.iter().map(|col| format_col_name(col)).collect());
+    }
+
+    table.to_string()
+}
+
+/// Format the value of a project column as a string to be displayed in a table
+fn format_project_col(project: &toado::Project, col: &str, config: &config::Config) -> String {
+    let value = match col {
+        "id" => project.id.map(|v| v.to_string()),
+        "name" => project.name.clone(),
+        "status" => project
+            .status
+            .map(|status| format_status(status, false, config)),
+        "start_time" => project
+            .start_time
+            .as_ref()
+            .map(|time| format_display_time(time, config)),
+        "end_time" => project
+            .end_time
+            .as_ref()
+            .map(|time| format_display_time(time, config)),
+        "notes" => project.notes.clone(),
+        "created_at" => project.created_at.clone(),
+        "progress" => project.progress.map(format_progress),
+        _ => None,
+    };
+
+    value
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| config.table.empty_placeholder.clone())
+}
+
+/// Format project progress as complete and total tasks with a percentage (eg. "1/4 (25%)")
+fn format_progress(progress: toado::Progress) -> String {
+    match progress.percent() {
+        Some(percent) => format!("{}/{} ({percent:.0}%)", progress.complete, progress.total),
+        None => format!("{}/{}", progress.complete, progress.total),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_list_header_names_cols() {
+        let config = config::Config::default();
+        let project: toado::Project =
+            serde_json::from_str(r#"{"id": 1, "name": "project"}"#).unwrap();
+        let output = format_project_list(vec![project], false, true, &config);
+
+        let header = output.lines().next().unwrap();
+        for name in ["ID", "NAME", "START TIME", "END TIME"] {
+            assert!(header.contains(name), "header should contain {name}");
+        }
+    }
+
+    #[test]
+    fn progress_shows_counts_and_percentage() {
+        assert_eq!(
+            format_progress(toado::Progress {
+                complete: 1,
+                total: 4
+            }),
+            "1/4 (25%)"
+        );
+        assert_eq!(
+            format_progress(toado::Progress {
+                complete: 0,
+                total: 0
+            }),
+            "0/0"
+        );
+    }
+
+    #[test]
+    fn empty_cells_use_configured_placeholder() {
+        let mut config = config::Config::default();
+        config.table.empty_placeholder = "n/a".to_string();
+        config.table.fit_to_terminal = false;
+        let project: toado::Project =
+            serde_json::from_str(r#"{"id": 1, "name": "project"}"#).unwrap();
+
+        let output = format_project_list(vec![project], false, false, &config);
+        assert_eq!(output.matches("n/a").count(), 2, "{output}");
+    }
 }
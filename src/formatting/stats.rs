@@ -0,0 +1,30 @@
+use crate::{
+    config,
+    formatting::table::{Align, AsciiTable},
+};
+
+/// Format database statistics as a table to be displayed to the user
+pub fn format_stats(stats: &toado::Stats, config: &config::Config) -> String {
+    let rows = vec![
+        vec!["Incomplete".to_string(), stats.incomplete.to_string()],
+        vec!["In progress".to_string(), stats.in_progress.to_string()],
+        vec!["Complete".to_string(), stats.complete.to_string()],
+        vec!["Archived".to_string(), stats.archived.to_string()],
+        vec!["Overdue".to_string(), stats.overdue.to_string()],
+        vec!["Projects".to_string(), stats.projects.to_string()],
+        vec![
+            "Average priority".to_string(),
+            stats
+                .average_priority
+                .map_or(config.table.empty_placeholder.clone(), |average| {
+                    format!("{average:.1}")
+                }),
+        ],
+    ];
+
+    AsciiTable::new(rows, &config.table)
+        .seperate_cols(config.table.seperate_cols)
+        .seperate_rows(config.table.seperate_rows)
+        .align_cols(vec![Align::Left, Align::Right])
+        .to_string()
+}
@@ -8,21 +8,39 @@ where
     T: Display,
 {
     rows: Vec<Vec<T>>,
+    header: Option<Vec<T>>,
     seperate_cols: bool,
     seperate_rows: bool,
+    col_aligns: Vec<Align>,
+    max_width: Option<usize>,
+    shrink_first: Vec<usize>,
     config: &'a config::TableConfig,
 }
 
+/// Minimum width columns are shrunk to when fitting a table to a maximum width
+const MIN_COL_WIDTH: usize = 3;
+
+/// Alignment of values in a table column
+#[derive(Clone, Copy)]
+pub enum Align {
+    Left,
+    Right,
+}
+
 impl<T> AsciiTable<'_, T>
 where
     T: Display,
 {
     /// Creates an AsciiTable
-    pub fn new(rows: Vec<Vec<T>>, config: &config::TableConfig) -> AsciiTable<T> {
+    pub fn new(rows: Vec<Vec<T>>, config: &config::TableConfig) -> AsciiTable<'_, T> {
         AsciiTable {
             rows,
+            header: None,
             seperate_cols: true,
             seperate_rows: false,
+            col_aligns: Vec::new(),
+            max_width: None,
+            shrink_first: Vec::new(),
             config,
         }
     }
@@ -39,17 +57,42 @@ where
         self
     }
 
-    /// Calculates the length of the longest value in each column of the table.
-    /// Returns vector of said values
-    fn calc_col_lengths(rows: &[Vec<T>]) -> Vec<usize> {
-        let mut rows = rows.iter();
+    /// Sets the alignment of each column by index. Columns without an alignment are left aligned
+    pub fn align_cols(mut self, aligns: Vec<Align>) -> Self {
+        self.col_aligns = aligns;
+        self
+    }
+
+    /// Sets the maximum display width of the table. If the table is wider, columns are shrunk in
+    /// proportion to their widths to fit, truncating their values with an ellipsis
+    pub fn max_width(mut self, width: Option<usize>) -> Self {
+        self.max_width = width;
+        self
+    }
+
+    /// Sets the columns, by index, shrunk before any others when fitting the table to its maximum
+    /// width
+    pub fn shrink_first(mut self, cols: Vec<usize>) -> Self {
+        self.shrink_first = cols;
+        self
+    }
+
+    /// Sets a header row displayed above the table, seperated from the rows by a line
+    pub fn header(mut self, header: Vec<T>) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Calculates the length of the longest value in each column of the table, including the
+    /// header row if present. Returns vector of said values
+    fn calc_col_lengths(header: Option<&Vec<String>>, rows: &[Vec<String>]) -> Vec<usize> {
+        let mut rows = header.into_iter().chain(rows.iter());
         if let Some(cols) = rows.next() {
-            let mut col_lengths: Vec<usize> =
-                cols.iter().map(|value| value.to_string().len()).collect();
+            let mut col_lengths: Vec<usize> = cols.iter().map(|value| display_len(value)).collect();
 
             for cols in rows {
                 for (i, val) in cols.iter().enumerate() {
-                    let length = val.to_string().len();
+                    let length = display_len(val);
 
                     if length > col_lengths[i] {
                         col_lengths[i] = length;
@@ -62,6 +105,72 @@ where
             Vec::new()
         }
     }
+
+    /// Shrinks columns until the total width of the table fits within max_width or all columns
+    /// are at the minimum width. Columns set to shrink first are shrunk before any others, then the
+    /// remaining columns are shrunk in proportion to how much wider than the minimum they are
+    fn fit_col_lengths(&self, col_lengths: &mut [usize], max_width: usize) {
+        // Each column is padded by one space, and columns are seperated by a seperator string
+        let seperator_width = if self.seperate_cols { 2 } else { 1 };
+        let table_width = col_lengths.iter().map(|length| length + 1).sum::<usize>()
+            + col_lengths.len().saturating_sub(1) * seperator_width;
+        let mut excess = table_width.saturating_sub(max_width);
+
+        for &i in &self.shrink_first {
+            if let Some(length) = col_lengths.get_mut(i) {
+                let cut = excess.min(length.saturating_sub(MIN_COL_WIDTH));
+                *length -= cut;
+                excess -= cut;
+            }
+        }
+
+        let shrinkable = col_lengths
+            .iter()
+            .map(|length| length.saturating_sub(MIN_COL_WIDTH))
+            .sum::<usize>();
+        let mut excess = excess.min(shrinkable);
+        if excess == 0 {
+            return;
+        }
+
+        let cuts = col_lengths
+            .iter()
+            .map(|length| length.saturating_sub(MIN_COL_WIDTH) * excess / shrinkable)
+            .collect::<Vec<usize>>();
+        for (length, cut) in col_lengths.iter_mut().zip(cuts) {
+            *length -= cut;
+            excess -= cut;
+        }
+
+        // Take what rounding down left over from the widest columns
+        while excess > 0 {
+            match col_lengths
+                .iter_mut()
+                .filter(|length| **length > MIN_COL_WIDTH)
+                .max_by_key(|length| **length)
+            {
+                Some(length) => *length -= 1,
+                None => break,
+            }
+            excess -= 1;
+        }
+    }
+
+    /// Converts a row of values into strings, truncating values wider than the max column width
+    /// with an ellipsis
+    fn row_strings(&self, row: &[T]) -> Vec<String> {
+        row.iter()
+            .map(|val| {
+                let val = val.to_string();
+                match self.config.max_col_width {
+                    Some(width) if width > 0 && display_len(&val) > width => {
+                        console::truncate_str(&val, width, "…").to_string()
+                    }
+                    _ => val,
+                }
+            })
+            .collect()
+    }
 }
 
 impl<T> Display for AsciiTable<'_, T>
@@ -69,7 +178,13 @@ where
     T: Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let col_lengths = AsciiTable::calc_col_lengths(&self.rows);
+        let header = self.header.as_ref().map(|header| self.row_strings(header));
+        let rows: Vec<Vec<String>> = self.rows.iter().map(|row| self.row_strings(row)).collect();
+
+        let mut col_lengths = AsciiTable::<T>::calc_col_lengths(header.as_ref(), &rows);
+        if let Some(max_width) = self.max_width {
+            self.fit_col_lengths(&mut col_lengths, max_width);
+        }
 
         let col_seperator = if self.seperate_cols {
             format!("{} ", self.config.vertical)
@@ -77,45 +192,169 @@ where
             " ".to_string()
         };
 
-        let row_seperator = if self.seperate_rows {
-            let cross_string = if self.seperate_cols {
-                format!(
-                    "{}{}",
-                    self.config.vertical_horizontal, self.config.horizontal
-                )
-            } else {
-                self.config.horizontal.to_string().repeat(2)
-            };
-
+        let cross_string = if self.seperate_cols {
             format!(
-                "\n{}\n",
-                col_lengths
-                    .clone()
-                    .into_iter()
-                    .map(|length| self.config.horizontal.to_string().repeat(length + 1))
-                    .collect::<Vec<String>>()
-                    .join(&cross_string)
+                "{}{}",
+                self.config.vertical_horizontal, self.config.horizontal
             )
+        } else {
+            self.config.horizontal.to_string().repeat(2)
+        };
+
+        // Horizontal line spanning the width of the table
+        let rule = col_lengths
+            .iter()
+            .map(|length| self.config.horizontal.to_string().repeat(length + 1))
+            .collect::<Vec<String>>()
+            .join(&cross_string);
+
+        let row_seperator = if self.seperate_rows {
+            format!("\n{rule}\n")
         } else {
             "\n".to_string()
         };
 
-        let table_str = self
-            .rows
+        let format_row = |col: &Vec<String>| {
+            col.iter()
+                .enumerate()
+                .map(|(i, val)| {
+                    // Truncate values wider than their column
+                    let val = if display_len(val) > col_lengths[i] {
+                        console::truncate_str(val, col_lengths[i], "…")
+                    } else {
+                        std::borrow::Cow::from(val)
+                    };
+                    let len_dif = col_lengths[i] - display_len(&val);
+                    // Add padding to value string
+                    match self.col_aligns.get(i).unwrap_or(&Align::Left) {
+                        Align::Left => format!("{val}{}", " ".repeat(len_dif + 1)),
+                        Align::Right => format!("{}{val} ", " ".repeat(len_dif)),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(&col_seperator) // Join columns of strings into single string
+        };
+
+        let table_str = rows
             .iter()
-            .map(|col| {
-                col.iter()
-                    .enumerate()
-                    .map(|(i, val)| {
-                        let len_dif = col_lengths[i] - val.to_string().len();
-                        format!("{val}{}", " ".repeat(len_dif + 1)) // Add padding to value string
-                    })
-                    .collect::<Vec<String>>()
-                    .join(&col_seperator) // Join columns of strings into single string
-            })
+            .map(format_row)
             .collect::<Vec<String>>()
             .join(&row_seperator); // Join rows of strings into single string
 
+        if let Some(header) = &header {
+            write!(f, "{}\n{rule}", format_row(header))?;
+            if !rows.is_empty() {
+                writeln!(f)?;
+            }
+        }
+
         write!(f, "{table_str}")
     }
 }
+
+/// Calculates the display width of a value string in terminal columns, accounting for wide and
+/// multi-byte characters, and ignoring ANSI escape codes
+fn display_len(value: &str) -> usize {
+    console::measure_text_width(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(config: &config::TableConfig) -> AsciiTable<'_, String> {
+        AsciiTable::new(
+            vec![vec![
+                "1".to_string(),
+                "a fairly long task name".to_string(),
+                "some notes that are much longer than the name of the task".to_string(),
+            ]],
+            config,
+        )
+        .header(vec![
+            "ID".to_string(),
+            "NAME".to_string(),
+            "NOTES".to_string(),
+        ])
+    }
+
+    #[test]
+    fn col_lengths_ignore_ansi_codes() {
+        let config = config::Config::default().table;
+        let styled = console::style("done")
+            .green()
+            .force_styling(true)
+            .to_string();
+        assert_ne!(styled.len(), "done".len());
+
+        let styled_output = AsciiTable::new(vec![vec![styled, "task".to_string()]], &config);
+        let plain_output = AsciiTable::new(vec![vec!["done", "task"]], &config);
+        assert_eq!(
+            console::strip_ansi_codes(&styled_output.to_string()),
+            plain_output.to_string()
+        );
+    }
+
+    #[test]
+    fn col_edges_align_with_wide_characters() {
+        let config = config::Config::default().table;
+        let output = AsciiTable::new(
+            vec![vec!["café", "1"], vec!["任务", "2"], vec!["task", "3"]],
+            &config,
+        )
+        .to_string();
+
+        let edges = output
+            .lines()
+            .map(|line| display_len(&line[..line.find(config.vertical).unwrap()]))
+            .collect::<Vec<usize>>();
+        assert_eq!(edges, [5, 5, 5]);
+    }
+
+    #[test]
+    fn right_aligned_cols_are_padded_on_the_left() {
+        let config = config::Config::default().table;
+        let output = AsciiTable::new(
+            vec![vec!["1", "a"], vec!["22", "b"], vec!["333", "c"]],
+            &config,
+        )
+        .align_cols(vec![Align::Right])
+        .to_string();
+
+        assert_eq!(
+            output.lines().collect::<Vec<&str>>(),
+            ["  1 │ a ", " 22 │ b ", "333 │ c "]
+        );
+    }
+
+    #[test]
+    fn max_width_fits_every_line() {
+        let config = config::Config::default().table;
+        for width in [20, 40, 60, 80] {
+            let output = table(&config).max_width(Some(width)).to_string();
+            for line in output.lines() {
+                assert!(display_len(line) <= width, "{line:?} is wider than {width}");
+            }
+        }
+    }
+
+    #[test]
+    fn max_width_shrinks_first_cols_first() {
+        let config = config::Config::default().table;
+        let output = table(&config)
+            .max_width(Some(60))
+            .shrink_first(vec![2])
+            .to_string();
+        assert!(output.contains("a fairly long task name"));
+        assert!(output.contains('…'));
+    }
+
+    #[test]
+    fn max_width_shrinks_cols_proportionally() {
+        let config = config::Config::default().table;
+        let table = table(&config);
+        let mut col_lengths = vec![3, 23, 57];
+        table.fit_col_lengths(&mut col_lengths, 50);
+        assert_eq!(col_lengths, vec![3, 13, 27]);
+    }
+}
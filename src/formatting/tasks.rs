@@ -1,28 +1,24 @@
-use crate::{config, formatting::table::AsciiTable};
+use crate::{
+    config,
+    formatting::{
+        format_col_name, format_display_time, format_item_header, format_notes,
+        table::{Align, AsciiTable},
+        table_max_width,
+    },
+};
 
 /// Format a single task as a string to be displayed to the user
 pub fn format_task(task: toado::Task, config: &config::Config) -> String {
     let mut lines: Vec<String> = Vec::new();
 
     // Push task id and or name
-    if let Some(name) = task.name {
-        let name_l = name.len();
-
-        if let Some(id) = task.id {
-            let id = id.to_string();
-            let id_l = id.len();
-
-            lines.push(format!("{} {} {}", name, config.table.vertical, id));
-            lines.push(format!(
-                "{}{}{}",
-                config.table.horizontal.to_string().repeat(name_l + 1),
-                config.table.up_horizontal,
-                config.table.horizontal.to_string().repeat(id_l + 1)
-            ))
-        } else {
-            lines.push(name);
-            lines.push(config.table.horizontal.to_string().repeat(name_l))
-        }
+    if let Some(name) = &task.name {
+        lines.extend(format_item_header(name, task.id, config));
+    }
+
+    // Push parent task
+    if let Some(parent_id) = task.parent_id {
+        lines.push(format!("Parent: {parent_id}"));
     }
 
     // Push task priority
@@ -32,17 +28,23 @@ pub fn format_task(task: toado::Task, config: &config::Config) -> String {
 
     // Push status
     if let Some(status) = task.status {
-        lines.push(format!("Status: {}", status.to_string().to_uppercase()));
+        lines.push(format!(
+            "Status: {}",
+            status_string(status, task.blocked.unwrap_or(false), config)
+        ));
     }
 
     // Push task start and or end time
     if let Some(start_time) = task.start_time {
-        lines.push(format!("Start: {start_time}"));
+        lines.push(format!(
+            "Start: {}",
+            format_display_time(&start_time, config)
+        ));
         if let Some(end_time) = task.end_time {
-            lines.push(format!(" End: {end_time}"));
+            lines.push(format!(" End: {}", format_display_time(&end_time, config)));
         }
     } else if let Some(end_time) = task.end_time {
-        lines.push(format!("End: {end_time}"));
+        lines.push(format!("End: {}", format_display_time(&end_time, config)));
     }
 
     // Push repeat
@@ -50,6 +52,11 @@ pub fn format_task(task: toado::Task, config: &config::Config) -> String {
         lines.push(format!("Repeats: {repeat}"));
     }
 
+    // Push owner
+    if let Some(owner) = task.owner {
+        lines.push(format!("Owner: {owner}"));
+    }
+
     // Push projects
     // if let Some(projects) = task.projects {
     //     lines.push(format!("Projects: {}", projects.join(", ")));
@@ -57,45 +64,406 @@ pub fn format_task(task: toado::Task, config: &config::Config) -> String {
 
     // Push notes
     if let Some(notes) = task.notes {
-        lines.push(format!("Notes: {notes}"))
+        lines.push(format_notes(&notes, config))
+    }
+
+    // Push creation, update, and completion times
+    if let Some(created_at) = task.created_at {
+        lines.push(format!("Created: {created_at}"))
+    }
+    if let Some(updated_at) = task.updated_at {
+        lines.push(format!("Updated: {updated_at}"))
+    }
+    if let Some(completed_at) = task.completed_at {
+        lines.push(format!("Completed: {completed_at}"))
     }
 
     lines.join("\n")
 }
 
-/// Format a vector of tasks as a string to be displayed to the user
+/// Task columns displayed in a non verbose task list
+const TASK_LIST_COLS: [&str; 4] = ["id", "name", "priority", "status"];
+
+/// Task columns displayed in a verbose task list
+const TASK_LIST_VERBOSE_COLS: [&str; 9] = [
+    "id",
+    "name",
+    "priority",
+    "status",
+    "start_time",
+    "end_time",
+    "repeat",
+    "notes",
+    "completed_at",
+];
+
+/// Format a vector of tasks as a string to be displayed to the user. If header is true, the
+/// table is displayed with a row of column names
 pub fn format_task_list(
     tasks: Vec<toado::Task>,
     verbose: bool,
-    config: &config::TableConfig,
+    header: bool,
+    config: &config::Config,
 ) -> String {
-    let table = AsciiTable::new(
+    let cols = if verbose {
+        TASK_LIST_VERBOSE_COLS.to_vec()
+    } else {
+        TASK_LIST_COLS.to_vec()
+    };
+
+    format_task_table(
+        tasks,
+        &toado::QueryCols::Some(cols),
+        header,
+        &chrono::Local::now().naive_local(),
+        config,
+    )
+}
+
+/// Format groups of tasks as tables of the given columns, each under a header of the group name
+pub fn format_grouped_task_tables(
+    groups: Vec<(String, Vec<toado::Task>)>,
+    cols: &toado::QueryCols,
+    header: bool,
+    now: &chrono::NaiveDateTime,
+    config: &config::Config,
+) -> String {
+    groups
+        .into_iter()
+        .map(|(name, tasks)| {
+            let mut lines = format_item_header(&name, None, config);
+            lines.push(format_task_table(tasks, cols, header, now, config));
+            lines.join("\n")
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+/// Format a vector of tasks as a table of the given columns, in order, to be displayed to the
+/// user. If header is true, the table is displayed with a row of column names. Tasks overdue at
+/// the time now have their name marked
+pub fn format_task_table(
+    tasks: Vec<toado::Task>,
+    cols: &toado::QueryCols,
+    header: bool,
+    now: &chrono::NaiveDateTime,
+    config: &config::Config,
+) -> String {
+    let cols = match cols {
+        toado::QueryCols::All => TASK_LIST_VERBOSE_COLS.to_vec(),
+        toado::QueryCols::Some(cols) => cols.clone(),
+    };
+
+    let mut table = AsciiTable::new(
         tasks
             .into_iter()
             .map(|task| {
-                let mut cols = vec![
-                    task.id.map_or_else(|| "-".to_string(), |v| v.to_string()),
-                    task.name.unwrap_or("-".to_string()),
-                    task.priority
-                        .map_or_else(|| "-".to_string(), |v| v.to_string()),
-                    task.status
-                        .map_or_else(|| "-".to_string(), |v| v.to_string().to_uppercase()),
-                ];
-                if verbose {
-                    // If verbose, add all task cols to display table
-                    cols.push(task.start_time.unwrap_or("-".to_string()));
-                    cols.push(task.end_time.unwrap_or("-".to_string()));
-                    cols.push(task.repeat.unwrap_or("-".to_string()));
-                    cols.push(task.notes.unwrap_or("-".to_string()));
-                }
-                cols
+                let overdue = task.is_overdue(now);
+                cols.iter()
+                    .map(|col| format_task_col(&task, col, overdue, config))
+                    .collect::<Vec<String>>()
             })
             .collect::<Vec<Vec<String>>>(),
-        config,
+        &config.table,
+    )
+    .seperate_cols(config.table.seperate_cols)
+    .seperate_rows(config.table.seperate_rows)
+    .max_width(table_max_width(config))
+    // Shrink notes before other columns to fit the table
+    .shrink_first(
+        cols.iter()
+            .position(|col| *col == "notes")
+            .into_iter()
+            .collect(),
+    )
+    .align_cols(
+        // Right align id and priority
+        cols.iter()
+            .map(|col| match *col {
+                "id" | "priority" => Align::Right,
+                _ => Align::Left,
+            })
+            .collect(),
     );
 
-    table
-        .seperate_cols(config.seperate_cols)
-        .seperate_rows(config.seperate_rows)
-        .to_string()
+    if header {
+        table = table.header(cols.iter().map(|col| format_col_name(col)).collect());
+    }
+
+    table.to_string()
+}
+
+/// Format the value of a task column as a string to be displayed in a table. The names of overdue
+/// tasks are prefixed with "!", and colored red if colors are enabled. Priorities at or above the
+/// configured high threshold are colored yellow if colors are enabled
+fn format_task_col(
+    task: &toado::Task,
+    col: &str,
+    overdue: bool,
+    config: &config::Config,
+) -> String {
+    let value = match col {
+        "id" => task.id.map(|v| v.to_string()),
+        "name" if overdue => task.name.as_ref().map(|name| {
+            let name = format!("! {name}");
+            if config.display.colors {
+                console::style(name).red().to_string()
+            } else {
+                name
+            }
+        }),
+        "name" => task.name.clone(),
+        "priority" => task
+            .priority
+            .map(|priority| match config.display.priority_high_threshold {
+                Some(threshold) if config.display.colors && priority >= threshold => {
+                    console::style(priority).yellow().to_string()
+                }
+                _ => priority.to_string(),
+            }),
+        "status" => task
+            .status
+            .map(|v| format_status(v, task.blocked.unwrap_or(false), config)),
+        "start_time" => task
+            .start_time
+            .as_ref()
+            .map(|time| format_display_time(time, config)),
+        "end_time" => task
+            .end_time
+            .as_ref()
+            .map(|time| format_display_time(time, config)),
+        "repeat" => task.repeat.clone(),
+        "notes" => task.notes.clone(),
+        "parent_id" => task.parent_id.map(|v| v.to_string()),
+        "created_at" => task.created_at.clone(),
+        "completed_at" => task.completed_at.clone(),
+        "owner" => task.owner.clone(),
+        "updated_at" => task.updated_at.clone(),
+        _ => None,
+    };
+
+    value
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| config.table.empty_placeholder.clone())
+}
+
+/// Format an item status as an uppercase string, colored by status if colors are enabled
+pub(super) fn format_status(
+    status: toado::ItemStatus,
+    blocked: bool,
+    config: &config::Config,
+) -> String {
+    let status_string = status_string(status, blocked, config);
+
+    if !config.display.colors {
+        return status_string;
+    }
+
+    let styled = console::style(status_string);
+    match status {
+        status if blocked && status.is_open() => styled.red(),
+        toado::ItemStatus::Incomplete => styled.yellow(),
+        toado::ItemStatus::InProgress => styled.cyan(),
+        toado::ItemStatus::Complete => styled.green(),
+        toado::ItemStatus::Archived => styled.dim(),
+    }
+    .to_string()
+}
+
+/// Format an item status as its configured symbol, or else as an uppercase string. Incomplete or in
+/// progress tasks with incomplete dependencies are displayed as blocked
+pub(super) fn status_string(
+    status: toado::ItemStatus,
+    blocked: bool,
+    config: &config::Config,
+) -> String {
+    let blocked = blocked && status.is_open();
+    let symbols = &config.display.status_symbols;
+    let symbol = match status {
+        _ if blocked => &symbols.blocked,
+        toado::ItemStatus::Incomplete => &symbols.incomplete,
+        toado::ItemStatus::InProgress => &symbols.in_progress,
+        toado::ItemStatus::Complete => &symbols.complete,
+        toado::ItemStatus::Archived => &symbols.archived,
+    };
+
+    match (symbol, blocked) {
+        (Some(symbol), _) => symbol.clone(),
+        (None, true) => "BLOCKED".to_string(),
+        (None, false) => status.to_string().to_uppercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deserializes a task from JSON, with unset values being None
+    fn task(json: &str) -> toado::Task {
+        serde_json::from_str(json).expect("task should deserialize")
+    }
+
+    #[test]
+    fn task_list_header_names_cols() {
+        let config = config::Config::default();
+        let output = format_task_list(
+            vec![task(
+                r#"{"id": 1, "name": "task", "priority": 0, "status": "incomplete"}"#,
+            )],
+            false,
+            true,
+            &config,
+        );
+
+        let header = output.lines().next().unwrap();
+        for name in ["ID", "NAME", "PRIORITY", "STATUS"] {
+            assert!(header.contains(name), "header should contain {name}");
+        }
+    }
+
+    #[test]
+    fn long_notes_are_truncated_to_max_col_width() {
+        let mut config = config::Config::default();
+        config.table.fit_to_terminal = false;
+        config.table.max_col_width = Some(20);
+
+        let notes = "é".repeat(200);
+        let output = format_task_list(
+            vec![task(&format!(
+                r#"{{"id": 1, "name": "task", "notes": "{notes}"}}"#
+            ))],
+            true,
+            false,
+            &config,
+        );
+
+        let truncated = format!("{}…", "é".repeat(19));
+        assert!(output.contains(&format!("{truncated} ")), "{output}");
+        assert!(!output.contains(&"é".repeat(20)));
+    }
+
+    #[test]
+    fn overdue_task_names_are_marked() {
+        let mut config = config::Config::default();
+        config.display.colors = false;
+        config.table.fit_to_terminal = false;
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 6, 15)
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .unwrap();
+
+        let output = format_task_table(
+            vec![
+                task(r#"{"name": "late", "status": "incomplete", "end_time": "2024-06-15T09:00"}"#),
+                task(r#"{"name": "today", "status": "incomplete", "end_time": "2024-06-15"}"#),
+                task(r#"{"name": "soon", "status": "incomplete", "end_time": "2024-06-16"}"#),
+                task(r#"{"name": "done", "status": "complete", "end_time": "2024-06-01"}"#),
+            ],
+            &toado::QueryCols::Some(vec!["name"]),
+            false,
+            &now,
+            &config,
+        );
+
+        let names = output.lines().map(str::trim_end).collect::<Vec<&str>>();
+        assert_eq!(names, ["! late", "today", "soon", "done"]);
+    }
+
+    #[test]
+    fn colors_off_emits_no_ansi_codes() {
+        let mut config = config::Config::default();
+        config.display.colors = false;
+        config.display.priority_high_threshold = Some(1);
+        config.table.fit_to_terminal = false;
+
+        let tasks = vec![
+            task(
+                r#"{"id": 1, "name": "late", "priority": 5, "status": "incomplete", "end_time": "2000-01-01"}"#,
+            ),
+            task(r#"{"id": 2, "name": "started", "priority": 0, "status": "in_progress"}"#),
+            task(r#"{"id": 3, "name": "done", "priority": 0, "status": "complete"}"#),
+        ];
+        let list = format_task_list(tasks.clone(), true, true, &config);
+        assert!(!list.contains('\x1b'), "{list:?}");
+
+        let single = format_task(tasks[0].clone(), &config);
+        assert!(!single.contains('\x1b'), "{single:?}");
+    }
+
+    #[test]
+    fn empty_cells_use_configured_placeholder() {
+        let mut config = config::Config::default();
+        config.table.empty_placeholder = "n/a".to_string();
+        config.table.fit_to_terminal = false;
+        config.display.colors = false;
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 6, 15)
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .unwrap();
+
+        let output = format_task_table(
+            vec![task(r#"{"name": "task", "status": "incomplete"}"#)],
+            &toado::QueryCols::Some(vec!["name", "end_time"]),
+            false,
+            &now,
+            &config,
+        );
+        assert!(output.trim_end().ends_with("n/a"), "{output}");
+    }
+
+    #[test]
+    fn priorities_at_threshold_are_colored() {
+        // Styling is otherwise disabled when stdout is not a terminal
+        console::set_colors_enabled(true);
+        let mut config = config::Config::default();
+        config.display.priority_high_threshold = Some(3);
+        config.table.fit_to_terminal = false;
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 6, 15)
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .unwrap();
+
+        let output = format_task_table(
+            vec![
+                task(r#"{"name": "high", "priority": 5}"#),
+                task(r#"{"name": "equal", "priority": 3}"#),
+                task(r#"{"name": "low", "priority": 1}"#),
+            ],
+            &toado::QueryCols::Some(vec!["priority"]),
+            false,
+            &now,
+            &config,
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].contains('\x1b'), "{:?}", lines[0]);
+        assert!(lines[0].contains(&console::style(5).yellow().to_string()));
+        assert!(lines[1].contains(&console::style(3).yellow().to_string()));
+        assert!(!lines[2].contains('\x1b'), "{:?}", lines[2]);
+    }
+
+    #[test]
+    fn status_column_shows_configured_symbols() {
+        let mut config = config::Config::default();
+        config.display.colors = false;
+        config.table.fit_to_terminal = false;
+        config.display.status_symbols = config::StatusSymbols {
+            complete: Some("✓".to_string()),
+            incomplete: Some("○".to_string()),
+            ..config::StatusSymbols::default()
+        };
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 6, 15)
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .unwrap();
+
+        let output = format_task_table(
+            vec![
+                task(r#"{"status": "complete"}"#),
+                task(r#"{"status": "incomplete"}"#),
+                task(r#"{"status": "archived"}"#),
+            ],
+            &toado::QueryCols::Some(vec!["status"]),
+            false,
+            &now,
+            &config,
+        );
+        let statuses: Vec<&str> = output.lines().map(str::trim_end).collect();
+        assert_eq!(statuses, ["✓", "○", "ARCHIVED"]);
+    }
 }
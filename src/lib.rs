@@ -6,11 +6,19 @@ pub use queries::{
     OrderBy, OrderDir, QueryCols, QueryConditions, RowLimit, SelectTasksQuery, UpdateAction,
     UpdateTaskCols, UpdateTaskQuery,
 };
-use std::{error, fmt, path::Path, usize};
+use serde_derive::{Deserialize, Serialize};
+use std::{collections::HashMap, error, fmt, path::Path};
 
 use crate::queries::AddTaskQuery;
+use crate::recurrence::Recurrence;
 
+mod migrations;
 pub mod queries;
+pub mod recurrence;
+pub mod time;
+
+/// Maximum number of copies of a task added at once
+const MAX_CLONES: usize = 1000;
 
 /// Toado application server
 pub struct Server {
@@ -20,6 +28,25 @@ pub struct Server {
 
 pub type Error = Box<dyn error::Error>;
 
+/// Errors with a known cause, which callers can distinguish from other errors by downcasting
+#[derive(Debug)]
+pub enum ToadoError {
+    /// No item matches a search term or id
+    NotFound(String),
+    /// Input is not valid
+    Input(String),
+}
+
+impl fmt::Display for ToadoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(message) | Self::Input(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl error::Error for ToadoError {}
+
 impl Server {
     /// Opens a new toado app server with an sqlite database file.
     /// If the sqlite file does not exist, one is created at the path.
@@ -33,10 +60,24 @@ impl Server {
     {
         let connection = rusqlite::Connection::open(file_path)?;
 
+        // Unicode aware lower casing, as the built in LOWER function only folds ASCII characters
+        connection.create_scalar_function(
+            "fold_case",
+            1,
+            rusqlite::functions::FunctionFlags::SQLITE_UTF8
+                | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx| {
+                Ok(ctx
+                    .get::<Option<String>>(0)?
+                    .map(|value| value.to_lowercase()))
+            },
+        )?;
+
         Ok(Server { connection })
     }
 
-    /// Initializes the application server by creating database tables
+    /// Initializes the application server by creating database tables and applying any pending
+    /// schema migrations
     ///
     /// # Errors
     ///
@@ -78,27 +119,95 @@ impl Server {
             Tables::TaskAssignments
         ))?;
 
+        migrations::migrate(&self.connection)?;
+
         Ok(())
     }
 
+    /// Returns the path of the database file, or None if the database is in memory
+    pub fn path(&self) -> Option<&str> {
+        self.connection.path().filter(|path| !path.is_empty())
+    }
+
+    /// Returns the database schema version, the number of schema migrations that have been applied
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the sql statment fails to execute
+    pub fn schema_version(&self) -> Result<usize, Error> {
+        Ok(self
+            .connection
+            .query_row("PRAGMA user_version", (), |row| row.get(0))?)
+    }
+
+    /// Runs a closure in a single transaction, committing its changes if it returns Ok, and rolling
+    /// them back if it returns an error. If already in a transaction, changes are committed with
+    /// the outer transaction. Returns the value returned by the closure
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the closure returns an error, or if beginning or committing the
+    /// transaction fails
+    pub fn with_transaction<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&Self) -> Result<T, Error>,
+    {
+        let transaction = self.transaction_if_needed()?;
+
+        // Dropping the transaction without committing it rolls back its changes
+        let value = f(self)?;
+
+        if let Some(transaction) = transaction {
+            transaction.commit()?;
+        }
+        Ok(value)
+    }
+
+    /// Add a new task to the database and assign it to a project in a single transaction, so if
+    /// the assignment fails, the task is not added. Returns id of added task
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if execution of the sql statments fails
+    pub fn add_assigned_task(&self, args: AddTaskArgs, project_id: i64) -> Result<i64, Error> {
+        self.with_transaction(|server| {
+            let task_id = server.add_task(args)?;
+            server.assign_task(task_id, project_id)?;
+            Ok(task_id)
+        })
+    }
+
     /// Add a new task to the database. Returns id of added task
     ///
     /// # Errors:
     ///
     /// Will return an error if execution of the sql statment fails
     pub fn add_task(&self, args: AddTaskArgs) -> Result<i64, Error> {
-        let query = AddTaskQuery::new(
-            args.name,
-            args.priority,
-            args.start_time,
-            args.end_time,
-            args.repeat,
-            args.notes,
-        );
+        let name = args.name.clone();
+        let query = AddTaskQuery::from(args);
 
-        self.connection.execute(&query.to_string(), ())?;
+        self.with_transaction(|server| {
+            server.connection.execute(&query.to_string(), ())?;
+            let id = server.connection.last_insert_rowid();
+            server.log_event("create", &format!("task {id} '{name}'"))?;
+            Ok(id)
+        })
+    }
 
-        Ok(self.connection.last_insert_rowid())
+    /// Add multiple tasks to the database in a single transaction. If adding any of the tasks
+    /// fails, no tasks are added. Returns ids of added tasks
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of any of the sql statments fails
+    pub fn add_tasks(&self, tasks: Vec<AddTaskArgs>) -> Result<Vec<i64>, Error> {
+        self.with_transaction(|server| {
+            let ids = tasks
+                .into_iter()
+                .map(|args| server.add_task(args))
+                .collect::<Result<Vec<i64>, Error>>()?;
+            Ok(ids)
+        })
     }
 
     /// Delete tasks from the database. Deletes all tasks matching query if is Some, if None deletes
@@ -108,42 +217,296 @@ impl Server {
     ///
     /// Will return an error if execution of the sql statment fails
     pub fn delete_task(&self, condition: Option<String>) -> Result<u64, Error> {
-        // Create delete query
-        let query = DeleteTaskQuery::new(condition);
-        // Execute query
-        self.connection.execute(&query.to_string(), ())?;
-        // Return number of rows deleted
-        Ok(self.connection.changes())
+        self.with_transaction(|server| {
+            // Get deleted tasks to log
+            let tasks = server.select_task_matches(condition.clone())?;
+
+            // Create delete query
+            let query = DeleteTaskQuery::new(condition);
+            // Execute query
+            server.connection.execute(&query.to_string(), ())?;
+            let changes = server.connection.changes();
+
+            for (id, name) in tasks {
+                server.log_event("delete", &format!("task {id} '{name}'"))?;
+            }
+
+            // Return number of rows deleted
+            Ok(changes)
+        })
     }
 
     /// Update tasks from the database with optional query. Only rows matching query will be
-    /// updated. If no query provided, all rows in table will be updated. Returns the number of
-    /// rows modified by update
+    /// updated. If no query provided, all rows in table will be updated. Tasks that become complete
+    /// have their completion time set, and tasks that become incomplete have it cleared. Returns
+    /// the number of rows modified by update
     ///
     /// # Errors:
     ///
-    /// Will return an error if execution of the sql statment fails
+    /// Will return an error if execution of the sql statments fails
     pub fn update_task(
         &self,
         condition: Option<String>,
         args: UpdateTaskArgs,
     ) -> Result<u64, Error> {
-        self.connection.execute(
-            &UpdateTaskQuery {
-                condition,
-                name: args.name,
-                priority: args.priority,
-                status: args.status,
-                start_time: args.start_time,
-                end_time: args.end_time,
-                repeat: args.repeat,
-                notes: args.notes,
+        // Run in a transaction, unless already in one (ie. when rescheduling a task)
+        self.with_transaction(|server| {
+            // Get updated tasks to log before the update, as the condition may match updated
+            // columns
+            let tasks = server.select_task_matches(condition.clone())?;
+            let (action, details) = match args.status {
+                UpdateAction::Some(ItemStatus::Complete) => ("check", String::new()),
+                _ => ("update", format!(" ({})", args.updated_cols().join(", "))),
+            };
+
+            // Update completion times before the update, as the condition may match updated columns
+            let completed_at = match args.status {
+                UpdateAction::Some(ItemStatus::Complete) => Some((
+                    format!("'{}'", time::now()),
+                    format!("status != {}", u32::from(ItemStatus::Complete)),
+                )),
+                UpdateAction::Some(ItemStatus::Incomplete | ItemStatus::InProgress) => {
+                    Some(("NULL".to_string(), "1".to_string()))
+                }
+                _ => None,
+            };
+
+            if let Some((value, transition)) = completed_at {
+                server.connection.execute(
+                    &format!(
+                        "UPDATE {} SET completed_at = {value} WHERE {transition}{}",
+                        Tables::Tasks,
+                        condition
+                            .as_ref()
+                            .map_or(String::new(), |condition| format!(" AND ({condition})"))
+                    ),
+                    (),
+                )?;
             }
-            .to_string(),
-            (),
-        )?;
 
-        Ok(self.connection.changes())
+            server.connection.execute(
+                &UpdateTaskQuery {
+                    condition,
+                    name: args.name,
+                    priority: args.priority,
+                    status: args.status,
+                    start_time: args.start_time,
+                    end_time: args.end_time,
+                    repeat: args.repeat,
+                    notes: args.notes,
+                    // Every update of a task, including status changes, sets its update time
+                    updated_at: UpdateAction::Some(time::now()),
+                }
+                .to_string(),
+                (),
+            )?;
+            let changes = server.connection.changes();
+
+            for (id, name) in tasks {
+                server.log_event(action, &format!("task {id} '{name}'{details}"))?;
+            }
+            Ok(changes)
+        })
+    }
+
+    /// Updates multiple tasks by id with a single update statment. Returns the number of tasks
+    /// updated
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statments fails
+    pub fn update_tasks_bulk(
+        &self,
+        task_ids: Vec<i64>,
+        args: UpdateTaskArgs,
+    ) -> Result<u64, Error> {
+        if task_ids.is_empty() {
+            return Ok(0);
+        }
+
+        self.update_task(
+            Some(
+                QueryConditions::In {
+                    col: "id",
+                    values: task_ids,
+                }
+                .to_string(),
+            ),
+            args,
+        )
+    }
+
+    /// Sets the priorities of tasks to sequential values in the order of the given ids, from the
+    /// number of ids for the first task down to 1 for the last, in a single transaction. Returns
+    /// the number of tasks updated
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of any of the sql statments fails
+    pub fn reprioritize_tasks(&self, task_ids: &[i64]) -> Result<u64, Error> {
+        self.with_transaction(|server| {
+            let mut changes = 0;
+            for (i, task_id) in task_ids.iter().enumerate() {
+                changes += server.update_task(
+                    Some(
+                        QueryConditions::Equal {
+                            col: "id",
+                            value: task_id,
+                        }
+                        .to_string(),
+                    ),
+                    UpdateTaskArgs::update_priority((task_ids.len() - i) as u64),
+                )?;
+            }
+            Ok(changes)
+        })
+    }
+
+    /// Moves the priority of a task up or down by a step, clamping the priority at 0. Returns the
+    /// new priority of the task, or None if no task has the id
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if execution of any of the sql statments fails
+    pub fn move_task_priority(&self, task_id: i64, step: i64) -> Result<Option<u64>, Error> {
+        self.with_transaction(|server| {
+            let priority = match server.get_task_by_id(task_id)? {
+                Some(task) => task.priority.unwrap_or(0).saturating_add_signed(step),
+                None => return Ok(None),
+            };
+
+            server.update_task(
+                Some(
+                    QueryConditions::Equal {
+                        col: "id",
+                        value: task_id,
+                    }
+                    .to_string(),
+                ),
+                UpdateTaskArgs::update_priority(priority),
+            )?;
+            Ok(Some(priority))
+        })
+    }
+
+    /// Sets a task as complete. If the task repeats, a copy of the task is added with its start
+    /// and end times advanced by the recurrence interval. Returns the id of the added task if the
+    /// task was rescheduled
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if no task has the given id, or if execution of the sql statments fails
+    pub fn complete_and_reschedule(&self, task_id: i64) -> Result<Option<i64>, Error> {
+        let condition = QueryConditions::Equal {
+            col: "id",
+            value: task_id,
+        }
+        .to_string();
+
+        // Get task to complete
+        let task = match self.get_task_by_id(task_id)? {
+            Some(task) => task,
+            None => return Err(ToadoError::NotFound(format!("no task with id {task_id}")).into()),
+        };
+
+        self.with_transaction(|server| {
+            server.update_task(
+                Some(condition),
+                UpdateTaskArgs::update_status(ItemStatus::Complete),
+            )?;
+
+            // If task repeats, add next occurrence of task
+            let rescheduled_id = match task.repeat.as_deref().map(str::parse::<Recurrence>) {
+                Some(Ok(recurrence)) => {
+                    // Advance time by recurrence interval, keeping times that can't be parsed
+                    let advance = |time: Option<String>| {
+                        time.map(|time| recurrence.advance_time(&time).unwrap_or(time))
+                    };
+
+                    Some(server.add_task(AddTaskArgs {
+                        name: match task.name {
+                            Some(name) => name,
+                            None => return Err(Into::into("task name should exist")),
+                        },
+                        priority: task.priority.unwrap_or(0),
+                        status: ItemStatus::Incomplete,
+                        start_time: advance(task.start_time),
+                        end_time: advance(task.end_time),
+                        repeat: Some(recurrence.to_string()),
+                        notes: task.notes,
+                        parent_id: task.parent_id,
+                        owner: task.owner,
+                    })?)
+                }
+                _ => None,
+            };
+            Ok(rescheduled_id)
+        })
+    }
+
+    /// Adds copies of a task in a single transaction, with " (copy)" appended to their names and
+    /// their status reset to incomplete. If with_assignments is true, the copies are also assigned
+    /// to the projects the task is assigned to. Returns the ids of the added tasks
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if times is greater than MAX_CLONES, if no task has the given id, or if
+    /// execution of the sql statments fails
+    pub fn clone_task(
+        &self,
+        task_id: i64,
+        times: usize,
+        with_assignments: bool,
+    ) -> Result<Vec<i64>, Error> {
+        if times > MAX_CLONES {
+            return Err(ToadoError::Input(format!(
+                "cannot add more than {MAX_CLONES} copies at once"
+            ))
+            .into());
+        }
+
+        let task = match self.get_task_by_id(task_id)? {
+            Some(task) => task,
+            None => return Err(ToadoError::NotFound(format!("no task with id {task_id}")).into()),
+        };
+
+        let name = match task.name {
+            Some(name) => format!("{name} (copy)"),
+            None => return Err(Into::into("task name should exist")),
+        };
+
+        let project_ids = match with_assignments {
+            true => self
+                .select_task_projects(task_id)?
+                .into_iter()
+                .filter_map(|project| project.id)
+                .collect(),
+            false => Vec::new(),
+        };
+
+        self.with_transaction(|server| {
+            let mut ids = Vec::new();
+            for _ in 0..times {
+                let id = server.add_task(AddTaskArgs {
+                    name: name.clone(),
+                    priority: task.priority.unwrap_or(0),
+                    status: ItemStatus::Incomplete,
+                    start_time: task.start_time.clone(),
+                    end_time: task.end_time.clone(),
+                    repeat: task.repeat.clone(),
+                    notes: task.notes.clone(),
+                    parent_id: task.parent_id,
+                    owner: task.owner.clone(),
+                })?;
+
+                for project_id in &project_ids {
+                    server.assign_task(id, *project_id)?;
+                }
+
+                ids.push(id);
+            }
+            Ok(ids)
+        })
     }
 
     /// Select all tasks
@@ -160,6 +523,8 @@ impl Server {
         limit: Option<RowLimit>,
         offset: Option<usize>,
     ) -> Result<Vec<Task>, Error> {
+        cols.validate(&Tables::Tasks)?;
+
         // Create query
         let query = SelectTasksQuery::new(cols, condition, order_by, order_dir, limit, offset);
         // Prepare query as statment
@@ -181,12 +546,138 @@ impl Server {
                 end_time: row.get("end_time").ok(),
                 repeat: row.get("repeat").ok(),
                 notes: row.get("notes").ok(),
+                parent_id: row.get("parent_id").ok().flatten(),
+                created_at: row.get("created_at").ok().flatten(),
+                completed_at: row.get("completed_at").ok().flatten(),
+                owner: row.get("owner").ok().flatten(),
+                updated_at: row.get("updated_at").ok().flatten(),
                 projects: None,
+                blocked: None,
             })
         })?;
 
-        // Remove all empty rows, collect as vector of data and return
-        Ok(rows.filter_map(|row| row.ok()).collect::<Vec<Task>>())
+        // Remove all empty rows, collect as vector of data
+        let mut tasks = rows.filter_map(|row| row.ok()).collect::<Vec<Task>>();
+
+        // Flag tasks with incomplete dependencies as blocked, which is only displayed with their
+        // status, so only if the status column is selected
+        if tasks.iter().any(|task| task.status.is_some()) {
+            let blocked_ids = self.select_blocked_task_ids()?;
+            for task in tasks.iter_mut() {
+                task.blocked = task.id.map(|id| blocked_ids.contains(&id));
+            }
+        }
+
+        Ok(tasks)
+    }
+
+    /// Selects the ids and names of tasks matching an optional condition without modifying them,
+    /// previewing the tasks a delete or update with the same condition would affect
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statment fails
+    pub fn select_task_matches(
+        &self,
+        condition: Option<String>,
+    ) -> Result<Vec<(i64, String)>, Error> {
+        Ok(self
+            .select_tasks(
+                QueryCols::Some(vec!["id", "name"]),
+                condition,
+                Some(OrderBy::Id),
+                Some(OrderDir::Asc),
+                Some(RowLimit::All),
+                None,
+            )?
+            .into_iter()
+            .filter_map(|task| Some((task.id?, task.name?)))
+            .collect())
+    }
+
+    /// Selects the distinct names of all tasks in alphabetical order, without reading any other
+    /// task columns
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statment fails
+    pub fn select_task_names(&self) -> Result<Vec<String>, Error> {
+        let mut statment = self.connection.prepare(&format!(
+            "SELECT DISTINCT name FROM {} ORDER BY name ASC",
+            Tables::Tasks
+        ))?;
+        let names = statment
+            .query_map((), |row| row.get(0))?
+            .collect::<Result<Vec<String>, rusqlite::Error>>()?;
+
+        Ok(names)
+    }
+
+    /// Selects the ids of tasks matching an optional condition in order of id, without reading
+    /// any other task columns
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statment fails
+    pub fn select_task_ids(&self, condition: Option<String>) -> Result<Vec<i64>, Error> {
+        let mut query = format!("SELECT id FROM {}", Tables::Tasks);
+        if let Some(condition) = condition {
+            query.push_str(&format!(" WHERE {condition}"));
+        }
+        query.push_str(" ORDER BY id ASC");
+
+        let mut statment = self.connection.prepare(&query)?;
+        let ids = statment
+            .query_map((), |row| row.get(0))?
+            .collect::<Result<Vec<i64>, rusqlite::Error>>()?;
+
+        Ok(ids)
+    }
+
+    /// Selects all columns of the task with an id. Returns None if no task has the id
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the sql statment fails to execute
+    pub fn get_task_by_id(&self, task_id: i64) -> Result<Option<Task>, Error> {
+        Ok(self
+            .select_tasks(
+                QueryCols::All,
+                Some(
+                    QueryConditions::Equal {
+                        col: "id",
+                        value: task_id,
+                    }
+                    .to_string(),
+                ),
+                None,
+                None,
+                None,
+                None,
+            )?
+            .pop())
+    }
+
+    /// Select the child tasks of a task
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statment fails
+    pub fn select_child_tasks(&self, parent_id: i64) -> Result<Vec<Task>, Error> {
+        self.select_tasks(
+            QueryCols::All,
+            Some(
+                QueryConditions::Equal {
+                    col: "parent_id",
+                    value: parent_id,
+                }
+                .to_string(),
+            ),
+            None,
+            None,
+            Some(RowLimit::All),
+            None,
+        )
     }
 
     /// Adds a new project to the application database
@@ -196,11 +687,18 @@ impl Server {
     /// Will return an error if execution of the query fails
     pub fn add_project(&self, args: AddProjectArgs) -> Result<i64, Error> {
         // Create query
+        let name = args.name.clone();
         let query = AddProjectQuery::new(args.name, args.start_time, args.end_time, args.notes);
-        // Execute query
-        self.connection.execute(&query.to_string(), ())?;
-        // Return id of inserted row
-        Ok(self.connection.last_insert_rowid())
+
+        self.with_transaction(|server| {
+            // Execute query
+            server.connection.execute(&query.to_string(), ())?;
+            let id = server.connection.last_insert_rowid();
+            server.log_event("create", &format!("project {id} '{name}'"))?;
+
+            // Return id of inserted row
+            Ok(id)
+        })
     }
 
     /// Updates a project in the application database
@@ -212,16 +710,90 @@ impl Server {
         &self,
         condition: Option<String>,
         name: UpdateAction<String>,
+        status: UpdateAction<ItemStatus>,
         start_time: UpdateAction<String>,
         end_time: UpdateAction<String>,
         notes: UpdateAction<String>,
     ) -> Result<u64, Error> {
-        // Create query
-        let query = UpdateProjectQuery::new(condition, name, start_time, end_time, notes);
-        // Execute query
-        self.connection.execute(&query.to_string(), ())?;
-        // Return number of updated rows
-        Ok(self.connection.changes())
+        self.with_transaction(|server| {
+            // Get updated projects to log before the update, as the condition may match updated
+            // columns
+            let projects = server.select_project_matches(condition.clone())?;
+            let updated_cols = [
+                ("name", name.is_none()),
+                ("status", status.is_none()),
+                ("start_time", start_time.is_none()),
+                ("end_time", end_time.is_none()),
+                ("notes", notes.is_none()),
+            ]
+            .into_iter()
+            .filter_map(|(col, is_none)| (!is_none).then_some(col))
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+            // Create query
+            let query =
+                UpdateProjectQuery::new(condition, name, status, start_time, end_time, notes);
+            // Execute query
+            server.connection.execute(&query.to_string(), ())?;
+            let changes = server.connection.changes();
+
+            for (id, project_name) in projects {
+                server.log_event(
+                    "update",
+                    &format!("project {id} '{project_name}' ({updated_cols})"),
+                )?;
+            }
+
+            // Return number of updated rows
+            Ok(changes)
+        })
+    }
+
+    /// Renames a project in the application database, leaving its other values and task
+    /// assignments unchanged. Returns the number of updated rows
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the execution of the query fails
+    pub fn rename_project(&self, project_id: i64, name: String) -> Result<u64, Error> {
+        self.update_project(
+            Some(
+                QueryConditions::Equal {
+                    col: "id",
+                    value: project_id,
+                }
+                .to_string(),
+            ),
+            UpdateAction::Some(name),
+            UpdateAction::None,
+            UpdateAction::None,
+            UpdateAction::None,
+            UpdateAction::None,
+        )
+    }
+
+    /// Sets the status of a project in the application database, leaving its other values
+    /// unchanged. Returns the number of updated rows
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the execution of the query fails
+    pub fn update_project_status(&self, project_id: i64, status: ItemStatus) -> Result<u64, Error> {
+        self.update_project(
+            Some(
+                QueryConditions::Equal {
+                    col: "id",
+                    value: project_id,
+                }
+                .to_string(),
+            ),
+            UpdateAction::None,
+            UpdateAction::Some(status),
+            UpdateAction::None,
+            UpdateAction::None,
+            UpdateAction::None,
+        )
     }
 
     /// Deletes one or more projects from the application database. If condition is None, deletes
@@ -231,12 +803,23 @@ impl Server {
     ///
     /// Will return an error if the sql statment fails to execute
     pub fn delete_project(&self, condition: Option<String>) -> Result<u64, Error> {
-        // Create delete query
-        let query = DeleteProjectQuery::new(condition);
-        // Execure query
-        self.connection.execute(&query.to_string(), ())?;
-        // Return number of deleted rows
-        Ok(self.connection.changes())
+        self.with_transaction(|server| {
+            // Get deleted projects to log
+            let projects = server.select_project_matches(condition.clone())?;
+
+            // Create delete query
+            let query = DeleteProjectQuery::new(condition);
+            // Execure query
+            server.connection.execute(&query.to_string(), ())?;
+            let changes = server.connection.changes();
+
+            for (id, name) in projects {
+                server.log_event("delete", &format!("project {id} '{name}'"))?;
+            }
+
+            // Return number of deleted rows
+            Ok(changes)
+        })
     }
 
     /// Selects projects from the application database
@@ -253,6 +836,8 @@ impl Server {
         limit: Option<RowLimit>,
         offset: Option<usize>,
     ) -> Result<Vec<Project>, Error> {
+        cols.validate(&Tables::Projects)?;
+
         // Create query
         let query = SelectProjectsQuery::new(cols, condition, order_by, order_dir, limit, offset);
         // Prepare query as statment
@@ -263,10 +848,13 @@ impl Server {
             Ok(Project {
                 id: row.get("id").ok(),
                 name: row.get("name").ok(),
+                status: row.get::<&str, i64>("status").ok().map(ItemStatus::from),
                 start_time: row.get("start_time").ok(),
                 end_time: row.get("end_time").ok(),
                 notes: row.get("notes").ok(),
+                created_at: row.get("created_at").ok().flatten(),
                 tasks: None,
+                progress: None,
             })
         })?;
 
@@ -274,6 +862,54 @@ impl Server {
         Ok(rows.filter_map(|row| row.ok()).collect::<Vec<Project>>())
     }
 
+    /// Selects the ids and names of projects matching an optional condition without modifying
+    /// them, previewing the projects a delete or update with the same condition would affect
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if execution of the query fails
+    pub fn select_project_matches(
+        &self,
+        condition: Option<String>,
+    ) -> Result<Vec<(i64, String)>, Error> {
+        Ok(self
+            .select_project(
+                QueryCols::Some(vec!["id", "name"]),
+                condition,
+                Some(OrderBy::Id),
+                Some(OrderDir::Asc),
+                Some(RowLimit::All),
+                None,
+            )?
+            .into_iter()
+            .filter_map(|project| Some((project.id?, project.name?)))
+            .collect())
+    }
+
+    /// Selects all columns of the project with an id. Returns None if no project has the id
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the sql statment fails to execute
+    pub fn get_project_by_id(&self, project_id: i64) -> Result<Option<Project>, Error> {
+        Ok(self
+            .select_project(
+                QueryCols::All,
+                Some(
+                    QueryConditions::Equal {
+                        col: "id",
+                        value: project_id,
+                    }
+                    .to_string(),
+                ),
+                None,
+                None,
+                None,
+                None,
+            )?
+            .pop())
+    }
+
     /// Creates a new task assignment in application database
     ///
     /// # Errors
@@ -288,24 +924,28 @@ impl Server {
         Ok(self.connection.last_insert_rowid())
     }
 
-    /// Batch creates new task assignments in application database
+    /// Batch creates new task assignments in application database. Assignments are created in a
+    /// single transaction, so if any assignment fails, none are created
     ///
     /// # Errors
     ///
     /// Will return an error if sql statment fails to execute
     pub fn batch_assign_tasks(&self, assignments: Vec<(i64, i64)>) -> Result<Vec<i64>, Error> {
-        // Create query strings
-        let query_strings = assignments
-            .into_iter()
-            .map(|(task_id, project_id)| AssignTaskQuery::new(task_id, project_id).to_string());
-        // Execute query strings aggregating new row ids
-        query_strings
-            .into_iter()
-            .map(|query_string| {
-                self.connection.execute(&query_string, ())?;
-                Ok(self.connection.last_insert_rowid())
-            })
-            .collect::<Result<Vec<i64>, Error>>()
+        self.with_transaction(|server| {
+            // Prepare the statment once, executing it with each assignment aggregating new row ids
+            let ids = {
+                let mut statment = server.connection.prepare(&format!(
+                    "INSERT INTO {} (task_id, project_id) VALUES (?1, ?2)",
+                    Tables::TaskAssignments
+                ))?;
+
+                assignments
+                    .into_iter()
+                    .map(|assignment| Ok(statment.insert(assignment)?))
+                    .collect::<Result<Vec<i64>, Error>>()?
+            };
+            Ok(ids)
+        })
     }
 
     /// Removes a task assignment from application database
@@ -324,71 +964,707 @@ impl Server {
         Ok(self.connection.changes())
     }
 
-    /// Batch removes task assignments from application database
+    /// Batch removes task assignments from application database. Assignments are removed in a
+    /// single transaction, so if any removal fails, none are removed
     ///
     /// # Errors
     ///
     /// Will return an error if sql statment fails to execute
     pub fn batch_unassign_tasks(&self, unassignments: Vec<(i64, i64)>) -> Result<usize, Error> {
-        // Create query strings
-        let query_strings = unassignments
-            .into_iter()
-            .map(|(task_id, project_id)| UnassignTaskQuery::new(task_id, project_id).to_string());
-        // Execute query strings aggregating number of changed rows
-        Ok(query_strings
-            .into_iter()
-            .filter_map(
-                |query_string| match self.connection.execute(&query_string, ()) {
-                    Ok(changed) => Some(changed),
-                    Err(e) => {
-                        eprintln!("{e}"); // TODO: Refactor errror handling: aggragate and return
-                                          // vector of errors
-                        None
-                    }
-                },
-            )
-            .sum())
+        self.with_transaction(|server| {
+            // Prepare the statment once, executing it with each unassignment aggregating number of
+            // changed rows
+            let changed = {
+                let mut statment = server.connection.prepare(&format!(
+                    "DELETE FROM {} WHERE task_id = ?1 AND project_id = ?2",
+                    Tables::TaskAssignments
+                ))?;
+
+                unassignments
+                    .into_iter()
+                    .map(|unassignment| Ok(statment.execute(unassignment)?))
+                    .collect::<Result<Vec<usize>, Error>>()?
+                    .into_iter()
+                    .sum()
+            };
+            Ok(changed)
+        })
     }
 
-    /// Returns the total number of rows in a given table.
+    /// Moves a task assignment from one project to another in a single transaction. Returns false,
+    /// without assigning the task, if the task is not assigned to the project it is moved from
     ///
-    /// # Errors:
+    /// # Errors
     ///
-    /// Will return an error if execution of the sql statment fails
-    pub fn get_table_row_count(&self, table: Tables) -> Result<usize, Error> {
-        Ok(self
-            .connection
-            .query_row(&format!("SELECT COUNT(*) FROM {table}"), (), |row| {
-                row.get(0)
-            })?)
+    /// Will return an error if sql statment fails to execute
+    pub fn move_task(
+        &self,
+        task_id: i64,
+        from_project_id: i64,
+        to_project_id: i64,
+    ) -> Result<bool, Error> {
+        self.with_transaction(|server| {
+            if server.unassign_task(task_id, from_project_id)? == 0 {
+                return Ok(false);
+            }
+            server.assign_task(task_id, to_project_id)?;
+            Ok(true)
+        })
     }
-}
 
-/// Toado database tables
-pub enum Tables {
-    /// "tasks"
-    Tasks,
-    /// "projects"
-    Projects,
-    /// "task_assignments"
-    TaskAssignments,
-}
+    /// Adds a dependency of one task on another, so that the task is blocked until the task it
+    /// depends on is complete. Returns the id of the new dependency row
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the task depends on itself, if the dependency would create a cycle
+    /// of dependencies, or if the sql statment fails to execute
+    pub fn add_dependency(&self, task_id: i64, depends_on_id: i64) -> Result<i64, Error> {
+        if task_id == depends_on_id {
+            return Err(ToadoError::Input("a task cannot depend on itself".to_string()).into());
+        }
 
-impl fmt::Display for Tables {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Tasks => "tasks",
-                Self::Projects => "projects",
+        // Check if the task is already a direct or indirect dependency of the task it depends on
+        let cycle_count: usize = self.connection.query_row(
+            &format!(
+                "WITH RECURSIVE dependencies(id) AS (
+                    SELECT depends_on_id FROM {table} WHERE task_id = {depends_on_id}
+                    UNION
+                    SELECT d.depends_on_id FROM {table} d
+                    JOIN dependencies ON d.task_id = dependencies.id
+                )
+                SELECT COUNT(*) FROM dependencies WHERE id = {task_id}",
+                table = Tables::TaskDependencies
+            ),
+            (),
+            |row| row.get(0),
+        )?;
+
+        if cycle_count > 0 {
+            return Err(ToadoError::Input("dependency would create a cycle".to_string()).into());
+        }
+
+        self.connection.execute(
+            &format!(
+                "INSERT INTO {} (task_id, depends_on_id) VALUES ({task_id}, {depends_on_id})",
+                Tables::TaskDependencies
+            ),
+            (),
+        )?;
+
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    /// Removes a dependency of one task on another. Returns the number of rows removed
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the sql statment fails to execute
+    pub fn remove_dependency(&self, task_id: i64, depends_on_id: i64) -> Result<u64, Error> {
+        self.connection.execute(
+            &format!(
+                "DELETE FROM {} WHERE task_id = {task_id} AND depends_on_id = {depends_on_id}",
+                Tables::TaskDependencies
+            ),
+            (),
+        )?;
+
+        Ok(self.connection.changes())
+    }
+
+    /// Selects the ids of tasks that depend on one or more incomplete or in progress tasks
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the sql statment fails to execute
+    pub fn select_blocked_task_ids(&self) -> Result<Vec<i64>, Error> {
+        let mut statment = self.connection.prepare(&format!(
+            "SELECT DISTINCT d.task_id FROM {} d
+            JOIN {} t ON t.id = d.depends_on_id
+            WHERE {}",
+            Tables::TaskDependencies,
+            Tables::Tasks,
+            open_condition(Some("t"))
+        ))?;
+
+        let ids = statment
+            .query_map((), |row| row.get(0))?
+            .collect::<Result<Vec<i64>, rusqlite::Error>>()?;
+
+        Ok(ids)
+    }
+
+    /// Selects every task assignment as pairs of task id and project id
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the sql statment fails to execute
+    pub fn select_assignments(&self) -> Result<Vec<(i64, i64)>, Error> {
+        let mut statment = self.connection.prepare(&format!(
+            "SELECT task_id, project_id FROM {}",
+            Tables::TaskAssignments
+        ))?;
+
+        let assignments = statment
+            .query_map((), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(i64, i64)>, rusqlite::Error>>()?;
+
+        Ok(assignments)
+    }
+
+    /// Selects the projects a task is assigned to
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the sql statment fails to execute
+    pub fn select_task_projects(&self, task_id: i64) -> Result<Vec<Project>, Error> {
+        let condition = format!(
+            "id IN (SELECT project_id FROM {} WHERE {})",
+            Tables::TaskAssignments,
+            QueryConditions::Equal {
+                col: "task_id",
+                value: task_id
+            }
+        );
+
+        self.select_project(
+            QueryCols::All,
+            Some(condition),
+            Some(OrderBy::Name),
+            None,
+            Some(RowLimit::All),
+            None,
+        )
+    }
+
+    /// Selects the tasks assigned to a project
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the sql statment fails to execute
+    pub fn select_project_tasks(&self, project_id: i64) -> Result<Vec<Task>, Error> {
+        self.select_tasks(
+            QueryCols::All,
+            Some(assigned_to_condition(project_id)),
+            None,
+            None,
+            Some(RowLimit::All),
+            None,
+        )
+    }
+
+    /// Counts the complete and total tasks assigned to each project. Projects without assigned
+    /// tasks are omitted. Returns a map of project ids to progress
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the sql statment fails to execute
+    pub fn select_project_progress(&self) -> Result<HashMap<i64, Progress>, Error> {
+        let mut statment = self.connection.prepare(&format!(
+            "SELECT a.project_id, COUNT(*), SUM(t.status = {}) FROM {} a \
+            JOIN {} t ON t.id = a.task_id GROUP BY a.project_id",
+            u32::from(ItemStatus::Complete),
+            Tables::TaskAssignments,
+            Tables::Tasks
+        ))?;
+
+        let rows = statment.query_map((), |row| {
+            Ok((
+                row.get::<usize, i64>(0)?,
+                Progress {
+                    total: row.get(1)?,
+                    complete: row.get(2)?,
+                },
+            ))
+        })?;
+
+        Ok(rows.collect::<Result<HashMap<i64, Progress>, rusqlite::Error>>()?)
+    }
+
+    /// Exports all tasks, projects, assignments, and dependencies in the database
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if execution of any of the sql statments fails
+    pub fn export_database(&self) -> Result<DatabaseExport, Error> {
+        let tasks = self.select_tasks(
+            QueryCols::All,
+            None,
+            Some(OrderBy::Id),
+            Some(OrderDir::Asc),
+            Some(RowLimit::All),
+            None,
+        )?;
+        let projects = self.select_project(
+            QueryCols::All,
+            None,
+            Some(OrderBy::Id),
+            Some(OrderDir::Asc),
+            Some(RowLimit::All),
+            None,
+        )?;
+
+        let assignments = self
+            .connection
+            .prepare(&format!(
+                "SELECT task_id, project_id FROM {} ORDER BY id",
+                Tables::TaskAssignments
+            ))?
+            .query_map((), |row| {
+                Ok(Assignment {
+                    task_id: row.get(0)?,
+                    project_id: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<Assignment>, rusqlite::Error>>()?;
+
+        let dependencies = self
+            .connection
+            .prepare(&format!(
+                "SELECT task_id, depends_on_id FROM {} ORDER BY id",
+                Tables::TaskDependencies
+            ))?
+            .query_map((), |row| {
+                Ok(Dependency {
+                    task_id: row.get(0)?,
+                    depends_on_id: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<Dependency>, rusqlite::Error>>()?;
+
+        Ok(DatabaseExport {
+            tasks,
+            projects,
+            assignments,
+            dependencies,
+        })
+    }
+
+    /// Imports exported tasks, projects, assignments, and dependencies into the database in a
+    /// single transaction. Items keep their exported ids unless the id is already in use, in which
+    /// case they are given a new id and references to them are remapped. Assignments and
+    /// dependencies referencing items not in the export are skipped. Returns the number of tasks
+    /// and projects imported
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if an item has no name, or if execution of any of the sql statments
+    /// fails, in which case nothing is imported
+    pub fn import_database(&self, data: DatabaseExport) -> Result<(usize, usize), Error> {
+        self.with_transaction(|server| {
+            // Map of exported ids to imported ids
+            let mut task_ids: HashMap<i64, i64> = HashMap::new();
+            let mut project_ids: HashMap<i64, i64> = HashMap::new();
+
+            for task in &data.tasks {
+                let name = match &task.name {
+                    Some(name) => name,
+                    None => return Err(Into::into("imported task should have a name")),
+                };
+
+                server.connection.execute(
+                    &format!(
+                        "INSERT INTO {} (id, name, priority, status, start_time, end_time, repeat,
+                        notes, created_at, completed_at, owner, updated_at)
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                        Tables::Tasks
+                    ),
+                    (
+                        server.unused_id(Tables::Tasks, task.id)?,
+                        name,
+                        task.priority.unwrap_or(0),
+                        u32::from(task.status.unwrap_or(ItemStatus::Incomplete)),
+                        &task.start_time,
+                        &task.end_time,
+                        &task.repeat,
+                        &task.notes,
+                        &task.created_at,
+                        &task.completed_at,
+                        &task.owner,
+                        &task.updated_at,
+                    ),
+                )?;
+
+                if let Some(id) = task.id {
+                    task_ids.insert(id, server.connection.last_insert_rowid());
+                }
+            }
+
+            // Set parents once all tasks are imported, as parents may be imported after their
+            // children
+            for task in &data.tasks {
+                if let (Some(id), Some(parent_id)) = (
+                    task.id.and_then(|id| task_ids.get(&id)),
+                    task.parent_id.and_then(|id| task_ids.get(&id)),
+                ) {
+                    server.connection.execute(
+                        &format!(
+                            "UPDATE {} SET parent_id = {parent_id} WHERE id = {id}",
+                            Tables::Tasks
+                        ),
+                        (),
+                    )?;
+                }
+            }
+
+            for project in &data.projects {
+                let name = match &project.name {
+                    Some(name) => name,
+                    None => return Err(Into::into("imported project should have a name")),
+                };
+
+                server.connection.execute(
+                    &format!(
+                        "INSERT INTO {} (id, name, status, start_time, end_time, notes, created_at)
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        Tables::Projects
+                    ),
+                    (
+                        server.unused_id(Tables::Projects, project.id)?,
+                        name,
+                        u32::from(project.status.unwrap_or(ItemStatus::Incomplete)),
+                        &project.start_time,
+                        &project.end_time,
+                        &project.notes,
+                        &project.created_at,
+                    ),
+                )?;
+
+                if let Some(id) = project.id {
+                    project_ids.insert(id, server.connection.last_insert_rowid());
+                }
+            }
+
+            for assignment in &data.assignments {
+                if let (Some(task_id), Some(project_id)) = (
+                    task_ids.get(&assignment.task_id),
+                    project_ids.get(&assignment.project_id),
+                ) {
+                    server.assign_task(*task_id, *project_id)?;
+                }
+            }
+
+            for dependency in &data.dependencies {
+                if let (Some(task_id), Some(depends_on_id)) = (
+                    task_ids.get(&dependency.task_id),
+                    task_ids.get(&dependency.depends_on_id),
+                ) {
+                    server.add_dependency(*task_id, *depends_on_id)?;
+                }
+            }
+
+            server.log_event(
+                "import",
+                &format!(
+                    "{} task(s) and {} project(s)",
+                    data.tasks.len(),
+                    data.projects.len()
+                ),
+            )?;
+            Ok((data.tasks.len(), data.projects.len()))
+        })
+    }
+
+    /// Selects audit log entries, most recent first
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if execution of the sql statment fails
+    pub fn select_log(&self, limit: Option<RowLimit>) -> Result<Vec<LogEntry>, Error> {
+        let mut statment = self.connection.prepare(&format!(
+            "SELECT id, timestamp, action, description FROM {} ORDER BY id DESC{}",
+            Tables::AuditLog,
+            match limit {
+                Some(RowLimit::Limit(limit)) => format!(" LIMIT {limit}"),
+                _ => String::new(),
+            }
+        ))?;
+
+        let rows = statment.query_map((), |row| {
+            Ok(LogEntry {
+                id: row.get("id")?,
+                timestamp: row.get("timestamp")?,
+                action: row.get("action")?,
+                description: row.get("description")?,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<LogEntry>, rusqlite::Error>>()?)
+    }
+
+    /// Records an event in the audit log. Should be called in the same transaction as the change
+    /// it describes
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if execution of the sql statment fails
+    fn log_event(&self, action: &str, description: &str) -> Result<(), Error> {
+        self.connection.execute(
+            &format!(
+                "INSERT INTO {} (timestamp, action, description) VALUES (?1, ?2, ?3)",
+                Tables::AuditLog
+            ),
+            (time::now(), action, description),
+        )?;
+        Ok(())
+    }
+
+    /// Begins a transaction, unless the connection is already in one. Returns None if already in a
+    /// transaction, in which case changes are committed with the outer transaction
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if beginning the transaction fails
+    fn transaction_if_needed(&self) -> Result<Option<rusqlite::Transaction<'_>>, Error> {
+        Ok(match self.connection.is_autocommit() {
+            true => Some(self.connection.unchecked_transaction()?),
+            false => None,
+        })
+    }
+
+    /// Returns an id if no row in a table has it, otherwise None
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if execution of the sql statment fails
+    fn unused_id(&self, table: Tables, id: Option<i64>) -> Result<Option<i64>, Error> {
+        let id = match id {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        let condition = QueryConditions::Equal {
+            col: "id",
+            value: id,
+        }
+        .to_string();
+        if self.get_row_count(table, Some(condition))? == 0 {
+            Ok(Some(id))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Computes summary statistics of the tasks and projects in the database
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if execution of any of the sql statments fails
+    pub fn get_stats(&self) -> Result<Stats, Error> {
+        let status_count = |status: ItemStatus| {
+            self.get_row_count(
+                Tables::Tasks,
+                Some(
+                    QueryConditions::Equal {
+                        col: "status",
+                        value: u32::from(status),
+                    }
+                    .to_string(),
+                ),
+            )
+        };
+
+        let average_priority: Option<f64> = self.connection.query_row(
+            &format!(
+                "SELECT AVG(priority) FROM {} WHERE {}",
+                Tables::Tasks,
+                open_condition(None)
+            ),
+            (),
+            |row| row.get(0),
+        )?;
+
+        Ok(Stats {
+            incomplete: status_count(ItemStatus::Incomplete)?,
+            in_progress: status_count(ItemStatus::InProgress)?,
+            complete: status_count(ItemStatus::Complete)?,
+            archived: status_count(ItemStatus::Archived)?,
+            projects: self.get_table_row_count(Tables::Projects)?,
+            average_priority,
+            overdue: self.get_row_count(Tables::Tasks, Some(overdue_condition()))?,
+        })
+    }
+
+    /// Returns the total number of rows in a given table.
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statment fails
+    pub fn get_table_row_count(&self, table: Tables) -> Result<usize, Error> {
+        self.get_row_count(table, None)
+    }
+
+    /// Returns the number of rows in a given table matching a condition. If condition is None,
+    /// returns the total number of rows in the table
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statment fails
+    pub fn get_row_count(&self, table: Tables, condition: Option<String>) -> Result<usize, Error> {
+        let mut query = format!("SELECT COUNT(*) FROM {table}");
+        if let Some(condition) = condition {
+            query.push_str(&format!(" WHERE {condition}"));
+        }
+
+        Ok(self.connection.query_row(&query, (), |row| row.get(0))?)
+    }
+
+    /// Rebuilds the database to reclaim the space left by deleted rows. Returns the size of the
+    /// database in bytes before and after vacuuming
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statment fails, or if called inside of a
+    /// transaction
+    pub fn vacuum(&self) -> Result<(u64, u64), Error> {
+        let before = self.database_size()?;
+        self.connection.execute("VACUUM", ())?;
+        Ok((before, self.database_size()?))
+    }
+
+    /// Gathers table statistics used by the query planner to choose indexes
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statments fails
+    pub fn optimize(&self) -> Result<(), Error> {
+        self.connection.execute_batch("ANALYZE; PRAGMA optimize;")?;
+        Ok(())
+    }
+
+    /// Returns the size of the database in bytes
+    ///
+    /// # Errors:
+    ///
+    /// Will return an error if execution of the sql statments fails
+    fn database_size(&self) -> Result<u64, Error> {
+        let page_count: u64 = self
+            .connection
+            .query_row("PRAGMA page_count", (), |row| row.get(0))?;
+        let page_size: u64 = self
+            .connection
+            .query_row("PRAGMA page_size", (), |row| row.get(0))?;
+
+        Ok(page_count * page_size)
+    }
+}
+
+/// Returns a sql condition matching items that are incomplete or in progress. If a table alias is
+/// given, the status column is qualified by it
+pub fn open_condition(alias: Option<&str>) -> String {
+    let col = match alias {
+        Some(alias) => format!("{alias}.status"),
+        None => "status".to_string(),
+    };
+
+    format!(
+        "{col} IN ({}, {})",
+        u32::from(ItemStatus::Incomplete),
+        u32::from(ItemStatus::InProgress)
+    )
+}
+
+/// Returns a sql condition matching incomplete or in progress tasks with an end time in the past.
+/// Tasks with an end date are overdue from the day after that date
+pub fn overdue_condition() -> String {
+    format!(
+        "{} AND ((length(end_time) = 10 AND end_time < '{}') \
+        OR (length(end_time) > 10 AND end_time < '{}'))",
+        open_condition(None),
+        time::today(),
+        time::now()
+    )
+}
+
+/// Returns a sql query selecting the ids of tasks assigned to projects matching a condition on
+/// project columns
+pub fn assigned_task_ids_query(project_condition: &str) -> String {
+    format!(
+        "SELECT a.task_id FROM {} a JOIN {} p ON p.id = a.project_id WHERE {project_condition}",
+        Tables::TaskAssignments,
+        Tables::Projects
+    )
+}
+
+/// Returns a sql condition matching tasks assigned to a project
+pub fn assigned_to_condition(project_id: i64) -> String {
+    format!(
+        "id IN (SELECT task_id FROM {} WHERE {})",
+        Tables::TaskAssignments,
+        QueryConditions::Equal {
+            col: "project_id",
+            value: project_id
+        }
+    )
+}
+
+/// Toado database tables
+pub enum Tables {
+    /// "tasks"
+    Tasks,
+    /// "projects"
+    Projects,
+    /// "task_assignments"
+    TaskAssignments,
+    /// "task_dependencies"
+    TaskDependencies,
+    /// "audit_log"
+    AuditLog,
+}
+
+impl Tables {
+    /// Returns the names of the columns of the table
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            Self::Tasks => &[
+                "id",
+                "name",
+                "priority",
+                "status",
+                "start_time",
+                "end_time",
+                "repeat",
+                "notes",
+                "parent_id",
+                "created_at",
+                "completed_at",
+                "owner",
+                "updated_at",
+            ],
+            Self::Projects => &[
+                "id",
+                "name",
+                "status",
+                "start_time",
+                "end_time",
+                "notes",
+                "created_at",
+            ],
+            Self::TaskAssignments => &["id", "task_id", "project_id"],
+            Self::TaskDependencies => &["id", "task_id", "depends_on_id"],
+            Self::AuditLog => &["id", "timestamp", "action", "description"],
+        }
+    }
+}
+
+impl fmt::Display for Tables {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Tasks => "tasks",
+                Self::Projects => "projects",
                 Self::TaskAssignments => "task_assignments",
+                Self::TaskDependencies => "task_dependencies",
+                Self::AuditLog => "audit_log",
             }
         )
     }
 }
 
 /// Task row data
+#[derive(Serialize, Deserialize)]
 pub struct Task {
     pub id: Option<i64>,
     /// Name of the task
@@ -405,8 +1681,40 @@ pub struct Task {
     pub repeat: Option<String>,
     /// Notes for the task
     pub notes: Option<String>,
+    /// Id of the parent task, if the task is a subtask
+    pub parent_id: Option<i64>,
+    /// Time the task was added in ISO 8601 format
+    pub created_at: Option<String>,
+    /// Time the task was completed in ISO 8601 format
+    pub completed_at: Option<String>,
+    /// Name of the user who added the task
+    pub owner: Option<String>,
+    /// Time the task was last updated in ISO 8601 format
+    pub updated_at: Option<String>,
     /// List of projects the task is associate with
+    #[serde(skip)]
     pub projects: Option<Vec<Project>>,
+    /// Whether the task depends on any incomplete tasks
+    #[serde(skip)]
+    pub blocked: Option<bool>,
+}
+
+impl Task {
+    /// Returns true if the task is incomplete or in progress and its end time is before now. Tasks
+    /// with an end date are overdue from the day after that date
+    pub fn is_overdue(&self, now: &chrono::NaiveDateTime) -> bool {
+        if !self.status.is_some_and(|status| status.is_open()) {
+            return false;
+        }
+
+        match &self.end_time {
+            Some(end_time) if time::is_date(end_time) => {
+                time::parse_time(end_time).is_some_and(|end_time| end_time.date() < now.date())
+            }
+            Some(end_time) => time::parse_time(end_time).is_some_and(|end_time| end_time < *now),
+            None => false,
+        }
+    }
 }
 
 impl Clone for Task {
@@ -420,7 +1728,13 @@ impl Clone for Task {
             end_time: self.end_time.clone(),
             repeat: self.repeat.clone(),
             notes: self.notes.clone(),
+            parent_id: self.parent_id,
+            created_at: self.created_at.clone(),
+            completed_at: self.completed_at.clone(),
+            owner: self.owner.clone(),
+            updated_at: self.updated_at.clone(),
             projects: self.projects.clone(),
+            blocked: self.blocked,
         }
     }
 }
@@ -434,6 +1748,8 @@ pub struct AddTaskArgs {
     pub end_time: Option<String>,
     pub repeat: Option<String>,
     pub notes: Option<String>,
+    pub parent_id: Option<i64>,
+    pub owner: Option<String>,
 }
 
 /// Arguments for updating a task in the database
@@ -459,22 +1775,59 @@ impl UpdateTaskArgs {
             notes: UpdateAction::None,
         }
     }
+
+    pub fn update_priority(priority: u64) -> Self {
+        UpdateTaskArgs {
+            name: UpdateAction::None,
+            priority: UpdateAction::Some(priority),
+            status: UpdateAction::None,
+            start_time: UpdateAction::None,
+            end_time: UpdateAction::None,
+            repeat: UpdateAction::None,
+            notes: UpdateAction::None,
+        }
+    }
+
+    /// Returns the names of the columns that are updated
+    fn updated_cols(&self) -> Vec<&'static str> {
+        [
+            ("name", self.name.is_none()),
+            ("priority", self.priority.is_none()),
+            ("status", self.status.is_none()),
+            ("start_time", self.start_time.is_none()),
+            ("end_time", self.end_time.is_none()),
+            ("repeat", self.repeat.is_none()),
+            ("notes", self.notes.is_none()),
+        ]
+        .into_iter()
+        .filter_map(|(col, is_none)| (!is_none).then_some(col))
+        .collect()
+    }
 }
 
 /// Project row data
+#[derive(Serialize, Deserialize)]
 pub struct Project {
     /// Id of project
     pub id: Option<i64>,
     /// Name of project
     pub name: Option<String>,
+    /// Completion status of project
+    pub status: Option<ItemStatus>,
     /// Start time of the project in ISO 8601 format
     pub start_time: Option<String>,
     /// End time of the project in ISO 8601 format
     pub end_time: Option<String>,
     /// Notes for the project
     pub notes: Option<String>,
+    /// Time the project was added in ISO 8601 format
+    pub created_at: Option<String>,
     /// Tasks assigned to the project
+    #[serde(skip)]
     pub tasks: Option<Vec<Task>>,
+    /// Completion progress of the tasks assigned to the project
+    #[serde(skip)]
+    pub progress: Option<Progress>,
 }
 
 impl Clone for Project {
@@ -482,14 +1835,94 @@ impl Clone for Project {
         Project {
             id: self.id,
             name: self.name.clone(),
+            status: self.status,
             start_time: self.start_time.clone(),
             end_time: self.end_time.clone(),
             notes: self.notes.clone(),
+            created_at: self.created_at.clone(),
             tasks: self.tasks.clone(),
+            progress: self.progress,
         }
     }
 }
 
+/// Audit log entry recording a change to the database
+#[derive(Serialize)]
+pub struct LogEntry {
+    pub id: i64,
+    /// Time of the change
+    pub timestamp: String,
+    /// Kind of change (eg. "create", "update", "delete", or "check")
+    pub action: String,
+    /// Description of the changed items
+    pub description: String,
+}
+
+/// Completion progress of the tasks assigned to a project
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct Progress {
+    /// Number of complete tasks
+    pub complete: usize,
+    /// Total number of tasks
+    pub total: usize,
+}
+
+impl Progress {
+    /// Returns the percentage of tasks that are complete, or None if there are no tasks
+    pub fn percent(&self) -> Option<f64> {
+        match self.total {
+            0 => None,
+            total => Some(self.complete as f64 / total as f64 * 100.0),
+        }
+    }
+}
+
+/// Summary statistics of the tasks and projects in a database
+#[derive(Serialize)]
+pub struct Stats {
+    /// Number of incomplete tasks
+    pub incomplete: usize,
+    /// Number of in progress tasks
+    pub in_progress: usize,
+    /// Number of complete tasks
+    pub complete: usize,
+    /// Number of archived tasks
+    pub archived: usize,
+    /// Number of projects
+    pub projects: usize,
+    /// Average priority of incomplete and in progress tasks, None if there are no such tasks
+    pub average_priority: Option<f64>,
+    /// Number of incomplete or in progress tasks with an end time in the past
+    pub overdue: usize,
+}
+
+/// Assignment of a task to a project
+#[derive(Serialize, Deserialize)]
+pub struct Assignment {
+    pub task_id: i64,
+    pub project_id: i64,
+}
+
+/// Dependency of a task on another task
+#[derive(Serialize, Deserialize)]
+pub struct Dependency {
+    pub task_id: i64,
+    pub depends_on_id: i64,
+}
+
+/// All tasks, projects, and their relations in a database, used to back up and restore data
+#[derive(Serialize, Deserialize)]
+pub struct DatabaseExport {
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    #[serde(default)]
+    pub assignments: Vec<Assignment>,
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+}
+
 /// Arguments for adding project to database
 pub struct AddProjectArgs {
     pub name: String,
@@ -499,11 +1932,20 @@ pub struct AddProjectArgs {
 }
 
 /// Status of an item (ie. task or project)
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
 pub enum ItemStatus {
     Incomplete,
     Complete,
     Archived,
+    InProgress,
+}
+
+impl ItemStatus {
+    /// Returns true if the status is of an item not yet done (ie. incomplete or in progress)
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Incomplete | Self::InProgress)
+    }
 }
 
 impl fmt::Display for ItemStatus {
@@ -515,6 +1957,7 @@ impl fmt::Display for ItemStatus {
                 Self::Incomplete => "incomplete",
                 Self::Complete => "complete",
                 Self::Archived => "archived",
+                Self::InProgress => "in progress",
             }
         )
     }
@@ -527,18 +1970,746 @@ impl From<ItemStatus> for u32 {
             ItemStatus::Incomplete => 0,
             ItemStatus::Complete => 1,
             ItemStatus::Archived => 2,
+            ItemStatus::InProgress => 3,
         }
     }
 }
 
-// Implements Item status conversion for i64
+// Implements Item status conversion for i64. Archived items have always been stored as 2, so
+// existing databases have no items stored as 3 before in progress statuses were added
 impl From<i64> for ItemStatus {
     fn from(value: i64) -> Self {
         match value {
             0 => ItemStatus::Incomplete,
             1 => ItemStatus::Complete,
-            3 => ItemStatus::Archived,
+            2 => ItemStatus::Archived,
+            3 => ItemStatus::InProgress,
             _ => ItemStatus::Archived,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Opens an initialized server with an in memory database
+    fn test_server() -> Server {
+        let app = Server::open(":memory:").expect("in memory database should open");
+        app.init().expect("database should initialize");
+        app
+    }
+
+    /// Returns arguments for adding an incomplete task with a name
+    fn task_args(name: &str) -> AddTaskArgs {
+        AddTaskArgs {
+            name: name.to_string(),
+            priority: 0,
+            status: ItemStatus::Incomplete,
+            start_time: None,
+            end_time: None,
+            repeat: None,
+            notes: None,
+            parent_id: None,
+            owner: None,
+        }
+    }
+
+    /// Returns arguments for adding a project with a name
+    fn project_args(name: &str) -> AddProjectArgs {
+        AddProjectArgs {
+            name: name.to_string(),
+            start_time: None,
+            end_time: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let app = test_server();
+        let task_ids: Vec<i64> = (0..15)
+            .map(|i| app.add_task(task_args(&format!("task {i}"))).unwrap())
+            .collect();
+        let project_ids: Vec<i64> = (0..12)
+            .map(|i| {
+                app.add_project(project_args(&format!("project {i}")))
+                    .unwrap()
+            })
+            .collect();
+        app.assign_task(task_ids[0], project_ids[11]).unwrap();
+        app.add_dependency(task_ids[14], task_ids[0]).unwrap();
+
+        let export = app.export_database().unwrap();
+        assert_eq!(export.tasks.len(), 15);
+        assert_eq!(export.projects.len(), 12);
+
+        let json = serde_json::to_string(&export).unwrap();
+        let imported = test_server();
+        let (tasks, projects) = imported
+            .import_database(serde_json::from_str(&json).unwrap())
+            .unwrap();
+        assert_eq!((tasks, projects), (15, 12));
+
+        let reexport = imported.export_database().unwrap();
+        let names = |tasks: &[Task]| tasks.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&export.tasks), names(&reexport.tasks));
+        assert_eq!(reexport.assignments.len(), 1);
+        assert_eq!(reexport.dependencies.len(), 1);
+    }
+
+    #[test]
+    fn match_previews_select_all_rows() {
+        let app = test_server();
+        for i in 0..12 {
+            app.add_task(task_args(&format!("task {i}"))).unwrap();
+            app.add_project(project_args(&format!("project {i}")))
+                .unwrap();
+        }
+
+        assert_eq!(app.select_task_matches(None).unwrap().len(), 12);
+        assert_eq!(app.select_project_matches(None).unwrap().len(), 12);
+    }
+
+    #[test]
+    fn log_records_every_deleted_row() {
+        let app = test_server();
+        for i in 0..12 {
+            app.add_task(task_args(&format!("task {i}"))).unwrap();
+        }
+
+        assert_eq!(app.delete_task(None).unwrap(), 12);
+
+        let deletes = app
+            .select_log(None)
+            .unwrap()
+            .into_iter()
+            .filter(|entry| entry.action == "delete")
+            .count();
+        assert_eq!(deletes, 12);
+    }
+
+    #[test]
+    fn update_tasks_bulk_updates_every_id() {
+        let app = test_server();
+        let ids: Vec<i64> = (0..4)
+            .map(|i| app.add_task(task_args(&format!("task {i}"))).unwrap())
+            .collect();
+
+        let updated = app
+            .update_tasks_bulk(
+                ids[..3].to_vec(),
+                UpdateTaskArgs::update_status(ItemStatus::Complete),
+            )
+            .unwrap();
+        assert_eq!(updated, 3);
+
+        for (i, id) in ids.iter().enumerate() {
+            let status = app.get_task_by_id(*id).unwrap().unwrap().status;
+            assert_eq!(matches!(status, Some(ItemStatus::Complete)), i < 3);
+        }
+        assert_eq!(
+            app.update_tasks_bulk(vec![], UpdateTaskArgs::update_status(ItemStatus::Complete))
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn log_lists_newest_entries_first() {
+        let app = test_server();
+        let task_id = app.add_task(task_args("logged")).unwrap();
+        app.delete_task(Some(
+            QueryConditions::Equal {
+                col: "id",
+                value: task_id,
+            }
+            .to_string(),
+        ))
+        .unwrap();
+
+        let actions: Vec<String> = app
+            .select_log(None)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.action)
+            .collect();
+        assert_eq!(actions, vec!["delete", "create"]);
+        assert_eq!(app.select_log(Some(RowLimit::Limit(1))).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn select_child_tasks_selects_all_children() {
+        let app = test_server();
+        let parent_id = app.add_task(task_args("parent")).unwrap();
+        for i in 0..12 {
+            app.add_task(AddTaskArgs {
+                parent_id: Some(parent_id),
+                ..task_args(&format!("child {i}"))
+            })
+            .unwrap();
+        }
+
+        assert_eq!(app.select_child_tasks(parent_id).unwrap().len(), 12);
+    }
+
+    #[test]
+    fn deleting_parent_tasks_deletes_children() {
+        let app = test_server();
+        let parent_id = app.add_task(task_args("parent")).unwrap();
+        let child_id = app
+            .add_task(AddTaskArgs {
+                parent_id: Some(parent_id),
+                ..task_args("child")
+            })
+            .unwrap();
+        app.add_task(AddTaskArgs {
+            parent_id: Some(child_id),
+            ..task_args("grandchild")
+        })
+        .unwrap();
+        app.add_task(task_args("unrelated")).unwrap();
+
+        let child = app.get_task_by_id(child_id).unwrap().unwrap();
+        assert_eq!(child.parent_id, Some(parent_id));
+
+        app.delete_task(Some(format!("id = {parent_id}"))).unwrap();
+        assert_eq!(app.get_table_row_count(Tables::Tasks).unwrap(), 1);
+    }
+
+    #[test]
+    fn complete_and_reschedule_advances_repeating_tasks() {
+        let app = test_server();
+
+        for (repeat, start_time, end_time, next_start_time, next_end_time) in [
+            (
+                "daily",
+                "2024-01-31",
+                "2024-01-31",
+                "2024-02-01",
+                "2024-02-01",
+            ),
+            (
+                "weekly",
+                "2024-02-26T09:00:00",
+                "2024-02-27T17:00:00",
+                "2024-03-04T09:00:00",
+                "2024-03-05T17:00:00",
+            ),
+        ] {
+            let task_id = app
+                .add_task(AddTaskArgs {
+                    start_time: Some(start_time.to_string()),
+                    end_time: Some(end_time.to_string()),
+                    repeat: Some(repeat.to_string()),
+                    ..task_args(repeat)
+                })
+                .unwrap();
+
+            let next_id = app
+                .complete_and_reschedule(task_id)
+                .unwrap()
+                .expect("repeating task should be rescheduled");
+            let task = app.get_task_by_id(task_id).unwrap().unwrap();
+            let next = app.get_task_by_id(next_id).unwrap().unwrap();
+
+            assert!(matches!(task.status, Some(ItemStatus::Complete)));
+            assert!(matches!(next.status, Some(ItemStatus::Incomplete)));
+            assert_eq!(next.start_time.as_deref(), Some(next_start_time));
+            assert_eq!(next.end_time.as_deref(), Some(next_end_time));
+            assert_eq!(next.repeat.as_deref(), Some(repeat));
+        }
+
+        let task_id = app.add_task(task_args("once")).unwrap();
+        assert_eq!(app.complete_and_reschedule(task_id).unwrap(), None);
+    }
+
+    #[test]
+    fn batch_assign_tasks_rolls_back_on_error() {
+        let app = test_server();
+        let task_ids = app
+            .add_tasks(vec![task_args("first"), task_args("second")])
+            .unwrap();
+        let project_id = app.add_project(project_args("project")).unwrap();
+
+        // The second pair references a project that does not exist
+        assert!(app
+            .batch_assign_tasks(vec![
+                (task_ids[0], project_id),
+                (task_ids[1], project_id + 1)
+            ])
+            .is_err());
+        assert_eq!(app.get_table_row_count(Tables::TaskAssignments).unwrap(), 0);
+
+        app.batch_assign_tasks(vec![(task_ids[0], project_id), (task_ids[1], project_id)])
+            .unwrap();
+        assert_eq!(app.get_table_row_count(Tables::TaskAssignments).unwrap(), 2);
+    }
+
+    #[test]
+    fn batch_assign_tasks_assigns_every_pair() {
+        let app = test_server();
+        let task_ids = app
+            .add_tasks((0..5).map(|i| task_args(&format!("task {i}"))).collect())
+            .unwrap();
+        let project_ids: Vec<i64> = (0..10)
+            .map(|i| {
+                app.add_project(project_args(&format!("project {i}")))
+                    .unwrap()
+            })
+            .collect();
+
+        let pairs: Vec<(i64, i64)> = task_ids
+            .iter()
+            .flat_map(|task_id| project_ids.iter().map(|project_id| (*task_id, *project_id)))
+            .collect();
+        let ids = app.batch_assign_tasks(pairs).unwrap();
+
+        assert_eq!(ids.len(), 50);
+        assert_eq!(
+            app.get_table_row_count(Tables::TaskAssignments).unwrap(),
+            50
+        );
+    }
+
+    #[test]
+    fn add_assigned_task_rolls_back_on_error() {
+        let app = test_server();
+        let project_id = app.add_project(project_args("project")).unwrap();
+
+        assert!(app
+            .add_assigned_task(task_args("orphan"), project_id + 1)
+            .is_err());
+        assert_eq!(app.get_table_row_count(Tables::Tasks).unwrap(), 0);
+
+        app.add_assigned_task(task_args("task"), project_id)
+            .unwrap();
+        assert_eq!(app.get_table_row_count(Tables::TaskAssignments).unwrap(), 1);
+    }
+
+    #[test]
+    fn rename_project_keeps_assignments() {
+        let app = test_server();
+        let task_id = app.add_task(task_args("task")).unwrap();
+        let project_id = app
+            .add_project(AddProjectArgs {
+                notes: Some("notes".to_string()),
+                ..project_args("old")
+            })
+            .unwrap();
+        app.assign_task(task_id, project_id).unwrap();
+
+        assert_eq!(
+            app.rename_project(project_id, "new".to_string()).unwrap(),
+            1
+        );
+
+        let project = app.get_project_by_id(project_id).unwrap().unwrap();
+        assert_eq!(project.name.as_deref(), Some("new"));
+        assert_eq!(project.notes.as_deref(), Some("notes"));
+        assert_eq!(app.select_assignments().unwrap(), [(task_id, project_id)]);
+    }
+
+    #[test]
+    fn move_task_moves_assignment_between_projects() {
+        let app = test_server();
+        let task_id = app.add_task(task_args("task")).unwrap();
+        let from_id = app.add_project(project_args("from")).unwrap();
+        let to_id = app.add_project(project_args("to")).unwrap();
+        let project_names = || {
+            app.select_task_projects(task_id)
+                .unwrap()
+                .into_iter()
+                .filter_map(|project| project.name)
+                .collect::<Vec<String>>()
+        };
+
+        // Task is not assigned to the project it is moved from
+        assert!(!app.move_task(task_id, from_id, to_id).unwrap());
+        assert!(project_names().is_empty());
+
+        app.assign_task(task_id, from_id).unwrap();
+        assert!(app.move_task(task_id, from_id, to_id).unwrap());
+        assert_eq!(project_names(), ["to"]);
+        assert_eq!(app.get_table_row_count(Tables::TaskAssignments).unwrap(), 1);
+    }
+
+    #[test]
+    fn completing_tasks_stamps_completion_time() {
+        let app = test_server();
+        let task_id = app.add_task(task_args("task")).unwrap();
+        let set_status = |status| {
+            app.update_task(
+                Some(format!("id = {task_id}")),
+                UpdateTaskArgs::update_status(status),
+            )
+            .unwrap();
+            app.get_task_by_id(task_id).unwrap().unwrap().completed_at
+        };
+
+        assert!(app
+            .get_task_by_id(task_id)
+            .unwrap()
+            .unwrap()
+            .completed_at
+            .is_none());
+        assert!(set_status(ItemStatus::Complete).is_some());
+        assert!(set_status(ItemStatus::Incomplete).is_none());
+        // Archiving keeps the time the task was completed
+        let completed_at = set_status(ItemStatus::Complete);
+        assert!(completed_at.is_some());
+        assert_eq!(set_status(ItemStatus::Archived), completed_at);
+        assert!(set_status(ItemStatus::InProgress).is_none());
+    }
+
+    #[test]
+    fn get_stats_summarizes_tasks() {
+        let app = test_server();
+        for (name, priority, end_time, status) in [
+            ("overdue", 2, Some("2000-01-01"), ItemStatus::Incomplete),
+            ("upcoming", 4, Some("2999-01-01"), ItemStatus::Incomplete),
+            ("undated", 6, None, ItemStatus::InProgress),
+            (
+                "started",
+                0,
+                Some("2000-01-01T09:00:00"),
+                ItemStatus::InProgress,
+            ),
+            ("done", 9, Some("2000-01-01"), ItemStatus::Complete),
+            ("shelved", 9, None, ItemStatus::Archived),
+        ] {
+            let task_id = app
+                .add_task(AddTaskArgs {
+                    priority,
+                    end_time: end_time.map(String::from),
+                    ..task_args(name)
+                })
+                .unwrap();
+            app.update_task(
+                Some(format!("id = {task_id}")),
+                UpdateTaskArgs::update_status(status),
+            )
+            .unwrap();
+        }
+        app.add_project(project_args("project")).unwrap();
+
+        let stats = app.get_stats().unwrap();
+        assert_eq!(
+            (
+                stats.incomplete,
+                stats.in_progress,
+                stats.complete,
+                stats.archived
+            ),
+            (2, 2, 1, 1)
+        );
+        assert_eq!(stats.projects, 1);
+        assert_eq!(stats.average_priority, Some(3.0));
+        assert_eq!(stats.overdue, 2);
+
+        assert_eq!(test_server().get_stats().unwrap().average_priority, None);
+    }
+
+    #[test]
+    fn clone_task_rejects_too_many_copies() {
+        let app = test_server();
+        let id = app.add_task(task_args("task")).unwrap();
+
+        let err = app.clone_task(id, usize::MAX, false).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(ToadoError::Input(_))));
+        assert_eq!(app.clone_task(id, 3, false).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn clone_task_copies_as_incomplete_with_suffixed_name() {
+        let app = test_server();
+        let id = app.add_task(task_args("task")).unwrap();
+        app.update_task(
+            Some(
+                QueryConditions::Equal {
+                    col: "id",
+                    value: id,
+                }
+                .to_string(),
+            ),
+            UpdateTaskArgs::update_status(ItemStatus::Complete),
+        )
+        .unwrap();
+
+        let ids = app.clone_task(id, 1, false).unwrap();
+        let copy = app.get_task_by_id(ids[0]).unwrap().unwrap();
+        assert_eq!(copy.name.as_deref(), Some("task (copy)"));
+        assert!(matches!(copy.status, Some(ItemStatus::Incomplete)));
+    }
+
+    #[test]
+    fn project_progress_counts_complete_tasks() {
+        let app = test_server();
+        let project_id = app.add_project(project_args("project")).unwrap();
+        let empty_id = app.add_project(project_args("empty")).unwrap();
+        let task_ids: Vec<i64> = (0..4)
+            .map(|i| app.add_task(task_args(&format!("task {i}"))).unwrap())
+            .collect();
+        for task_id in &task_ids {
+            app.assign_task(*task_id, project_id).unwrap();
+        }
+        app.update_tasks_bulk(
+            vec![task_ids[0]],
+            UpdateTaskArgs::update_status(ItemStatus::Complete),
+        )
+        .unwrap();
+
+        let progress = app.select_project_progress().unwrap();
+        assert_eq!(
+            progress
+                .get(&project_id)
+                .map(|progress| (progress.complete, progress.total)),
+            Some((1, 4))
+        );
+        assert!(!progress.contains_key(&empty_id));
+    }
+
+    #[test]
+    fn maintenance_runs_on_seeded_database() {
+        let app = test_server();
+        let ids: Vec<i64> = (0..20)
+            .map(|i| app.add_task(task_args(&format!("task {i}"))).unwrap())
+            .collect();
+        app.delete_task(Some(
+            QueryConditions::LessThanOrEqual {
+                col: "id",
+                value: ids[9],
+            }
+            .to_string(),
+        ))
+        .unwrap();
+
+        app.vacuum().unwrap();
+        app.optimize().unwrap();
+        assert_eq!(app.get_table_row_count(Tables::Tasks).unwrap(), 10);
+    }
+
+    #[test]
+    fn init_creates_indexes() {
+        let app = test_server();
+        let indexes: Vec<(String, String)> = app
+            .connection
+            .prepare("SELECT name, tbl_name FROM sqlite_master WHERE type = 'index'")
+            .unwrap()
+            .query_map((), |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        for (name, table) in [
+            ("tasks_status_index", "tasks"),
+            ("tasks_priority_index", "tasks"),
+            ("task_assignments_project_index", "task_assignments"),
+        ] {
+            assert!(
+                indexes.contains(&(name.to_string(), table.to_string())),
+                "missing index {name}"
+            );
+        }
+        // The unique constraint indexes assignments by task and project
+        assert!(indexes.iter().any(
+            |(name, table)| table == "task_assignments" && name.starts_with("sqlite_autoindex")
+        ));
+    }
+
+    #[test]
+    fn get_items_by_id_finds_only_existing_items() {
+        let app = test_server();
+        let task_id = app.add_task(task_args("task")).unwrap();
+        let project_id = app.add_project(project_args("project")).unwrap();
+
+        let task = app.get_task_by_id(task_id).unwrap().unwrap();
+        assert_eq!(task.name.as_deref(), Some("task"));
+        assert!(task.created_at.is_some());
+        assert!(app.get_task_by_id(task_id + 1).unwrap().is_none());
+
+        let project = app.get_project_by_id(project_id).unwrap().unwrap();
+        assert_eq!(project.name.as_deref(), Some("project"));
+        assert!(app.get_project_by_id(project_id + 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn select_task_ids_selects_matching_ids_in_order() {
+        let app = test_server();
+        let ids: Vec<i64> = ["b", "a", "b"]
+            .into_iter()
+            .map(|name| app.add_task(task_args(name)).unwrap())
+            .collect();
+
+        assert_eq!(app.select_task_ids(None).unwrap(), ids);
+        assert_eq!(
+            app.select_task_ids(Some("name = 'b'".to_string())).unwrap(),
+            vec![ids[0], ids[2]]
+        );
+        assert!(app
+            .select_task_ids(Some("name = 'c'".to_string()))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn statuses_round_trip_through_database() {
+        let app = test_server();
+        for status in [
+            ItemStatus::Incomplete,
+            ItemStatus::Complete,
+            ItemStatus::Archived,
+            ItemStatus::InProgress,
+        ] {
+            let value = u32::from(status);
+            assert_eq!(u32::from(ItemStatus::from(i64::from(value))), value);
+
+            let id = app.add_task(task_args("task")).unwrap();
+            app.update_tasks_bulk(vec![id], UpdateTaskArgs::update_status(status))
+                .unwrap();
+            let stored = app.get_task_by_id(id).unwrap().unwrap().status;
+            assert_eq!(stored.map(u32::from), Some(value));
+        }
+    }
+
+    #[test]
+    fn move_task_priority_clamps_at_zero() {
+        let app = test_server();
+        let id = app.add_task(task_args("task")).unwrap();
+
+        assert_eq!(app.move_task_priority(id, 1).unwrap(), Some(1));
+        assert_eq!(app.move_task_priority(id, 3).unwrap(), Some(4));
+        assert_eq!(app.move_task_priority(id, -5).unwrap(), Some(0));
+        assert_eq!(app.move_task_priority(id, -1).unwrap(), Some(0));
+        assert_eq!(app.get_task_by_id(id).unwrap().unwrap().priority, Some(0));
+        assert_eq!(app.move_task_priority(id + 1, 1).unwrap(), None);
+    }
+
+    #[test]
+    fn owners_with_quotes_are_added_and_matched() {
+        let app = test_server();
+        app.add_task(AddTaskArgs {
+            owner: Some("o'brien".to_string()),
+            ..task_args("it's a task")
+        })
+        .unwrap();
+
+        let condition = QueryConditions::Equal {
+            col: "owner",
+            value: queries::quote_string("o'brien"),
+        };
+        let tasks = app
+            .select_tasks(
+                QueryCols::All,
+                Some(condition.to_string()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name.as_deref(), Some("it's a task"));
+    }
+
+    #[test]
+    fn with_transaction_rolls_back_on_error() {
+        let app = test_server();
+
+        let result: Result<(), Error> = app.with_transaction(|server| {
+            server.add_task(task_args("task"))?;
+            Err(Into::into("closure failed"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(app.get_table_row_count(Tables::Tasks).unwrap(), 0);
+    }
+
+    #[test]
+    fn with_transaction_nests_multi_operation_methods() {
+        let app = test_server();
+        let id = app.add_task(task_args("task")).unwrap();
+
+        let ids = app
+            .with_transaction(|server| {
+                let ids = server.clone_task(id, 2, false)?;
+                server.reprioritize_tasks(&ids)?;
+                Ok(ids)
+            })
+            .unwrap();
+
+        assert_eq!(ids.len(), 2);
+        assert_eq!(app.get_table_row_count(Tables::Tasks).unwrap(), 3);
+    }
+
+    #[test]
+    fn blocked_tasks_are_flagged_with_status() {
+        let app = test_server();
+        let task_id = app.add_task(task_args("task")).unwrap();
+        let depends_on_id = app.add_task(task_args("dependency")).unwrap();
+        app.add_dependency(task_id, depends_on_id).unwrap();
+
+        let select = |cols: Vec<&str>| {
+            app.select_tasks(
+                QueryCols::Some(cols),
+                None,
+                Some(OrderBy::Id),
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+        };
+
+        let tasks = select(vec!["id", "status"]);
+        assert_eq!(tasks[0].blocked, Some(true));
+        assert_eq!(tasks[1].blocked, Some(false));
+        assert_eq!(select(vec!["id", "name"])[0].blocked, None);
+    }
+
+    #[test]
+    fn add_dependency_rejects_self_and_cyclic_dependencies() {
+        let app = test_server();
+        let ids = app
+            .add_tasks(vec![task_args("a"), task_args("b"), task_args("c")])
+            .unwrap();
+        let is_input_error = |result: Result<i64, Error>| {
+            matches!(
+                result.err().as_deref().and_then(|e| e.downcast_ref()),
+                Some(ToadoError::Input(_))
+            )
+        };
+
+        app.add_dependency(ids[0], ids[1]).unwrap();
+        app.add_dependency(ids[1], ids[2]).unwrap();
+        assert_eq!(app.select_blocked_task_ids().unwrap().len(), 2);
+
+        assert!(is_input_error(app.add_dependency(ids[0], ids[0])));
+        assert!(is_input_error(app.add_dependency(ids[2], ids[0])));
+        assert_eq!(
+            app.get_table_row_count(Tables::TaskDependencies).unwrap(),
+            2
+        );
+
+        assert_eq!(app.remove_dependency(ids[1], ids[2]).unwrap(), 1);
+        assert_eq!(app.select_blocked_task_ids().unwrap(), vec![ids[0]]);
+    }
+
+    #[test]
+    fn completed_dependencies_do_not_block() {
+        let app = test_server();
+        let blocked_id = app.add_task(task_args("blocked")).unwrap();
+        let unblocked_id = app.add_task(task_args("unblocked")).unwrap();
+        let open_id = app.add_task(task_args("open")).unwrap();
+        let complete_id = app.add_task(task_args("complete")).unwrap();
+        app.update_task(
+            Some(format!("id = {complete_id}")),
+            UpdateTaskArgs::update_status(ItemStatus::Complete),
+        )
+        .unwrap();
+        app.add_dependency(blocked_id, open_id).unwrap();
+        app.add_dependency(unblocked_id, complete_id).unwrap();
+
+        assert_eq!(app.select_blocked_task_ids().unwrap(), vec![blocked_id]);
+    }
+}
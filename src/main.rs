@@ -1,5 +1,5 @@
 use clap::Parser;
-use std::{env, fs, path::PathBuf, process};
+use std::{env, fs, io, path::PathBuf, process};
 
 mod commands;
 mod config;
@@ -8,14 +8,28 @@ mod formatting;
 
 /// "The ships hung in the sky in much the same way that bricks don't."
 fn main() {
-    // Run the application and capture result
+    // Run the application and capture result. Errors exit the process with a code for their cause:
+    //   1   any other error
+    //   2   no item matches a search term or id
+    //   3   invalid input, including invalid CLI arguments
+    //   4   database error
+    //   130 an interactive prompt was cancelled
     let run = || -> Result<(), toado::Error> {
-        // Get CLI arguments
-        let args = flags::Cli::parse();
+        // Get CLI arguments, exiting with the invalid input code if they are not valid
+        let args = flags::Cli::try_parse().unwrap_or_else(|e| {
+            let _ = e.print();
+            process::exit(if e.use_stderr() { 3 } else { 0 })
+        });
+
+        // Completion scripts are generated without loading config or opening the database
+        if let Some(flags::Commands::Completions(completions_args)) = args.command {
+            handle_completions(completions_args);
+            return Ok(());
+        }
 
         // Get app configuration
         let config_path = args.config.map(PathBuf::from);
-        let app_config = match config::get_config(config_path) {
+        let mut app_config = match config::get_config(config_path) {
             Ok(c) => c,
             Err(e) => {
                 eprintln!("Failed to load config: {e}");
@@ -23,6 +37,18 @@ fn main() {
             }
         };
 
+        if args.ignore_case {
+            app_config.search.ignore_case = true;
+        }
+
+        // Disable colors for both formatted output and prompts if requested by argument or by a
+        // non empty NO_COLOR environment variable
+        if args.no_color || env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            app_config.display.colors = false;
+            console::set_colors_enabled(false);
+            console::set_colors_enabled_stderr(false);
+        }
+
         // Get application directory
         let database_path = match init_database_path(args.file) {
             Ok(d) => d,
@@ -53,28 +79,43 @@ fn main() {
                 if let Some(search) = args.search {
                     handle_search(
                         flags::SearchArgs {
-                            term: search,
+                            term: vec![search],
                             task: args.task,
                             project: args.project,
                             verbose: args.verbose,
+                            limit: None,
+                            offset: None,
+                            format: flags::OutputFormat::Table,
+                            in_notes: false,
+                            regex: false,
+                            in_project: false,
                         },
                         app,
                         &app_config,
                     )
                 } else if let Some(command) = args.command {
-                    handle_command(command, app, &app_config)
+                    handle_command(command, app, &app_config, args.json)
                 } else {
                     Ok(None)
                 }
             };
 
             match res {
-                Ok(Some(message)) => println!("{message}"),
+                Ok(message) => {
+                    if let Some(message) = output_message(message, args.quiet, args.json) {
+                        println!("{message}")
+                    }
+                }
+                // Cancelled prompts are not failures, so exit quietly with the SIGINT exit code
+                Err(e) if commands::is_cancelled(&e) => {
+                    let _ = console::Term::stderr().show_cursor();
+                    eprintln!("Cancelled");
+                    process::exit(130)
+                }
                 Err(e) => {
                     eprintln!("Failed to execute command: {e}");
                     return Err(e);
                 }
-                _ => {}
             };
 
             return Ok(());
@@ -92,12 +133,33 @@ fn main() {
             eprintln!("Caused by: {e}")
         }
 
-        process::exit(1)
+        process::exit(exit_code(&e))
     }
 }
 
-/// Gets the path to the application database. If none is provieded, uses the default database file
-/// location while ensuring the path exists
+/// Gets the message of a successful command to print. Quiet output only silences successful
+/// commands, JSON is still printed for scripts
+fn output_message(message: Option<String>, quiet: bool, json: bool) -> Option<String> {
+    message.filter(|_| !quiet || json)
+}
+
+/// Gets the process exit code for an error, based on the cause of the error
+fn exit_code(error: &toado::Error) -> i32 {
+    if commands::is_cancelled(error) {
+        return 130;
+    }
+
+    match error.downcast_ref::<toado::ToadoError>() {
+        Some(toado::ToadoError::NotFound(_)) => 2,
+        Some(toado::ToadoError::Input(_)) => 3,
+        None if error.is::<rusqlite::Error>() => 4,
+        None => 1,
+    }
+}
+
+/// Gets the path to the application database. If none is provieded, uses the path set by the
+/// TOADO_DB environment variable, or else the default database file location while ensuring the
+/// path exists
 ///
 /// # Errors
 ///
@@ -107,6 +169,8 @@ fn init_database_path(path_string: Option<String>) -> Result<PathBuf, toado::Err
     if let Some(path_string) = path_string {
         let path = PathBuf::from(path_string);
         Ok(path)
+    } else if let Some(path) = env::var_os("TOADO_DB") {
+        Ok(PathBuf::from(path))
     } else {
         let home_dir = env::var("HOME")?;
         let mut path = PathBuf::from(format!("{home_dir}/.local/share/toado/"));
@@ -129,15 +193,37 @@ fn handle_command(
     command: flags::Commands,
     app: toado::Server,
     config: &config::Config,
+    json: bool,
 ) -> Result<Option<String>, toado::Error> {
     let message = match command {
         flags::Commands::Search(args) => handle_search(args, app, config)?,
-        flags::Commands::Add(args) => handle_add(args, app, config)?,
-        flags::Commands::Delete(args) => handle_delete(args, app, config)?,
-        flags::Commands::Update(args) => handle_update(args, app, config)?,
+        flags::Commands::Add(args) => handle_add(args, app, config, json)?,
+        flags::Commands::Delete(args) => handle_delete(args, app, config, json)?,
+        flags::Commands::Purge(args) => handle_purge(args, app, json)?,
+        flags::Commands::Update(args) => handle_update(args, app, config, json)?,
+        flags::Commands::Rename(args) => handle_rename(args, app, config)?,
+        flags::Commands::Edit(args) => handle_edit(args, app, config)?,
+        flags::Commands::Clone(args) => handle_clone(args, app, config, json)?,
         flags::Commands::Ls(args) => handle_ls(args, app, config)?,
         flags::Commands::Check(args) => handle_check(args, app, config)?,
         flags::Commands::Assign(args) => handle_assign(args, app, config)?,
+        flags::Commands::Move(args) => handle_move(args, app, config)?,
+        flags::Commands::Depend(args) => handle_depend(args, app, config)?,
+        flags::Commands::Next => handle_next(app, config)?,
+        flags::Commands::Upcoming(args) => handle_upcoming(args, app, config)?,
+        flags::Commands::Prioritize(args) => handle_prioritize(args, app)?,
+        flags::Commands::Bump(args) => handle_bump(args, app, config)?,
+        flags::Commands::Count(args) => handle_count(args, app, json)?,
+        flags::Commands::Stats => handle_stats(app, config, json)?,
+        flags::Commands::Log(args) => handle_log(args, app, config, json)?,
+        flags::Commands::Theme(args) => handle_theme(args, config)?,
+        flags::Commands::Export(args) => handle_export(args, app)?,
+        flags::Commands::Import(args) => handle_import(args, app)?,
+        flags::Commands::Info => handle_info(app, config)?,
+        flags::Commands::Maintenance(args) => handle_maintenance(args, app)?,
+        flags::Commands::Completions(_) => {
+            unreachable!("completions are generated before the database is opened")
+        }
     };
 
     Ok(message)
@@ -156,7 +242,7 @@ fn handle_search(
     if args.task || !args.project {
         commands::search_tasks(args, app, config)
     } else {
-        Err(Into::into("search is not implemented for projects"))
+        commands::search_projects(args, app, config)
     }
 }
 
@@ -168,18 +254,32 @@ fn handle_search(
 fn handle_add(
     args: flags::AddArgs,
     app: toado::Server,
-    _config: &config::Config,
+    config: &config::Config,
+    json: bool,
 ) -> Result<Option<String>, toado::Error> {
-    if args.task || !args.project {
-        let (task_id, task_name) = commands::create_task(args, app)?;
-        Ok(Some(format!(
-            "Created task '{task_name}' with id '{task_id}'"
-        )))
+    if let Some(path) = args.from {
+        let count = commands::import_tasks(&path, app, config)?;
+        Ok(Some(if json {
+            json_count(count)
+        } else {
+            format!("Created {count} task(s) from '{path}'")
+        }))
+    } else if args.task || !args.project {
+        let (task_id, task_name, project_name) = commands::create_task(args, app, config)?;
+        Ok(Some(match (json, project_name) {
+            (true, _) => json_id(Some(task_id)),
+            (false, Some(project_name)) => format!(
+                "Created task '{task_name}' with id '{task_id}' and assigned it to '{project_name}'"
+            ),
+            (false, None) => format!("Created task '{task_name}' with id '{task_id}'"),
+        }))
     } else {
         let (project_id, project_name) = commands::create_project(args, app)?;
-        Ok(Some(format!(
-            "Created project '{project_name}' with id '{project_id}'"
-        )))
+        Ok(Some(if json {
+            json_id(Some(project_id))
+        } else {
+            format!("Created project '{project_name}' with id '{project_id}'")
+        }))
     }
 }
 
@@ -192,18 +292,53 @@ fn handle_delete(
     args: flags::DeleteArgs,
     app: toado::Server,
     config: &config::Config,
+    json: bool,
 ) -> Result<Option<String>, toado::Error> {
-    if args.task || !args.project {
-        match commands::delete_task(args, app, config)? {
-            Some(id) => Ok(Some(format!("Deleted task with id {id}"))),
-            None => Ok(None),
-        }
+    let item = if args.task || !args.project {
+        "task"
     } else {
-        match commands::delete_project(args, app, config)? {
-            Some(id) => Ok(Some(format!("Deleted project with id {id}"))),
-            None => Ok(None),
-        }
+        "project"
+    };
+
+    if args.dry_run {
+        let matches = if args.task || !args.project {
+            commands::preview_task_matches(args.term, args.all, app, config)?
+        } else {
+            commands::preview_project_matches(args.term, args.all, app, config)?
+        };
+
+        return Ok(Some(if json {
+            json_ids(matches.iter().map(|(id, _)| *id))
+        } else {
+            format_dry_run("delete", item, matches)
+        }));
+    }
+
+    if args.all {
+        let count = if args.task || !args.project {
+            commands::delete_all_tasks(args, app)?
+        } else {
+            commands::delete_all_projects(args, app)?
+        };
+
+        return Ok(Some(if json {
+            json_count(count)
+        } else {
+            format!("Deleted {count} {item}(s)")
+        }));
     }
+
+    let id = if args.task || !args.project {
+        commands::delete_task(args, app, config)?
+    } else {
+        commands::delete_project(args, app, config)?
+    };
+
+    Ok(Some(match (json, id) {
+        (true, id) => json_id(id),
+        (false, Some(id)) => format!("Deleted {item} with id {id}"),
+        (false, None) => "Delete cancelled".to_string(),
+    }))
 }
 
 /// Handle the update command
@@ -215,7 +350,28 @@ fn handle_update(
     args: flags::UpdateArgs,
     app: toado::Server,
     config: &config::Config,
+    json: bool,
 ) -> Result<Option<String>, toado::Error> {
+    if args.dry_run {
+        let (item, matches) = if args.task || !args.project {
+            (
+                "task",
+                commands::preview_task_matches(args.term, false, app, config)?,
+            )
+        } else {
+            (
+                "project",
+                commands::preview_project_matches(args.term, false, app, config)?,
+            )
+        };
+
+        return Ok(Some(if json {
+            json_ids(matches.iter().map(|(id, _)| *id))
+        } else {
+            format_dry_run("update", item, matches)
+        }));
+    }
+
     Ok(Some(format!(
         "{} row(s) updated",
         if args.task || !args.project {
@@ -226,6 +382,70 @@ fn handle_update(
     )))
 }
 
+/// Handle the rename command
+///
+/// # Errors
+///
+/// Will return an error if renaming the item fails
+fn handle_rename(
+    args: flags::RenameArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    let item = if args.task || !args.project {
+        "task"
+    } else {
+        "project"
+    };
+    let name = args.name.clone();
+
+    Ok(Some(match commands::rename_item(args, app, config)? {
+        0 => format!("No {item} renamed"),
+        _ => format!("Renamed {item} to '{name}'"),
+    }))
+}
+
+/// Handle the edit command
+///
+/// # Errors
+///
+/// Will return an error if editing the task fails
+fn handle_edit(
+    args: flags::EditArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    match commands::edit_task(args, app, config)? {
+        Some(task_name) => Ok(Some(format!("Updated task '{task_name}'"))),
+        None => Ok(Some("No changes made, edit cancelled".to_string())),
+    }
+}
+
+/// Handle the clone command
+///
+/// # Errors
+///
+/// Will return an error if copying the task fails
+fn handle_clone(
+    args: flags::CloneArgs,
+    app: toado::Server,
+    config: &config::Config,
+    json: bool,
+) -> Result<Option<String>, toado::Error> {
+    let (task_name, ids) = commands::clone_task(args, app, config)?;
+    Ok(Some(if json {
+        json_ids(ids.into_iter())
+    } else {
+        format!(
+            "Copied task '{task_name}' with id(s) {}",
+            ids.iter()
+                .map(|id| format!("'{id}'"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }))
+}
+
 /// Handle the list command
 ///
 /// # Errors
@@ -244,6 +464,8 @@ fn handle_ls(
     }
     // Set verbose arg
     args.verbose = verbose;
+    // Enable header if set in config
+    args.header = args.header || config.table.show_header;
 
     // Execute command
     if args.task || !args.project {
@@ -263,11 +485,47 @@ fn handle_check(
     app: toado::Server,
     config: &config::Config,
 ) -> Result<Option<String>, toado::Error> {
-    let (task_name, task_status) = commands::check_task(args, app, config)?;
-    Ok(Some(format!(
+    if args.project {
+        let (project_name, project_status) = commands::check_project(args, app, config)?;
+        return Ok(Some(format!(
+            "Set '{project_name}' to {}",
+            project_status.to_string().to_uppercase()
+        )));
+    }
+
+    // Multiple tasks are checked by id or by selection
+    if args.multi || args.term.len() > 1 {
+        let (names, task_status, rescheduled) = commands::check_tasks(args, app, config)?;
+        if names.is_empty() {
+            return Ok(Some("No tasks selected".to_string()));
+        }
+
+        let status = task_status.to_string().to_uppercase();
+        let mut lines: Vec<String> = names
+            .into_iter()
+            .map(|name| format!("Set '{name}' to {status}"))
+            .collect();
+
+        lines.extend(
+            rescheduled
+                .into_iter()
+                .map(|(name, id)| format!("Rescheduled '{name}' with id '{id}'")),
+        );
+
+        return Ok(Some(lines.join("\n")));
+    }
+
+    let (task_name, task_status, rescheduled_id) = commands::check_task(args, app, config)?;
+    let mut message = format!(
         "Set '{task_name}' to {}",
         task_status.to_string().to_uppercase()
-    )))
+    );
+
+    if let Some(id) = rescheduled_id {
+        message.push_str(&format!("\nRescheduled '{task_name}' with id '{id}'"));
+    }
+
+    Ok(Some(message))
 }
 
 /// Handle the assign command
@@ -280,13 +538,23 @@ fn handle_assign(
     app: toado::Server,
     config: &config::Config,
 ) -> Result<Option<String>, toado::Error> {
+    if args.list {
+        return Ok(Some(commands::list_assignments(args, app, config)?));
+    }
+
     let (pairs, action) = if !args.unassign {
         // Assign task(s)
         (
             if !args.no_select {
-                commands::assign_multiple_tasks(args, app, config)?
+                match commands::assign_multiple_tasks(args, app, config)? {
+                    Some(pairs) if pairs.is_empty() => {
+                        return Ok(Some("Tasks are already assigned to projects".to_string()))
+                    }
+                    Some(pairs) => pairs,
+                    None => return Ok(Some("Assignment cancelled".to_string())),
+                }
             } else {
-                vec![commands::assign_task(args, app)?]
+                vec![commands::assign_task(args, app, config)?]
             },
             "assigned to",
         )
@@ -296,7 +564,7 @@ fn handle_assign(
             if !args.no_select {
                 commands::unassign_multiple_tasks(args, app, config)?
             } else {
-                vec![commands::unassign_task(args, app)?]
+                vec![commands::unassign_task(args, app, config)?]
             },
             "unassigned from",
         )
@@ -310,3 +578,517 @@ fn handle_assign(
 
     Ok(Some(message))
 }
+
+/// Handle the move command
+///
+/// # Errors
+///
+/// Will return an error if moving the task fails
+fn handle_move(
+    args: flags::MoveArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    let (task_name, from_name, to_name) = commands::move_task(args, app, config)?;
+    Ok(Some(format!(
+        "Moved '{task_name}' from '{from_name}' to '{to_name}'"
+    )))
+}
+
+/// Handle the upcoming command
+///
+/// # Errors
+///
+/// Will return an error if computing the upcoming occurrences of the task fails
+fn handle_upcoming(
+    args: flags::UpcomingArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    let (task_name, repeat, occurrences) = commands::upcoming_task(args, app, config)?;
+    let mut lines = vec![format!(
+        "Next {} occurrences of '{task_name}' ({repeat}):",
+        occurrences.len()
+    )];
+    lines.extend(occurrences);
+
+    Ok(Some(lines.join("\n")))
+}
+
+/// Handle the depend command
+///
+/// # Errors
+///
+/// Will return an error if adding or removing the dependency fails
+fn handle_depend(
+    args: flags::DependArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    let remove = args.remove;
+    let (task_name, on_name) = commands::depend_task(args, app, config)?;
+    Ok(Some(if remove {
+        format!("'{task_name}' no longer depends on '{on_name}'")
+    } else {
+        format!("'{task_name}' now depends on '{on_name}'")
+    }))
+}
+
+/// Handle the next command
+///
+/// # Errors
+///
+/// Will return an error if selecting the next task fails
+fn handle_next(
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    match commands::next_task(app, config)? {
+        Some(task_string) => Ok(Some(task_string)),
+        None => Ok(Some("No upcoming tasks".to_string())),
+    }
+}
+
+/// Handle the prioritize command
+///
+/// # Errors
+///
+/// Will return an error if reprioritizing the tasks fails
+fn handle_prioritize(
+    args: flags::PrioritizeArgs,
+    app: toado::Server,
+) -> Result<Option<String>, toado::Error> {
+    let count = commands::prioritize_tasks(args, app)?;
+    Ok(Some(format!("Prioritized {count} task(s)")))
+}
+
+/// Handle the bump command
+///
+/// # Errors
+///
+/// Will return an error if bumping the task priority fails
+fn handle_bump(
+    args: flags::BumpArgs,
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    let (name, priority) = commands::bump_task(args, app, config)?;
+    Ok(Some(format!("Set priority of '{name}' to {priority}")))
+}
+
+/// Handle the count command
+///
+/// # Errors
+///
+/// Will return an error if counting the items fails
+fn handle_count(
+    args: flags::CountArgs,
+    app: toado::Server,
+    json: bool,
+) -> Result<Option<String>, toado::Error> {
+    let (table, item) = if args.task || !args.project {
+        (toado::Tables::Tasks, "task")
+    } else {
+        (toado::Tables::Projects, "project")
+    };
+
+    let count = app.get_table_row_count(table)?;
+    Ok(Some(if json {
+        json_count(count)
+    } else {
+        format!("{count} {item}(s)")
+    }))
+}
+
+/// Handle the stats command
+///
+/// # Errors
+///
+/// Will return an error if computing the statistics fails
+fn handle_stats(
+    app: toado::Server,
+    config: &config::Config,
+    json: bool,
+) -> Result<Option<String>, toado::Error> {
+    let stats = app.get_stats()?;
+    Ok(Some(if json {
+        serde_json::to_string(&stats)?
+    } else {
+        formatting::format_stats(&stats, config)
+    }))
+}
+
+/// Handle the log command
+///
+/// # Errors
+///
+/// Will return an error if selecting the log entries fails
+fn handle_log(
+    args: flags::LogArgs,
+    app: toado::Server,
+    config: &config::Config,
+    json: bool,
+) -> Result<Option<String>, toado::Error> {
+    let limit = match args.full {
+        true => toado::RowLimit::All,
+        false => toado::RowLimit::Limit(args.limit),
+    };
+
+    let entries = app.select_log(Some(limit))?;
+    Ok(Some(if json {
+        serde_json::to_string(&entries)?
+    } else {
+        formatting::format_log(&entries, config)
+    }))
+}
+
+/// Handle the export command
+///
+/// # Errors
+///
+/// Will return an error if exporting items fails
+fn handle_export(
+    args: flags::ExportArgs,
+    app: toado::Server,
+) -> Result<Option<String>, toado::Error> {
+    let export = commands::export_items(args, app)?;
+
+    // ICS exports end with their own CRLF, which println would double
+    Ok(Some(export.trim_end_matches("\r\n").to_string()))
+}
+
+/// Handle the import command
+///
+/// # Errors
+///
+/// Will return an error if importing items fails
+fn handle_import(
+    args: flags::ImportArgs,
+    app: toado::Server,
+) -> Result<Option<String>, toado::Error> {
+    let (tasks, projects) = commands::import_items(args, app)?;
+    Ok(Some(format!(
+        "Imported {tasks} task(s) and {projects} project(s)"
+    )))
+}
+
+/// Handle the completions command
+fn handle_completions(args: flags::CompletionsArgs) {
+    commands::generate_completions(args.shell, &mut io::stdout());
+}
+
+/// Handle the info command
+///
+/// # Errors
+///
+/// Will return an error if getting the database schema version fails
+fn handle_info(
+    app: toado::Server,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    let database = app.path().unwrap_or("in memory").to_string();
+    let config_path = config
+        .path
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or("none".to_string());
+
+    Ok(Some(format!(
+        "Database: {database}\nConfig: {config_path}\nSchema version: {}",
+        app.schema_version()?
+    )))
+}
+
+/// Handle the purge command
+///
+/// # Errors
+///
+/// Will return an error if purging archived tasks fails
+fn handle_purge(
+    args: flags::PurgeArgs,
+    app: toado::Server,
+    json: bool,
+) -> Result<Option<String>, toado::Error> {
+    Ok(Some(match (json, commands::purge_tasks(args, app)?) {
+        (true, count) => json_count(count.unwrap_or(0)),
+        (false, Some(count)) => format!("Purged {count} archived task(s)"),
+        (false, None) => "Purge cancelled".to_string(),
+    }))
+}
+
+/// Handle the maintenance command
+///
+/// # Errors
+///
+/// Will return an error if no maintenance action is provided, or if a maintenance action fails
+fn handle_maintenance(
+    args: flags::MaintenanceArgs,
+    app: toado::Server,
+) -> Result<Option<String>, toado::Error> {
+    if !args.vacuum && !args.optimize {
+        return Err(toado::ToadoError::Input(
+            "no maintenance action provided, try 'toado maintenance --vacuum'".to_string(),
+        )
+        .into());
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+
+    if args.vacuum {
+        let (before, after) = app.vacuum()?;
+        lines.push(format!("Vacuumed database from {before} to {after} bytes"));
+    }
+
+    if args.optimize {
+        app.optimize()?;
+        lines.push("Optimized database".to_string());
+    }
+
+    Ok(Some(lines.join("\n")))
+}
+
+/// Handle the theme command
+///
+/// # Errors
+///
+/// Will return an error if no theme action is provided
+fn handle_theme(
+    args: flags::ThemeArgs,
+    config: &config::Config,
+) -> Result<Option<String>, toado::Error> {
+    if args.preview {
+        Ok(Some(commands::preview_theme(config)))
+    } else {
+        Err(Into::into(
+            "no theme action provided, try 'toado theme --preview'",
+        ))
+    }
+}
+
+/// Formats an item id as a JSON object (eg. {"id": 5}). If id is None, the id is null
+fn json_id(id: Option<i64>) -> String {
+    format!(
+        "{{\"id\": {}}}",
+        id.map_or("null".to_string(), |id| id.to_string())
+    )
+}
+
+/// Formats a list of item ids as a JSON object (eg. {"ids": [1, 2]})
+fn json_ids(ids: impl Iterator<Item = i64>) -> String {
+    format!(
+        "{{\"ids\": [{}]}}",
+        ids.map(|id| id.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+/// Formats the items a dry run of an action would affect as a message listing their ids and names
+fn format_dry_run(action: &str, item: &str, matches: Vec<(i64, String)>) -> String {
+    let mut lines = vec![format!("Would {action} {} {item}(s):", matches.len())];
+    lines.extend(matches.into_iter().map(|(id, name)| format!("{id} {name}")));
+    lines.join("\n")
+}
+
+/// Formats a number of items as a JSON object (eg. {"count": 12})
+fn json_count(count: impl std::fmt::Display) -> String {
+    format!("{{\"count\": {count}}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// Runs command line arguments, without the program name, against a new in memory database
+    fn run(args: &[&str]) -> Result<Option<String>, toado::Error> {
+        let app = toado::Server::open(":memory:")?;
+        app.init()?;
+        run_on(app, args)
+    }
+
+    /// Runs command line arguments, without the program name, against a server
+    fn run_on(app: toado::Server, args: &[&str]) -> Result<Option<String>, toado::Error> {
+        let cli = flags::Cli::parse_from(["toado"].iter().chain(args));
+
+        handle_command(
+            cli.command.expect("arguments should include a command"),
+            app,
+            &config::Config::default(),
+            cli.json,
+        )
+    }
+
+    /// Held by tests that set environment variables, so they don't run at the same time
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn paths_are_resolved_from_args_then_env() {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = env::temp_dir().join(format!("toado-{}-env", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let env_config = dir.join("env.toml");
+        let arg_config = dir.join("arg.toml");
+        fs::write(&env_config, "[list]\ndefault_limit = 5\n").unwrap();
+        fs::write(&arg_config, "[list]\ndefault_limit = 7\n").unwrap();
+
+        env::set_var("TOADO_CONFIG", &env_config);
+        env::set_var("TOADO_DB", dir.join("env.db"));
+
+        let config = config::get_config(None).unwrap();
+        assert_eq!(config.path.as_deref(), Some(env_config.as_path()));
+        assert_eq!(config.list.default_limit, 5);
+        let config = config::get_config(Some(arg_config.clone())).unwrap();
+        assert_eq!(config.path.as_deref(), Some(arg_config.as_path()));
+        assert_eq!(config.list.default_limit, 7);
+
+        assert_eq!(init_database_path(None).unwrap(), dir.join("env.db"));
+        assert_eq!(
+            init_database_path(Some("arg.db".to_string())).unwrap(),
+            PathBuf::from("arg.db")
+        );
+
+        env::remove_var("TOADO_CONFIG");
+        env::remove_var("TOADO_DB");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn default_database_path_is_in_home() {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let home = env::temp_dir().join(format!("toado-{}-home", process::id()));
+        let previous_home = env::var_os("HOME");
+        env::set_var("HOME", &home);
+        env::remove_var("TOADO_DB");
+
+        let path = init_database_path(None).unwrap();
+        assert_eq!(path, home.join(".local/share/toado/database"));
+        assert!(home.join(".local/share/toado").is_dir());
+
+        match previous_home {
+            Some(previous_home) => env::set_var("HOME", previous_home),
+            None => env::remove_var("HOME"),
+        }
+        let _ = fs::remove_dir_all(home);
+    }
+
+    #[test]
+    fn quiet_silences_only_non_json_output() {
+        let message = run(&["add", "task", "--item-priority", "0", "--optional"]).unwrap();
+        assert!(message.is_some());
+        assert_eq!(output_message(message.clone(), true, false), None);
+        assert_eq!(output_message(message.clone(), false, false), message);
+
+        let json = run(&[
+            "--json",
+            "add",
+            "task",
+            "--item-priority",
+            "0",
+            "--optional",
+        ])
+        .unwrap();
+        assert!(json.is_some());
+        assert_eq!(output_message(json.clone(), true, true), json);
+    }
+
+    #[test]
+    fn ics_export_is_silenced_by_quiet() {
+        let export = run(&["export", "--format", "ics"]).unwrap().unwrap();
+        assert!(export.starts_with("BEGIN:VCALENDAR"));
+        assert!(export.ends_with("END:VCALENDAR"));
+        assert_eq!(output_message(Some(export), true, false), None);
+    }
+
+    #[test]
+    fn dry_run_reports_matches_without_changes() {
+        let db_path = env::temp_dir().join(format!("toado-{}-dry-run.db", process::id()));
+        let _ = fs::remove_file(&db_path);
+        let open = || toado::Server::open(&db_path).unwrap();
+        let app = open();
+        app.init().unwrap();
+        for name in ["alpha", "beta"] {
+            app.add_task(toado::AddTaskArgs {
+                name: name.to_string(),
+                priority: 1,
+                status: toado::ItemStatus::Incomplete,
+                start_time: None,
+                end_time: None,
+                repeat: None,
+                notes: None,
+                parent_id: None,
+                owner: None,
+            })
+            .unwrap();
+        }
+
+        let output = run_on(open(), &["delete", "--all", "--dry-run"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(output, "Would delete 2 task(s):\n1 alpha\n2 beta");
+
+        let output = run_on(open(), &["--json", "delete", "beta", "--dry-run"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(output, "{\"ids\": [2]}");
+
+        let output = run_on(
+            open(),
+            &["update", "alpha", "--item-priority", "9", "--dry-run"],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(output, "Would update 1 task(s):\n1 alpha");
+
+        let app = open();
+        let tasks = app
+            .select_tasks(
+                toado::QueryCols::All,
+                None,
+                Some(toado::OrderBy::Id),
+                None,
+                Some(toado::RowLimit::All),
+                None,
+            )
+            .unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks.iter().all(|task| task.priority == Some(1)));
+        let _ = fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn json_flag_outputs_json_objects() {
+        let output = run(&[
+            "--json",
+            "add",
+            "task",
+            "--item-priority",
+            "1",
+            "--optional",
+        ])
+        .unwrap()
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["id"], 1);
+
+        let output = run(&["--json", "count"]).unwrap().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["count"], 0);
+    }
+
+    #[test]
+    fn exit_code_matches_error_cause() {
+        let not_found: toado::Error = toado::ToadoError::NotFound("no task".to_string()).into();
+        let input: toado::Error = toado::ToadoError::Input("bad time".to_string()).into();
+        let sql: toado::Error = rusqlite::Error::InvalidQuery.into();
+        let other: toado::Error = Into::into("other");
+        let cancelled: toado::Error =
+            dialoguer::Error::IO(std::io::Error::from(std::io::ErrorKind::Interrupted)).into();
+
+        assert_eq!(exit_code(&not_found), 2);
+        assert_eq!(exit_code(&input), 3);
+        assert_eq!(exit_code(&sql), 4);
+        assert_eq!(exit_code(&other), 1);
+        assert_eq!(exit_code(&cancelled), 130);
+    }
+}
@@ -0,0 +1,107 @@
+//! Database schema migrations
+
+/// Migrations applied to the database in order. The database `user_version` is the number of
+/// migrations that have been applied, so migrations must only ever be appended to this list
+const MIGRATIONS: &[&str] = &[
+    // 1: Task dependencies
+    "CREATE TABLE IF NOT EXISTS task_dependencies(
+        id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+        task_id INTEGER NOT NULL,
+        depends_on_id INTEGER NOT NULL,
+        FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+        FOREIGN KEY (depends_on_id) REFERENCES tasks(id) ON DELETE CASCADE,
+        UNIQUE(task_id, depends_on_id),
+        CHECK(task_id != depends_on_id)
+    );",
+    // 2: Subtasks
+    "ALTER TABLE tasks ADD COLUMN parent_id INTEGER REFERENCES tasks(id) ON DELETE CASCADE;",
+    // 3: Creation times, set when items are added as existing rows have no known creation time
+    "ALTER TABLE tasks ADD COLUMN created_at TEXT;
+    ALTER TABLE projects ADD COLUMN created_at TEXT;",
+    // 4: Task completion times
+    "ALTER TABLE tasks ADD COLUMN completed_at TEXT;",
+    // 5: Audit log of item changes
+    "CREATE TABLE IF NOT EXISTS audit_log(
+        id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+        timestamp TEXT NOT NULL,
+        action TEXT NOT NULL,
+        description TEXT NOT NULL
+    );",
+    // 6: Indexes on columns tasks are commonly filtered, ordered, and joined by. Assignments are
+    // already indexed by (task_id, project_id) through their unique constraint, so only lookups
+    // by project need an index
+    "CREATE INDEX IF NOT EXISTS tasks_status_index ON tasks(status);
+    CREATE INDEX IF NOT EXISTS tasks_priority_index ON tasks(priority);
+    CREATE INDEX IF NOT EXISTS task_assignments_project_index ON task_assignments(project_id);",
+    // 7: Task owners, set when tasks are added as existing rows have no known owner
+    "ALTER TABLE tasks ADD COLUMN owner TEXT;",
+    // 8: Project statuses, existing projects are incomplete
+    "ALTER TABLE projects ADD COLUMN status INTEGER NOT NULL DEFAULT 0;",
+    // 9: Task update times, set when tasks are updated as existing rows have no known update time
+    "ALTER TABLE tasks ADD COLUMN updated_at TEXT;",
+];
+
+/// Applies all migrations that have not yet been applied to the database. Each migration is
+/// applied in its own transaction along with the update to the database schema version
+///
+/// # Errors
+///
+/// Will return an error if getting the schema version fails, or if a migration fails to execute
+pub fn migrate(connection: &rusqlite::Connection) -> Result<(), rusqlite::Error> {
+    apply_migrations(connection, MIGRATIONS)
+}
+
+/// Applies the migrations after the database schema version, in order. If a migration fails, its
+/// changes are rolled back and the schema version is left at the last applied migration
+///
+/// # Errors
+///
+/// Will return an error if getting the schema version fails, or if a migration fails to execute
+fn apply_migrations(
+    connection: &rusqlite::Connection,
+    migrations: &[&str],
+) -> Result<(), rusqlite::Error> {
+    let version: usize = connection.query_row("PRAGMA user_version", (), |row| row.get(0))?;
+
+    for (i, migration) in migrations.iter().enumerate().skip(version) {
+        // Dropping the transaction without committing it rolls back a failed migration
+        let transaction = connection.unchecked_transaction()?;
+        transaction.execute_batch(&format!(
+            "{migration}
+            PRAGMA user_version = {};",
+            i + 1
+        ))?;
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_migrations_roll_back() {
+        let connection = rusqlite::Connection::open_in_memory().unwrap();
+        let migrations = [
+            "CREATE TABLE a (id INTEGER);",
+            "CREATE TABLE b (id INTEGER); INSERT INTO missing VALUES (1);",
+        ];
+
+        assert!(apply_migrations(&connection, &migrations).is_err());
+
+        let version: usize = connection
+            .query_row("PRAGMA user_version", (), |row| row.get(0))
+            .unwrap();
+        let tables: usize = connection
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name IN ('a', 'b')",
+                (),
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!((version, tables), (1, 1));
+        assert!(connection.is_autocommit());
+    }
+}
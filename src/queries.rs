@@ -1,5 +1,6 @@
 //! Database query utilites
 
+use serde_derive::Deserialize;
 use std::fmt::{self};
 
 pub use projects::*;
@@ -22,7 +23,7 @@ trait Query: fmt::Display {
 /// Database addition query supertrait
 trait AddQuery: Query + fmt::Display {
     /// Vector of key value pairs for query (ie. ("name", "lorem ipsum"))
-    fn key_value_pairs(&self) -> KeyValuePairs;
+    fn key_value_pairs(&self) -> KeyValuePairs<'_>;
 
     /// Returns keys and values as seperate list strings
     fn get_key_value_strings(&self) -> (String, String) {
@@ -47,7 +48,7 @@ trait UpdateQuery: Query + fmt::Display {
     type Action: fmt::Display;
 
     fn condition(&self) -> Option<&str>;
-    fn update_cols(&self) -> UpdateCols<Self::Action>;
+    fn update_cols(&self) -> UpdateCols<'_, Self::Action>;
 
     fn build_query_string(&self) -> String {
         let mut query_string = format!("UPDATE {} SET {}", self.query_table(), self.update_cols());
@@ -94,10 +95,16 @@ type SelectFilters<'a> = (
 /// Database select query trait
 trait SelectQuery<'a>: Query + fmt::Display {
     /// Get query filter values
-    fn query_filters(&self) -> SelectFilters;
+    fn query_filters(&self) -> SelectFilters<'_>;
 
     fn select_cols(&self) -> &QueryCols<'a>;
 
+    /// Get the column and direction used to order rows with equal order by values. If None, no
+    /// secondary ordering is applied
+    fn tiebreak_order(&self) -> Option<(OrderBy, OrderDir)> {
+        None
+    }
+
     /// Appends selection filters to a query string
     fn append_filters(&self, mut query_string: String) -> String {
         let (condition, order_by, order_by_default, order_dir, limit, offset) =
@@ -121,21 +128,30 @@ trait SelectQuery<'a>: Query + fmt::Display {
         query_string.push_str(&format!(
             " ORDER BY {} {}",
             order_by,
-            match order_dir {
-                // Set order direction if provided, else use defaults
-                Some(dir) => dir,
-                None => match order_by {
-                    OrderBy::Priority => &OrderDir::Desc,
-                    _ => &OrderDir::Asc,
-                },
-            }
+            // Set order direction if provided, else use the column's default
+            order_dir.unwrap_or(order_by.default_dir())
         ));
 
+        // Break ties with secondary order column
+        if let Some((tiebreak_by, tiebreak_dir)) = self.tiebreak_order() {
+            if tiebreak_by != order_by {
+                query_string.push_str(&format!(", {tiebreak_by} {tiebreak_dir}"));
+            }
+        }
+
+        // Keep remaining ties in the order rows were added, as the order rows are scanned in
+        // depends on which index is used
+        if order_by != OrderBy::Id {
+            query_string.push_str(&format!(", {} {}", OrderBy::Id, OrderDir::Asc));
+        }
+
         //
         // Query Limit
         //
         match limit {
             Some(RowLimit::Limit(limit)) => query_string.push_str(&format!(" LIMIT {limit}")),
+            // An offset requires a limit, a negative limit selects all rows
+            Some(RowLimit::All) if offset.is_some() => query_string.push_str(" LIMIT -1"),
             Some(RowLimit::All) => {}
             None => query_string.push_str(" LIMIT 10"),
         }
@@ -143,14 +159,8 @@ trait SelectQuery<'a>: Query + fmt::Display {
         //
         // Query Offset
         //
-        if limit.is_none()
-            || limit
-                .as_ref()
-                .is_some_and(|limit| !matches!(limit, RowLimit::All))
-        {
-            if let Some(offset) = offset {
-                query_string.push_str(&format!(" OFFSET {offset}"))
-            }
+        if let Some(offset) = offset {
+            query_string.push_str(&format!(" OFFSET {offset}"))
         }
 
         query_string.push(';');
@@ -190,7 +200,7 @@ impl Query for AssignTaskQuery {
 }
 
 impl AddQuery for AssignTaskQuery {
-    fn key_value_pairs(&self) -> KeyValuePairs {
+    fn key_value_pairs(&self) -> KeyValuePairs<'_> {
         KeyValuePairs(vec![
             ("task_id", self.task_id.to_string()),
             ("project_id", self.project_id.to_string()),
@@ -253,6 +263,7 @@ impl fmt::Display for UnassignTaskQuery {
 //
 
 /// Columns to use in query
+#[derive(Clone)]
 pub enum QueryCols<'a> {
     /// All columns
     All,
@@ -260,6 +271,29 @@ pub enum QueryCols<'a> {
     Some(Vec<&'a str>),
 }
 
+impl QueryCols<'_> {
+    /// Checks that all columns are columns of a table
+    ///
+    /// # Errors
+    ///
+    /// Will return an error listing the columns of the table if any column is not a column of the
+    /// table
+    pub fn validate(&self, table: &Tables) -> Result<(), crate::Error> {
+        if let Self::Some(cols) = self {
+            let valid_cols = table.columns();
+            if let Some(col) = cols.iter().find(|col| !valid_cols.contains(col)) {
+                return Err(crate::ToadoError::Input(format!(
+                    "unknown column '{col}' for table '{table}', expected one of: {}",
+                    valid_cols.join(", ")
+                ))
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
 // Implements String conversion for QueryCols
 impl fmt::Display for QueryCols<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -318,7 +352,7 @@ where
     }
 
     /// Returns true if the UpdateAction value None
-    fn is_none(&self) -> bool {
+    pub fn is_none(&self) -> bool {
         matches!(&self, Self::None)
     }
     /// Create the sql update statment string for a given column.
@@ -376,14 +410,25 @@ where
 }
 
 /// Table column to order selection by
-#[derive(Clone, Copy, clap::ValueEnum)]
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum OrderBy {
     Id,
     Name,
     Priority,
-    // TODO: These options cause an sql error
-    // StartDate,
-    // EndDate,
+    StartTime,
+    EndTime,
+    CompletedAt,
+}
+
+impl OrderBy {
+    /// Order direction used for the column when none is given
+    pub fn default_dir(&self) -> OrderDir {
+        match self {
+            Self::Priority | Self::CompletedAt => OrderDir::Desc,
+            _ => OrderDir::Asc,
+        }
+    }
 }
 
 impl fmt::Display for OrderBy {
@@ -395,8 +440,9 @@ impl fmt::Display for OrderBy {
                 Self::Id => "id",
                 Self::Name => "name",
                 Self::Priority => "priority",
-                // Self::StartDate => "start_date",
-                // Self::EndDate => "end_date",
+                Self::StartTime => "start_time",
+                Self::EndTime => "end_time",
+                Self::CompletedAt => "completed_at",
             }
         )
     }
@@ -405,12 +451,23 @@ impl fmt::Display for OrderBy {
 /// Direction of selection order.
 /// Asc: smallest value to largest
 /// Desc: Largest value to smallest
-#[derive(Clone, Copy, clap::ValueEnum)]
+#[derive(Clone, Copy, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum OrderDir {
     Asc,
     Desc,
 }
 
+impl OrderDir {
+    /// Returns the opposite order direction
+    pub fn reverse(&self) -> Self {
+        match self {
+            Self::Asc => Self::Desc,
+            Self::Desc => Self::Asc,
+        }
+    }
+}
+
 impl fmt::Display for OrderDir {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -425,6 +482,7 @@ impl fmt::Display for OrderDir {
 }
 
 /// Defines the total number of rows to limit a query to
+#[derive(Clone, Copy)]
 pub enum RowLimit {
     /// A set number of rows
     Limit(usize),
@@ -432,6 +490,23 @@ pub enum RowLimit {
     All,
 }
 
+// Parses row limits of the form "all" or a number of rows (eg. "25")
+impl std::str::FromStr for RowLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+        if input.eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+
+        input
+            .parse::<usize>()
+            .map(Self::Limit)
+            .map_err(|_| format!("'{s}' is not a valid limit (eg. 10 or all)"))
+    }
+}
+
 pub struct KeyValuePairs<'a>(Vec<(&'a str, String)>);
 
 impl<'a> KeyValuePairs<'a> {
@@ -448,15 +523,54 @@ pub enum QueryConditions<'a, T>
 where
     T: fmt::Display,
 {
-    Equal { col: &'a str, value: T },
-    NotEqual { col: &'a str, value: T },
-    GreaterThan { col: &'a str, value: T },
-    LessThan { col: &'a str, value: T },
-    GreaterThanOrEqual { col: &'a str, value: T },
-    LessThanOrEqual { col: &'a str, value: T },
-    Between { col: &'a str, values: (T, T) },
-    Like { col: &'a str, value: T },
-    In { col: &'a str, values: Vec<T> },
+    Equal {
+        col: &'a str,
+        value: T,
+    },
+    NotEqual {
+        col: &'a str,
+        value: T,
+    },
+    GreaterThan {
+        col: &'a str,
+        value: T,
+    },
+    LessThan {
+        col: &'a str,
+        value: T,
+    },
+    GreaterThanOrEqual {
+        col: &'a str,
+        value: T,
+    },
+    LessThanOrEqual {
+        col: &'a str,
+        value: T,
+    },
+    Between {
+        col: &'a str,
+        values: (T, T),
+    },
+    Like {
+        col: &'a str,
+        value: T,
+    },
+    In {
+        col: &'a str,
+        values: Vec<T>,
+    },
+    IsNull {
+        col: &'a str,
+    },
+    IsNotNull {
+        col: &'a str,
+    },
+    /// All conditions are true
+    And(Vec<QueryConditions<'a, T>>),
+    /// Any condition is true
+    Or(Vec<QueryConditions<'a, T>>),
+    /// Condition is false
+    Not(Box<QueryConditions<'a, T>>),
 }
 
 // Implements String conversion for QueryConditions
@@ -487,12 +601,114 @@ where
                         .collect::<Vec<String>>()
                         .join(", ") // Convert vector of values into string of format "a, b, c"
                 ),
+                QueryConditions::IsNull { col } => format!("{col} IS NULL"),
+                QueryConditions::IsNotNull { col } => format!("{col} IS NOT NULL"),
+                QueryConditions::And(conditions) => join_conditions(conditions, "AND"),
+                QueryConditions::Or(conditions) => join_conditions(conditions, "OR"),
+                QueryConditions::Not(condition) => format!("NOT ({condition})"),
             }
         )
     }
 }
 
-/// Surronds input str with single quote
-fn quote_string(str: &str) -> String {
-    format!("'{str}'")
+/// Joins conditions into a single condition with a logical operator, surronding each condition
+/// with parentheses
+fn join_conditions<T>(conditions: &[QueryConditions<'_, T>], operator: &str) -> String
+where
+    T: fmt::Display,
+{
+    conditions
+        .iter()
+        .map(|condition| format!("({condition})"))
+        .collect::<Vec<String>>()
+        .join(&format!(" {operator} "))
+}
+
+/// Surronds input str with single quote, escaping any single quotes within it
+pub fn quote_string(str: &str) -> String {
+    format!("'{}'", str.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_string_escapes_quotes() {
+        assert_eq!(quote_string("lorem"), "'lorem'");
+        assert_eq!(quote_string("o'brien"), "'o''brien'");
+    }
+
+    #[test]
+    fn null_conditions_render() {
+        let is_null: QueryConditions<String> = QueryConditions::IsNull { col: "end_time" };
+        let is_not_null: QueryConditions<String> = QueryConditions::IsNotNull { col: "end_time" };
+
+        assert_eq!(is_null.to_string(), "end_time IS NULL");
+        assert_eq!(is_not_null.to_string(), "end_time IS NOT NULL");
+    }
+
+    #[test]
+    fn not_conditions_render() {
+        let not_archived = QueryConditions::Not(Box::new(QueryConditions::Equal {
+            col: "status",
+            value: 2,
+        }));
+        assert_eq!(not_archived.to_string(), "NOT (status = 2)");
+
+        let combined = QueryConditions::And(vec![
+            not_archived,
+            QueryConditions::Not(Box::new(QueryConditions::Or(vec![
+                QueryConditions::Equal {
+                    col: "priority",
+                    value: 0,
+                },
+                QueryConditions::Equal {
+                    col: "priority",
+                    value: 1,
+                },
+            ]))),
+        ]);
+        assert_eq!(
+            combined.to_string(),
+            "(NOT (status = 2)) AND (NOT ((priority = 0) OR (priority = 1)))"
+        );
+    }
+
+    #[test]
+    fn cols_are_validated_per_table() {
+        for (table, cols) in [
+            (Tables::Tasks, vec!["id", "name", "owner"]),
+            (Tables::Projects, vec!["id", "name", "status"]),
+            (Tables::TaskAssignments, vec!["task_id", "project_id"]),
+        ] {
+            assert!(QueryCols::Some(cols).validate(&table).is_ok());
+            assert!(QueryCols::All.validate(&table).is_ok());
+        }
+
+        let err = QueryCols::Some(vec!["id", "nmae"])
+            .validate(&Tables::Tasks)
+            .expect_err("unknown column should fail");
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(crate::ToadoError::Input(_))
+        ));
+        assert!(err.to_string().contains("'nmae'"));
+        assert!(err
+            .to_string()
+            .contains(&Tables::Tasks.columns().join(", ")));
+
+        assert!(QueryCols::Some(vec!["owner"])
+            .validate(&Tables::Projects)
+            .is_err());
+    }
+
+    #[test]
+    fn row_limits_parse_from_strings() {
+        assert!(matches!("all".parse(), Ok(RowLimit::All)));
+        assert!(matches!(" ALL ".parse(), Ok(RowLimit::All)));
+        assert!(matches!("25".parse(), Ok(RowLimit::Limit(25))));
+        assert!("-1".parse::<RowLimit>().is_err());
+        assert!("some".parse::<RowLimit>().is_err());
+    }
 }
@@ -45,7 +45,7 @@ impl fmt::Display for AddProjectQuery {
 }
 
 impl AddQuery for AddProjectQuery {
-    fn key_value_pairs(&self) -> KeyValuePairs {
+    fn key_value_pairs(&self) -> KeyValuePairs<'_> {
         // Create pairs vector with name key value pair
         let mut pairs = KeyValuePairs(vec![("name", self.name.clone())]);
 
@@ -53,6 +53,7 @@ impl AddQuery for AddProjectQuery {
         pairs.push_pairs_if_some("start_time", self.start_time.clone());
         pairs.push_pairs_if_some("end_time", self.end_time.clone());
         pairs.push_pairs_if_some("notes", self.notes.clone());
+        pairs.push_pairs_if_some("created_at", Some(crate::time::now()));
 
         pairs
     }
@@ -65,6 +66,7 @@ impl AddQuery for AddProjectQuery {
 pub struct UpdateProjectQuery {
     condition: Option<String>,
     name: UpdateAction<String>,
+    status: UpdateAction<crate::ItemStatus>,
     start_time: UpdateAction<String>,
     end_time: UpdateAction<String>,
     notes: UpdateAction<String>,
@@ -74,6 +76,7 @@ impl UpdateProjectQuery {
     pub fn new(
         condition: Option<String>,
         name: UpdateAction<String>,
+        status: UpdateAction<crate::ItemStatus>,
         start_time: UpdateAction<String>,
         end_time: UpdateAction<String>,
         notes: UpdateAction<String>,
@@ -81,6 +84,7 @@ impl UpdateProjectQuery {
         Self {
             condition,
             name,
+            status,
             start_time,
             end_time,
             notes,
@@ -101,9 +105,10 @@ impl UpdateQuery for UpdateProjectQuery {
         self.condition.as_deref()
     }
 
-    fn update_cols(&self) -> UpdateCols<Self::Action> {
+    fn update_cols(&self) -> UpdateCols<'_, Self::Action> {
         UpdateCols(vec![
             ("name", self.name.clone()),
+            ("status", self.status.map(|v| u32::from(v).to_string())),
             ("start_time", self.start_time.clone()),
             ("end_time", self.end_time.clone()),
             ("notes", self.notes.clone()),
@@ -189,7 +194,7 @@ impl Query for SelectProjectsQuery<'_> {
 }
 
 impl<'a> SelectQuery<'a> for SelectProjectsQuery<'a> {
-    fn query_filters(&self) -> SelectFilters {
+    fn query_filters(&self) -> SelectFilters<'_> {
         (
             &self.condition,
             &self.order_by,
@@ -2,9 +2,9 @@ use super::*;
 use crate::Tables;
 use std::fmt;
 
-///
-/// Add query
-///
+//
+// Add query
+//
 
 /// Database query for adding a new task
 pub struct AddTaskQuery {
@@ -14,24 +14,21 @@ pub struct AddTaskQuery {
     end_time: Option<String>,
     repeat: Option<String>,
     notes: Option<String>,
+    parent_id: Option<i64>,
+    owner: Option<String>,
 }
 
-impl AddTaskQuery {
-    pub fn new(
-        name: String,
-        priority: u64,
-        start_time: Option<String>,
-        end_time: Option<String>,
-        repeat: Option<String>,
-        notes: Option<String>,
-    ) -> Self {
+impl From<crate::AddTaskArgs> for AddTaskQuery {
+    fn from(args: crate::AddTaskArgs) -> Self {
         Self {
-            name,
-            priority,
-            start_time,
-            end_time,
-            repeat,
-            notes,
+            name: args.name,
+            priority: args.priority,
+            start_time: args.start_time,
+            end_time: args.end_time,
+            repeat: args.repeat,
+            notes: args.notes,
+            parent_id: args.parent_id,
+            owner: args.owner,
         }
     }
 }
@@ -43,7 +40,7 @@ impl Query for AddTaskQuery {
 }
 
 impl AddQuery for AddTaskQuery {
-    fn key_value_pairs(&self) -> KeyValuePairs {
+    fn key_value_pairs(&self) -> KeyValuePairs<'_> {
         let mut pairs = KeyValuePairs(vec![
             ("name", self.name.clone()),
             ("priority", self.priority.to_string()),
@@ -57,6 +54,9 @@ impl AddQuery for AddTaskQuery {
         pairs.push_pairs_if_some("end_time", self.end_time.clone());
         pairs.push_pairs_if_some("repeat", self.repeat.clone());
         pairs.push_pairs_if_some("notes", self.notes.clone());
+        pairs.push_pairs_if_some("parent_id", self.parent_id.map(|id| id.to_string()));
+        pairs.push_pairs_if_some("created_at", Some(crate::time::now()));
+        pairs.push_pairs_if_some("owner", self.owner.clone());
 
         pairs
     }
@@ -68,9 +68,9 @@ impl fmt::Display for AddTaskQuery {
     }
 }
 
-///
-/// Update Query
-///
+//
+// Update Query
+//
 
 /// Database query struct for task update queries
 pub struct UpdateTaskQuery {
@@ -82,6 +82,7 @@ pub struct UpdateTaskQuery {
     pub end_time: UpdateAction<String>,
     pub repeat: UpdateAction<String>,
     pub notes: UpdateAction<String>,
+    pub updated_at: UpdateAction<String>,
 }
 
 impl Query for UpdateTaskQuery {
@@ -97,7 +98,7 @@ impl UpdateQuery for UpdateTaskQuery {
         self.condition.as_deref()
     }
 
-    fn update_cols(&self) -> UpdateCols<Self::Action> {
+    fn update_cols(&self) -> UpdateCols<'_, Self::Action> {
         UpdateCols(vec![
             ("name", self.name.clone()),
             ("priority", self.priority.map(|v| v.to_string())),
@@ -106,6 +107,7 @@ impl UpdateQuery for UpdateTaskQuery {
             ("end_time", self.end_time.clone()),
             ("repeat", self.repeat.clone()),
             ("notes", self.notes.clone()),
+            ("updated_at", self.updated_at.clone()),
         ])
     }
 }
@@ -279,7 +281,7 @@ impl Query for SelectTasksQuery<'_> {
 }
 
 impl<'a> SelectQuery<'a> for SelectTasksQuery<'a> {
-    fn query_filters(&self) -> SelectFilters {
+    fn query_filters(&self) -> SelectFilters<'_> {
         (
             &self.condition,
             &self.order_by,
@@ -293,6 +295,10 @@ impl<'a> SelectQuery<'a> for SelectTasksQuery<'a> {
     fn select_cols(&self) -> &QueryCols<'a> {
         &self.cols
     }
+
+    fn tiebreak_order(&self) -> Option<(OrderBy, OrderDir)> {
+        Some((OrderBy::Priority, OrderDir::Desc))
+    }
 }
 
 impl fmt::Display for SelectTasksQuery<'_> {
@@ -0,0 +1,181 @@
+//! Task recurrence utilities
+
+use chrono::{Days, Months, NaiveDateTime};
+use std::{fmt, str::FromStr};
+
+use crate::time;
+
+/// Interval at which a task repeats
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Recurrence {
+    /// Repeats every n days
+    Days(u32),
+    /// Repeats every n weeks
+    Weeks(u32),
+    /// Repeats every n months
+    Months(u32),
+    /// Repeats every n years
+    Years(u32),
+}
+
+impl Recurrence {
+    /// Advances a date time by the recurrence interval. Returns None if the result is out of range
+    pub fn advance(&self, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self {
+            Self::Days(n) => time.checked_add_days(Days::new(u64::from(*n))),
+            Self::Weeks(n) => time.checked_add_days(Days::new(u64::from(*n) * 7)),
+            Self::Months(n) => time.checked_add_months(Months::new(*n)),
+            Self::Years(n) => time.checked_add_months(Months::new(n.checked_mul(12)?)),
+        }
+    }
+
+    /// Advances an ISO 8601 time string by the recurrence interval, keeping date only strings as
+    /// dates. Returns None if the time string is not a valid time
+    pub fn advance_time(&self, input: &str) -> Option<String> {
+        let advanced = self.advance(time::parse_time(input)?)?;
+        Some(time::format_time(&advanced, time::is_date(input)))
+    }
+
+    /// Returns the next count occurrences of an ISO 8601 time string, in order, keeping date only
+    /// strings as dates. Occurrences out of range are omitted. Returns None if the time string is
+    /// not a valid time
+    pub fn next_occurrences(&self, input: &str, count: usize) -> Option<Vec<String>> {
+        let date_only = time::is_date(input);
+        let mut time = time::parse_time(input)?;
+        let mut occurrences = Vec::new();
+
+        for _ in 0..count {
+            time = match self.advance(time) {
+                Some(time) => time,
+                None => break,
+            };
+            occurrences.push(time::format_time(&time, date_only));
+        }
+
+        Some(occurrences)
+    }
+}
+
+// Implements canonical String conversion for Recurrence
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Days(1) => write!(f, "daily"),
+            Self::Weeks(1) => write!(f, "weekly"),
+            Self::Months(1) => write!(f, "monthly"),
+            Self::Years(1) => write!(f, "yearly"),
+            Self::Days(n) => write!(f, "every {n} days"),
+            Self::Weeks(n) => write!(f, "every {n} weeks"),
+            Self::Months(n) => write!(f, "every {n} months"),
+            Self::Years(n) => write!(f, "every {n} years"),
+        }
+    }
+}
+
+// Parses recurrences of the form "daily", "weekly", "every week", "every 3 days", etc.
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_lowercase();
+        let invalid = || {
+            format!("'{s}' is not a valid recurrence (eg. daily, weekly, every 3 days, monthly)")
+        };
+
+        match input.as_str() {
+            "daily" => return Ok(Self::Days(1)),
+            "weekly" => return Ok(Self::Weeks(1)),
+            "monthly" => return Ok(Self::Months(1)),
+            "yearly" | "annually" => return Ok(Self::Years(1)),
+            _ => {}
+        }
+
+        let mut words = input.split_whitespace();
+        if words.next() != Some("every") {
+            return Err(invalid());
+        }
+
+        // Get interval count and unit, with the count defaulting to 1 (ie. "every week")
+        let (count, unit) = match (words.next(), words.next(), words.next()) {
+            (Some(count), Some(unit), None) => match count.parse::<u32>() {
+                Ok(count) if count > 0 => (count, unit),
+                _ => return Err(invalid()),
+            },
+            (Some(unit), None, None) => (1, unit),
+            _ => return Err(invalid()),
+        };
+
+        match unit.trim_end_matches('s') {
+            "day" => Ok(Self::Days(count)),
+            "week" => Ok(Self::Weeks(count)),
+            "month" => Ok(Self::Months(count)),
+            "year" => Ok(Self::Years(count)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recurrences_parse_into_canonical_strings() {
+        for (input, canonical) in [
+            ("daily", "daily"),
+            ("Every 1 Week", "weekly"),
+            ("every week", "weekly"),
+            ("every 3 days", "every 3 days"),
+            ("every 2 months", "every 2 months"),
+            ("annually", "yearly"),
+        ] {
+            assert_eq!(input.parse::<Recurrence>().unwrap().to_string(), canonical);
+        }
+
+        for input in [
+            "",
+            "sometimes",
+            "every 0 days",
+            "every -1 days",
+            "every 3 fortnights",
+        ] {
+            assert!(
+                input.parse::<Recurrence>().is_err(),
+                "'{input}' should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn advance_time_crosses_month_boundaries() {
+        assert_eq!(
+            Recurrence::Days(1).advance_time("2024-01-31"),
+            Some("2024-02-01".to_string())
+        );
+        assert_eq!(
+            Recurrence::Weeks(1).advance_time("2024-02-26T09:00:00"),
+            Some("2024-03-04T09:00:00".to_string())
+        );
+        assert_eq!(
+            Recurrence::Months(1).advance_time("2024-01-31"),
+            Some("2024-02-29".to_string())
+        );
+    }
+
+    #[test]
+    fn next_occurrences_keeps_input_format() {
+        assert_eq!(
+            Recurrence::Weeks(1).next_occurrences("2024-01-01", 2),
+            Some(vec!["2024-01-08".to_string(), "2024-01-15".to_string()])
+        );
+        assert_eq!(Recurrence::Days(1).next_occurrences("not a time", 2), None);
+    }
+
+    #[test]
+    fn next_occurrences_stops_out_of_range() {
+        let occurrences = Recurrence::Years(100_000)
+            .next_occurrences("2024-01-01", usize::MAX)
+            .unwrap();
+        assert!(occurrences.len() < 10);
+    }
+}
@@ -0,0 +1,155 @@
+//! Item time utilities
+
+use chrono::{DateTime, Days, Months, NaiveDate, NaiveDateTime};
+
+/// Formats accepted for ISO 8601 date time strings without a UTC offset
+const DATE_TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Format accepted for ISO 8601 date strings
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Format used when writing ISO 8601 date time strings
+const DATE_TIME_OUTPUT_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Parses an ISO 8601 / RFC 3339 time string into a date time. Date only strings are parsed as
+/// the start of the day. Returns None if the string is not a valid time
+pub fn parse_time(input: &str) -> Option<NaiveDateTime> {
+    let input = input.trim();
+
+    // RFC 3339 times with a UTC offset
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Some(time.naive_local());
+    }
+
+    // ISO 8601 date times without an offset
+    if let Some(time) = DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+    {
+        return Some(time);
+    }
+
+    // ISO 8601 dates
+    NaiveDate::parse_from_str(input, DATE_FORMAT)
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+}
+
+/// Resolves a time string to an ISO 8601 time string. ISO 8601 times are returned unchanged, and
+/// relative expressions are resolved relative to now: "now", "today", "tomorrow", "yesterday",
+/// "next week", "next month", "next year", or an offset from today in days, weeks, months, or
+/// years (eg. "+3d", "-2w", "+1m", "+1y"). Returns None if the string is not a valid time
+pub fn resolve_time(input: &str, now: &NaiveDateTime) -> Option<String> {
+    let input = input.trim();
+    if parse_time(input).is_some() {
+        return Some(input.to_string());
+    }
+
+    let today = now.date();
+    let input = input.to_lowercase();
+    let date = match input.as_str() {
+        "now" => return Some(format_time(now, false)),
+        "today" => Some(today),
+        "tomorrow" => today.checked_add_days(Days::new(1)),
+        "yesterday" => today.checked_sub_days(Days::new(1)),
+        "next week" => today.checked_add_days(Days::new(7)),
+        "next month" => today.checked_add_months(Months::new(1)),
+        "next year" => today.checked_add_months(Months::new(12)),
+        _ => offset_date(&input, today),
+    }?;
+
+    Some(date.format(DATE_FORMAT).to_string())
+}
+
+/// Offsets a date by an expression of the form "+3d" or "-2w", with units of days (d), weeks (w),
+/// months (m), or years (y). Returns None if the expression is invalid or the result is out of
+/// range
+fn offset_date(input: &str, date: NaiveDate) -> Option<NaiveDate> {
+    let (sign, offset) = input.split_at_checked(1)?;
+    let (count, unit) = offset.split_at_checked(offset.len().checked_sub(1)?)?;
+    let count: u32 = count.parse().ok()?;
+
+    let (days, months) = match unit {
+        "d" => (count, 0),
+        "w" => (count.checked_mul(7)?, 0),
+        "m" => (0, count),
+        "y" => (0, count.checked_mul(12)?),
+        _ => return None,
+    };
+
+    match sign {
+        "+" => date
+            .checked_add_days(Days::new(u64::from(days)))?
+            .checked_add_months(Months::new(months)),
+        "-" => date
+            .checked_sub_days(Days::new(u64::from(days)))?
+            .checked_sub_months(Months::new(months)),
+        _ => None,
+    }
+}
+
+/// Returns true if an ISO 8601 time string is a date without a time of day
+pub fn is_date(input: &str) -> bool {
+    NaiveDate::parse_from_str(input.trim(), DATE_FORMAT).is_ok()
+}
+
+/// Returns the current local time as an ISO 8601 date time string
+pub fn now() -> String {
+    format_time(&chrono::Local::now().naive_local(), false)
+}
+
+/// Returns the current local date as an ISO 8601 date string
+pub fn today() -> String {
+    chrono::Local::now()
+        .date_naive()
+        .format(DATE_FORMAT)
+        .to_string()
+}
+
+/// Formats a date time as an ISO 8601 string. If date_only is true, the time of day is omitted
+pub fn format_time(time: &NaiveDateTime, date_only: bool) -> String {
+    time.format(if date_only {
+        DATE_FORMAT
+    } else {
+        DATE_TIME_OUTPUT_FORMAT
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_times_resolve_from_now() {
+        let now = NaiveDate::from_ymd_opt(2024, 12, 30)
+            .and_then(|date| date.and_hms_opt(17, 30, 0))
+            .unwrap();
+
+        for (input, resolved) in [
+            ("tomorrow", "2024-12-31"),
+            ("+7d", "2025-01-06"),
+            ("Today", "2024-12-30"),
+            ("next week", "2025-01-06"),
+            ("-2w", "2024-12-16"),
+            ("+1m", "2025-01-30"),
+            ("now", "2024-12-30T17:30:00"),
+            ("2024-01-01T09:00", "2024-01-01T09:00"),
+        ] {
+            assert_eq!(
+                resolve_time(input, &now).as_deref(),
+                Some(resolved),
+                "{input}"
+            );
+        }
+
+        for input in ["", "later", "+d", "3d", "+3x", "+99999999y"] {
+            assert_eq!(resolve_time(input, &now), None, "{input}");
+        }
+    }
+}